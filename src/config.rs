@@ -37,10 +37,36 @@ pub fn get_renames_file() -> PathBuf {
     get_config_folder().join("renames.json")
 }
 
+pub fn get_user_renames_file() -> PathBuf {
+    get_config_folder().join("user_renames.json")
+}
+
+pub fn get_pending_collections_file() -> PathBuf {
+    get_config_folder().join("pending_collections.json")
+}
+
+pub fn get_shortcut_flags_file() -> PathBuf {
+    get_config_folder().join("shortcut_flags.json")
+}
+
+pub fn get_controller_templates_file() -> PathBuf {
+    get_config_folder().join("controller_templates.json")
+}
+
+pub fn get_compat_tools_file() -> PathBuf {
+    get_config_folder().join("compat_tools.json")
+}
+
 pub fn get_cache_file() -> PathBuf {
     get_config_folder().join("cache.json")
 }
 
+pub fn get_platform_cache_folder() -> PathBuf {
+    let cache_path = get_config_folder().join("platform_cache");
+    let _ = create_dir_all(&cache_path);
+    cache_path
+}
+
 pub fn get_backups_folder() -> PathBuf {
     let backups_path = get_config_folder().join("backup");
     let _ = create_dir_all(&backups_path);
@@ -0,0 +1,264 @@
+use std::path::{Path, PathBuf};
+
+use crate::platforms::{get_platforms, GamesPlatform, HostCommand};
+use crate::settings::Settings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Info,
+    Warning,
+    Error,
+}
+
+/// What was looked at to come to the result of a check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckDetail {
+    Path {
+        path: PathBuf,
+        found: bool,
+    },
+    Command {
+        command: String,
+        exit_code: Option<i32>,
+        output: String,
+    },
+    Version(String),
+    Message(String),
+}
+
+/// One thing that was checked while looking for the reason a platform (or Steam) is not working
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticCheck {
+    pub status: CheckStatus,
+    pub description: String,
+    pub detail: CheckDetail,
+}
+
+impl DiagnosticCheck {
+    pub fn new<S: Into<String>>(status: CheckStatus, description: S, detail: CheckDetail) -> Self {
+        Self {
+            status,
+            description: description.into(),
+            detail,
+        }
+    }
+
+    pub fn message<S: Into<String>, M: Into<String>>(
+        status: CheckStatus,
+        description: S,
+        message: M,
+    ) -> Self {
+        Self::new(status, description, CheckDetail::Message(message.into()))
+    }
+
+    /// Checks if a path exists, a missing path is only an error if it is required
+    pub fn path<S: Into<String>, P: AsRef<Path>>(description: S, path: P, required: bool) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let found = path.exists();
+        let status = match (found, required) {
+            (true, _) => CheckStatus::Ok,
+            (false, true) => CheckStatus::Error,
+            (false, false) => CheckStatus::Info,
+        };
+        Self::new(status, description, CheckDetail::Path { path, found })
+    }
+
+    /// Checks a path a platform looked for, it is an error if the platform could not tell where to look
+    pub fn located<S: Into<String>>(description: S, located: eyre::Result<PathBuf>) -> Self {
+        match located {
+            Ok(path) => Self::path(description, path, true),
+            Err(err) => Self::message(CheckStatus::Error, description, format!("{err}")),
+        }
+    }
+
+    /// Runs a command on the host and records its exit code and the first line it printed
+    pub fn command<S: Into<String>>(description: S, program: &str, args: &[&str]) -> Self {
+        let command = HostCommand::new(program).args(args.iter().copied());
+        let (status, exit_code, output) = match command.output() {
+            Ok(output) => {
+                let first_line = |text: &str| {
+                    text.lines()
+                        .map(str::trim)
+                        .find(|line| !line.is_empty())
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                if output.success() {
+                    (
+                        CheckStatus::Ok,
+                        output.exit_code,
+                        first_line(&output.stdout),
+                    )
+                } else {
+                    (
+                        CheckStatus::Error,
+                        output.exit_code,
+                        first_line(&output.stderr),
+                    )
+                }
+            }
+            Err(err) => (CheckStatus::Error, None, format!("{err}")),
+        };
+        Self::new(
+            status,
+            description,
+            CheckDetail::Command {
+                command: command.to_string(),
+                exit_code,
+                output,
+            },
+        )
+    }
+
+    /// Like [DiagnosticCheck::command], but what the command prints is reported as a version
+    pub fn version<S: Into<String>>(description: S, program: &str, args: &[&str]) -> Self {
+        let check = Self::command(description, program, args);
+        match check.detail {
+            CheckDetail::Command { output, .. } if check.status == CheckStatus::Ok => Self::new(
+                CheckStatus::Ok,
+                check.description,
+                CheckDetail::Version(output),
+            ),
+            _ => check,
+        }
+    }
+
+    pub fn detail_text(&self) -> String {
+        match &self.detail {
+            CheckDetail::Path { path, found: true } => format!("Found {}", path.to_string_lossy()),
+            CheckDetail::Path { path, found: false } => {
+                format!("Not found {}", path.to_string_lossy())
+            }
+            CheckDetail::Command {
+                command,
+                exit_code,
+                output,
+            } => match exit_code {
+                Some(exit_code) if output.is_empty() => {
+                    format!("\"{command}\" exited with code {exit_code}")
+                }
+                Some(exit_code) => format!("\"{command}\" exited with code {exit_code}: {output}"),
+                None => format!("\"{command}\": {output}"),
+            },
+            CheckDetail::Version(version) => format!("Version {version}"),
+            CheckDetail::Message(message) => message.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for DiagnosticCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self.status {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Info => "info",
+            CheckStatus::Warning => "warning",
+            CheckStatus::Error => "error",
+        };
+        write!(f, "[{status}] {}: {}", self.description, self.detail_text())
+    }
+}
+
+/// Checks that are the same for all platforms
+fn environment_checks() -> Vec<DiagnosticCheck> {
+    let mut checks = vec![];
+    if Path::new("/.flatpak-info").exists() {
+        let check = if cfg!(feature = "flatpak") {
+            DiagnosticCheck::message(
+                CheckStatus::Info,
+                "Flatpak sandbox",
+                "BoilR runs inside the flatpak sandbox, commands are run on the host with flatpak-spawn",
+            )
+        } else {
+            DiagnosticCheck::message(
+                CheckStatus::Warning,
+                "Flatpak sandbox",
+                "This build of BoilR is not made for flatpak, it can not run commands on the host",
+            )
+        };
+        checks.push(check);
+    }
+    checks
+}
+
+/// The platform's own checks, followed by the result of looking for its games
+pub fn diagnose_platform(platform: &dyn GamesPlatform) -> Vec<DiagnosticCheck> {
+    let mut checks = vec![];
+    if !platform.enabled() {
+        checks.push(DiagnosticCheck::message(
+            CheckStatus::Info,
+            "Enabled",
+            "The platform is not enabled in the settings",
+        ));
+    }
+    checks.extend(platform.diagnose());
+    let result = match platform.get_shortcut_info() {
+        Ok(shortcuts) if shortcuts.is_empty() => {
+            DiagnosticCheck::message(CheckStatus::Warning, "Games", "Did not find any games")
+        }
+        Ok(shortcuts) => DiagnosticCheck::message(
+            CheckStatus::Ok,
+            "Games",
+            format!("Found {} games", shortcuts.len()),
+        ),
+        Err(err) => DiagnosticCheck::message(CheckStatus::Error, "Games", format!("{err}")),
+    };
+    checks.push(result);
+    checks
+}
+
+pub fn diagnose_steam(settings: &Settings) -> Vec<DiagnosticCheck> {
+    let mut checks = environment_checks();
+    checks.extend(crate::steam::diagnose_steam(&settings.steam));
+    checks
+}
+
+/// Runs all the checks and prints them, this is what "boilr doctor" does
+pub fn run_doctor() -> eyre::Result<()> {
+    let settings = Settings::new()?;
+    println!("Steam");
+    for check in diagnose_steam(&settings) {
+        println!("  {check}");
+    }
+    for platform in get_platforms() {
+        println!("{}", platform.name());
+        for check in diagnose_platform(platform.as_ref()) {
+            println!("  {check}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_path_is_only_an_error_when_required() {
+        let path = Path::new("src/testdata/does-not-exist");
+        assert_eq!(
+            CheckStatus::Error,
+            DiagnosticCheck::path("db", path, true).status
+        );
+        assert_eq!(
+            CheckStatus::Info,
+            DiagnosticCheck::path("db", path, false).status
+        );
+        let found = DiagnosticCheck::path("db", "src/testdata/itch", true);
+        assert_eq!(CheckStatus::Ok, found.status);
+        assert!(found.to_string().starts_with("[ok] db: Found "));
+    }
+
+    #[test]
+    fn missing_command_is_an_error() {
+        let check = DiagnosticCheck::version("Tool", "boilr-command-that-does-not-exist", &[]);
+        assert_eq!(CheckStatus::Error, check.status);
+        assert!(matches!(
+            check.detail,
+            CheckDetail::Command {
+                exit_code: None,
+                ..
+            }
+        ));
+    }
+}
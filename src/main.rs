@@ -7,6 +7,7 @@
 #![deny(clippy::todo)]
 
 mod config;
+mod diagnostics;
 mod migration;
 mod platforms;
 mod settings;
@@ -22,6 +23,12 @@ fn main() -> Result<()> {
     color_eyre::install()?;
     ensure_config_folder();
 
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("doctor") {
+        //Only reads files and runs commands, so it can run next to another instance
+        return diagnostics::run_doctor();
+    }
+
     // Acquire single instance lock
     let _instance_lock = match single_instance::InstanceLock::acquire() {
         Ok(lock) => lock,
@@ -34,7 +41,6 @@ fn main() -> Result<()> {
 
     migration::migrate_config();
 
-    let args: Vec<String> = std::env::args().collect();
     if args.contains(&"--no-ui".to_string()) {
         ui::run_sync()?;
     } else {
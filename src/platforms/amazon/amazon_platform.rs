@@ -3,6 +3,7 @@ use sqlite::State;
 use std::path::{Path, PathBuf};
 use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
 
+use crate::diagnostics::DiagnosticCheck;
 use crate::platforms::{
     load_settings, to_shortcuts_simple, FromSettingsString, GamesPlatform, ShortcutToImport,
 };
@@ -79,6 +80,13 @@ impl GamesPlatform for AmazonPlatform {
     fn code_name(&self) -> &str {
         "amazon"
     }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        vec![
+            DiagnosticCheck::located("Amazon Games launcher", get_launcher_path()),
+            DiagnosticCheck::located("Amazon Games database", get_sqlite_path()),
+        ]
+    }
 }
 
 impl AmazonPlatform {
@@ -0,0 +1,6 @@
+mod platform;
+mod product_db;
+mod products;
+mod settings;
+
+pub use platform::BattleNetPlatform;
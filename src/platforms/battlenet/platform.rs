@@ -0,0 +1,327 @@
+use std::path::{Path, PathBuf};
+
+use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
+
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+use crate::platforms::{
+    executable_needs_proton, load_settings, render_extra_prefixes, to_shortcuts, wine_prefix,
+    wine_prefix_candidates, FromSettingsString, GameMetadata, GamesPlatform, NeedsProton,
+    ShortcutToImport,
+};
+
+use super::{
+    product_db::parse_product_db,
+    products::{find_known_product, NOT_GAMES},
+    settings::BattleNetSettings,
+};
+
+#[derive(Clone)]
+pub struct BattleNetPlatform {
+    pub settings: BattleNetSettings,
+    locations: Option<Vec<BattleNetPaths>>,
+}
+
+#[derive(Clone, Debug)]
+struct BattleNetPaths {
+    //~/.steam/steam/steamapps/compatdata/X/pfx/drive_c/Program Files (x86)/Battle.net/Battle.net.exe
+    launcher_path: PathBuf,
+    //~/.steam/steam/steamapps/compatdata/X/pfx/drive_c/ProgramData/Battle.net/Agent/product.db
+    product_db_path: PathBuf,
+    //~/.steam/steam/steamapps/compatdata/X
+    compat_folder: PathBuf,
+}
+
+#[derive(Clone, Debug)]
+pub struct BattleNetGame {
+    pub title: String,
+    pub launch_code: String,
+    pub launcher_path: PathBuf,
+    pub compat_folder: PathBuf,
+}
+
+impl From<BattleNetGame> for ShortcutOwned {
+    fn from(game: BattleNetGame) -> Self {
+        let launch = format!(
+            "STEAM_COMPAT_DATA_PATH=\"{}\" %command% --exec=\"launch {}\"",
+            game.compat_folder.to_string_lossy(),
+            game.launch_code
+        );
+        let exe = format!("\"{}\"", game.launcher_path.to_string_lossy());
+        let start_dir = game
+            .launcher_path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .to_string_lossy()
+            .to_string();
+        Shortcut::new("0", &game.title, &exe, &start_dir, "", "", &launch).to_owned()
+    }
+}
+
+impl NeedsProton<BattleNetPlatform> for BattleNetGame {
+    fn needs_proton(&self, _platform: &BattleNetPlatform) -> bool {
+        executable_needs_proton(self.launcher_path.to_string_lossy()).unwrap_or(true)
+    }
+
+    fn create_symlinks(&self, _platform: &BattleNetPlatform) -> bool {
+        false
+    }
+
+    fn metadata(&self, _platform: &BattleNetPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            game_id: Some(self.launch_code.clone()),
+            store: Some("bnet".to_string()),
+            native: Some(false),
+            developer: Some("Blizzard Entertainment".to_string()),
+            ..Default::default()
+        })
+    }
+}
+
+impl BattleNetPlatform {
+    fn get_battle_net_games(&self) -> eyre::Result<Vec<BattleNetGame>> {
+        let locations = get_locations(&self.settings.prefixes);
+        if locations.is_empty() {
+            return Err(eyre::format_err!("Could not find Battle.net in any prefix"));
+        }
+        let mut games: Vec<BattleNetGame> = vec![];
+        for location in locations {
+            //A broken product.db in one prefix must not hide the games of the other prefixes
+            let products = std::fs::read(&location.product_db_path)
+                .map_err(eyre::Report::from)
+                .and_then(|content| parse_product_db(&content));
+            let products = match products {
+                Ok(products) => products,
+                Err(err) => {
+                    eprintln!(
+                        "Could not read the Battle.net games in {:?}: {err}",
+                        location.product_db_path
+                    );
+                    continue;
+                }
+            };
+            for product in products {
+                if NOT_GAMES.contains(&product.product_code.as_str())
+                    || product.install_path.is_empty()
+                    || !product.installed
+                {
+                    continue;
+                }
+                let known_product = find_known_product(&product.product_code);
+                let title = match known_product {
+                    Some(known) => known.name.to_string(),
+                    None => install_folder_name(&product.install_path)
+                        .unwrap_or_else(|| product.product_code.clone()),
+                };
+                let launch_code = known_product
+                    .map(|known| known.launch_code.to_string())
+                    .unwrap_or_else(|| product.product_code.clone());
+                if games.iter().any(|game| game.launch_code == launch_code) {
+                    continue;
+                }
+                games.push(BattleNetGame {
+                    title,
+                    launch_code,
+                    launcher_path: location.launcher_path.clone(),
+                    compat_folder: location.compat_folder.clone(),
+                });
+            }
+        }
+        Ok(games)
+    }
+}
+
+fn install_folder_name(install_path: &str) -> Option<String> {
+    install_path
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty() && !name.ends_with(':'))
+        .map(|name| name.to_string())
+}
+
+fn get_locations(configured_prefixes: &[String]) -> Vec<BattleNetPaths> {
+    let mut compat_folders: Vec<PathBuf> = configured_prefixes
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| Path::new(p).to_path_buf())
+        .collect();
+    compat_folders.extend(wine_prefix_candidates());
+
+    let mut locations: Vec<BattleNetPaths> = vec![];
+    for compat_folder in compat_folders {
+        if let Some(location) = get_location_in_prefix(compat_folder) {
+            let already_found = locations.iter().any(|l| {
+                l.product_db_path.canonicalize().ok()
+                    == location.product_db_path.canonicalize().ok()
+            });
+            if !already_found {
+                locations.push(location);
+            }
+        }
+    }
+    locations
+}
+
+fn get_location_in_prefix(compat_folder: PathBuf) -> Option<BattleNetPaths> {
+    let drive_c = wine_prefix(&compat_folder).join("drive_c");
+    let launcher_path = drive_c
+        .join("Program Files (x86)")
+        .join("Battle.net")
+        .join("Battle.net.exe");
+    let product_db_path = drive_c
+        .join("ProgramData")
+        .join("Battle.net")
+        .join("Agent")
+        .join("product.db");
+    if launcher_path.exists() && product_db_path.exists() {
+        Some(BattleNetPaths {
+            launcher_path,
+            product_db_path,
+            compat_folder,
+        })
+    } else {
+        None
+    }
+}
+
+impl FromSettingsString for BattleNetPlatform {
+    fn from_settings_string<S: AsRef<str>>(s: S) -> Self {
+        BattleNetPlatform {
+            settings: load_settings(s),
+            locations: None,
+        }
+    }
+}
+
+impl GamesPlatform for BattleNetPlatform {
+    fn name(&self) -> &str {
+        "Battle.net"
+    }
+
+    fn enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    fn get_shortcut_info(&self) -> eyre::Result<Vec<ShortcutToImport>> {
+        to_shortcuts(self, self.get_battle_net_games())
+    }
+
+    fn render_ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Battle.net");
+        ui.checkbox(&mut self.settings.enabled, "Import from Battle.net");
+        if self.settings.enabled {
+            let settings = &mut self.settings;
+            let locations = self
+                .locations
+                .get_or_insert_with(|| get_locations(&settings.prefixes));
+            let locations_header = match locations.len() {
+                0 => "Battle.net not found".to_string(),
+                1 => "Found Battle.net in one prefix".to_string(),
+                x => format!("Found Battle.net in {x} prefixes"),
+            };
+            let mut prefixes_changed = false;
+            egui::CollapsingHeader::new(locations_header)
+                .id_salt("Battle_net_locations")
+                .show(ui, |ui| {
+                    for location in locations.iter() {
+                        ui.label(location.compat_folder.to_string_lossy())
+                            .on_hover_text(location.launcher_path.to_string_lossy());
+                    }
+                    prefixes_changed = render_extra_prefixes(&mut settings.prefixes, ui);
+                });
+            if prefixes_changed {
+                self.locations = None;
+            }
+        }
+    }
+
+    fn get_settings_serializable(&self) -> String {
+        toml::to_string(&self.settings).unwrap_or_default()
+    }
+
+    fn code_name(&self) -> &str {
+        "battlenet"
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let locations = get_locations(&self.settings.prefixes);
+        if locations.is_empty() {
+            return vec![DiagnosticCheck::message(
+                CheckStatus::Error,
+                "Battle.net",
+                "Could not find Battle.net in any prefix",
+            )];
+        }
+        let mut checks = vec![];
+        for location in locations {
+            checks.push(DiagnosticCheck::path(
+                "Battle.net wine prefix",
+                location.compat_folder,
+                true,
+            ));
+            checks.push(DiagnosticCheck::path(
+                "Battle.net launcher",
+                location.launcher_path,
+                true,
+            ));
+            let products = std::fs::read(&location.product_db_path)
+                .map_err(eyre::Report::from)
+                .and_then(|content| parse_product_db(&content));
+            checks.push(match products {
+                Ok(products) => DiagnosticCheck::message(
+                    CheckStatus::Ok,
+                    "Battle.net product.db",
+                    format!(
+                        "Read {} products from {}",
+                        products.len(),
+                        location.product_db_path.to_string_lossy()
+                    ),
+                ),
+                Err(err) => DiagnosticCheck::message(
+                    CheckStatus::Error,
+                    "Battle.net product.db",
+                    format!(
+                        "Could not read {}: {err}",
+                        location.product_db_path.to_string_lossy()
+                    ),
+                ),
+            });
+        }
+        checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launches_through_battle_net() {
+        let game = BattleNetGame {
+            title: "Overwatch 2".to_string(),
+            launch_code: "Pro".to_string(),
+            launcher_path: Path::new("/pfx/drive_c/Program Files (x86)/Battle.net/Battle.net.exe")
+                .to_path_buf(),
+            compat_folder: Path::new("/compatdata/123").to_path_buf(),
+        };
+        let shortcut: ShortcutOwned = game.into();
+        assert_eq!("Overwatch 2", shortcut.app_name);
+        assert_eq!(
+            "\"/pfx/drive_c/Program Files (x86)/Battle.net/Battle.net.exe\"",
+            shortcut.exe
+        );
+        assert_eq!(
+            "STEAM_COMPAT_DATA_PATH=\"/compatdata/123\" %command% --exec=\"launch Pro\"",
+            shortcut.launch_options
+        );
+    }
+
+    #[test]
+    fn unknown_products_are_named_after_their_folder() {
+        assert_eq!(
+            Some("Some New Game".to_string()),
+            install_folder_name("D:/Games/Some New Game/")
+        );
+        assert_eq!(None, install_folder_name("C:\\"));
+    }
+}
@@ -0,0 +1,179 @@
+//! Battle.net keeps the list of installed products in ProgramData/Battle.net/Agent/product.db.
+//! The file is a protobuf "Database" message, this only reads the few fields we need:
+//!
+//! Database { repeated ProductInstall product_install = 1; }
+//! ProductInstall { string uid = 1; string product_code = 2; UserSettings settings = 3; CachedProductState cached_product_state = 4; }
+//! UserSettings { string install_path = 1; }
+//! CachedProductState { BaseProductState base_product_state = 1; }
+//! BaseProductState { bool installed = 1; bool playable = 2; }
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub(crate) struct ProductInstall {
+    pub(crate) uid: String,
+    pub(crate) product_code: String,
+    pub(crate) install_path: String,
+    pub(crate) installed: bool,
+    pub(crate) playable: bool,
+}
+
+pub(crate) fn parse_product_db(data: &[u8]) -> eyre::Result<Vec<ProductInstall>> {
+    let mut result = vec![];
+    for field in ProtoReader::new(data) {
+        if let (1, WireValue::Bytes(bytes)) = field? {
+            result.push(parse_product_install(bytes)?);
+        }
+    }
+    Ok(result)
+}
+
+fn parse_product_install(data: &[u8]) -> eyre::Result<ProductInstall> {
+    let mut product = ProductInstall::default();
+    for field in ProtoReader::new(data) {
+        match field? {
+            (1, WireValue::Bytes(bytes)) => {
+                product.uid = String::from_utf8_lossy(bytes).to_string()
+            }
+            (2, WireValue::Bytes(bytes)) => {
+                product.product_code = String::from_utf8_lossy(bytes).to_string()
+            }
+            (3, WireValue::Bytes(settings)) => {
+                for field in ProtoReader::new(settings) {
+                    if let (1, WireValue::Bytes(bytes)) = field? {
+                        product.install_path = String::from_utf8_lossy(bytes).to_string();
+                    }
+                }
+            }
+            (4, WireValue::Bytes(cached_state)) => {
+                for field in ProtoReader::new(cached_state) {
+                    if let (1, WireValue::Bytes(base_state)) = field? {
+                        for field in ProtoReader::new(base_state) {
+                            match field? {
+                                (1, WireValue::Varint(installed)) => {
+                                    product.installed = installed != 0
+                                }
+                                (2, WireValue::Varint(playable)) => {
+                                    product.playable = playable != 0
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(product)
+}
+
+enum WireValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+struct ProtoReader<'a> {
+    data: &'a [u8],
+    failed: bool,
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            failed: false,
+        }
+    }
+
+    fn read_varint(&mut self) -> eyre::Result<u64> {
+        let mut result = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (byte, rest) = self
+                .data
+                .split_first()
+                .ok_or(eyre::format_err!("Unexpected end of product.db"))?;
+            self.data = rest;
+            result |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err(eyre::format_err!("Varint too long in product.db"))
+    }
+
+    fn skip(&mut self, length: usize) -> eyre::Result<&'a [u8]> {
+        if length > self.data.len() {
+            return Err(eyre::format_err!("Field longer than product.db"));
+        }
+        let (value, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(value)
+    }
+
+    fn read_field(&mut self) -> eyre::Result<(u64, WireValue<'a>)> {
+        let key = self.read_varint()?;
+        let field_number = key >> 3;
+        let value = match key & 0x7 {
+            0 => WireValue::Varint(self.read_varint()?),
+            1 => {
+                self.skip(8)?;
+                WireValue::Fixed
+            }
+            2 => {
+                let length = usize::try_from(self.read_varint()?)?;
+                WireValue::Bytes(self.skip(length)?)
+            }
+            5 => {
+                self.skip(4)?;
+                WireValue::Fixed
+            }
+            wire_type => {
+                return Err(eyre::format_err!(
+                    "Unsupported wire type {wire_type} in product.db"
+                ))
+            }
+        };
+        Ok((field_number, value))
+    }
+}
+
+impl<'a> Iterator for ProtoReader<'a> {
+    type Item = eyre::Result<(u64, WireValue<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() || self.failed {
+            return None;
+        }
+        let field = self.read_field();
+        self.failed = field.is_err();
+        Some(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn can_parse_product_db() {
+        let content = include_bytes!("../../testdata/battlenet/product.db");
+        let products = parse_product_db(content).unwrap();
+        let codes: Vec<_> = products.iter().map(|p| p.product_code.as_str()).collect();
+        assert_eq!(vec!["agent", "bna", "pro", "d3", "fenris"], codes);
+        let overwatch = products.iter().find(|p| p.product_code == "pro").unwrap();
+        assert_eq!("prometheus", overwatch.uid);
+        assert_eq!("C:/Program Files (x86)/Overwatch", overwatch.install_path);
+        assert!(overwatch.installed);
+        assert!(overwatch.playable);
+        let diablo3 = products.iter().find(|p| p.product_code == "d3").unwrap();
+        assert!(!diablo3.installed);
+    }
+
+    #[test]
+    fn truncated_product_db_is_an_error() {
+        let content = include_bytes!("../../testdata/battlenet/product.db");
+        let (truncated, _) = content.split_at(content.len() - 3);
+        assert!(parse_product_db(truncated).is_err());
+    }
+}
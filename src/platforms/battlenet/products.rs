@@ -0,0 +1,124 @@
+/// A product known to Battle.net.
+/// The code in product.db is not the same as the code used to launch the game, so we keep both.
+pub(crate) struct KnownProduct {
+    pub(crate) product_code: &'static str,
+    pub(crate) launch_code: &'static str,
+    pub(crate) name: &'static str,
+}
+
+/// Products that are part of the launcher itself and not games
+pub(crate) const NOT_GAMES: [&str; 2] = ["agent", "bna"];
+
+pub(crate) const KNOWN_PRODUCTS: [KnownProduct; 21] = [
+    KnownProduct {
+        product_code: "pro",
+        launch_code: "Pro",
+        name: "Overwatch 2",
+    },
+    KnownProduct {
+        product_code: "d3",
+        launch_code: "D3",
+        name: "Diablo III",
+    },
+    KnownProduct {
+        product_code: "fenris",
+        launch_code: "Fen",
+        name: "Diablo IV",
+    },
+    KnownProduct {
+        product_code: "osi",
+        launch_code: "OSI",
+        name: "Diablo II: Resurrected",
+    },
+    KnownProduct {
+        product_code: "anbs",
+        launch_code: "ANBS",
+        name: "Diablo Immortal",
+    },
+    KnownProduct {
+        product_code: "wow",
+        launch_code: "WoW",
+        name: "World of Warcraft",
+    },
+    KnownProduct {
+        product_code: "wow_classic",
+        launch_code: "WoWC",
+        name: "World of Warcraft Classic",
+    },
+    KnownProduct {
+        product_code: "hs_beta",
+        launch_code: "WTCG",
+        name: "Hearthstone",
+    },
+    KnownProduct {
+        product_code: "heroes",
+        launch_code: "Hero",
+        name: "Heroes of the Storm",
+    },
+    KnownProduct {
+        product_code: "s2",
+        launch_code: "S2",
+        name: "StarCraft II",
+    },
+    KnownProduct {
+        product_code: "s1",
+        launch_code: "S1",
+        name: "StarCraft: Remastered",
+    },
+    KnownProduct {
+        product_code: "w3",
+        launch_code: "W3",
+        name: "Warcraft III: Reforged",
+    },
+    KnownProduct {
+        product_code: "gryphon",
+        launch_code: "GRY",
+        name: "Warcraft Rumble",
+    },
+    KnownProduct {
+        product_code: "rtro",
+        launch_code: "RTRO",
+        name: "Blizzard Arcade Collection",
+    },
+    KnownProduct {
+        product_code: "wlby",
+        launch_code: "WLBY",
+        name: "Crash Bandicoot 4: It's About Time",
+    },
+    KnownProduct {
+        product_code: "odin",
+        launch_code: "ODIN",
+        name: "Call of Duty: Modern Warfare",
+    },
+    KnownProduct {
+        product_code: "viper",
+        launch_code: "VIPR",
+        name: "Call of Duty: Black Ops 4",
+    },
+    KnownProduct {
+        product_code: "lazarus",
+        launch_code: "LAZR",
+        name: "Call of Duty: Modern Warfare 2 Campaign Remastered",
+    },
+    KnownProduct {
+        product_code: "zeus",
+        launch_code: "ZEUS",
+        name: "Call of Duty: Black Ops Cold War",
+    },
+    KnownProduct {
+        product_code: "fore",
+        launch_code: "FORE",
+        name: "Call of Duty: Vanguard",
+    },
+    KnownProduct {
+        product_code: "auks",
+        launch_code: "AUKS",
+        name: "Call of Duty",
+    },
+];
+
+pub(crate) fn find_known_product(product_code: &str) -> Option<&'static KnownProduct> {
+    KNOWN_PRODUCTS
+        .iter()
+        .find(|product| product.product_code.eq_ignore_ascii_case(product_code))
+}
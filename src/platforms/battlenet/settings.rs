@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BattleNetSettings {
+    pub enabled: bool,
+    /// Extra wine prefixes (or Steam compatdata folders) to look for Battle.net in
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+}
+
+impl Default for BattleNetSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            prefixes: Default::default(),
+        }
+    }
+}
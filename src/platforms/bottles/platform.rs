@@ -1,12 +1,12 @@
-use std::process::Command;
-
 use eframe::epaint::ahash::HashMap;
 use serde::{Deserialize, Serialize};
 
 use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
 
+use crate::diagnostics::DiagnosticCheck;
 use crate::platforms::{
-    load_settings, to_shortcuts_simple, FromSettingsString, GamesPlatform, ShortcutToImport,
+    load_settings, to_shortcuts_simple, CommandRunner, FromSettingsString, GamesPlatform,
+    HostCommand, ShortcutToImport, SystemRunner,
 };
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -56,8 +56,16 @@ impl From<BottlesApp> for ShortcutOwned {
     }
 }
 
-fn get_bottles() -> eyre::Result<Vec<Bottle>> {
-    let json = get_bottles_output()?;
+fn get_bottles(runner: &dyn CommandRunner) -> eyre::Result<Vec<Bottle>> {
+    let command = HostCommand::new("flatpak").args([
+        "run",
+        "--command=bottles-cli",
+        "com.usebottles.bottles",
+        "-j",
+        "list",
+        "bottles",
+    ]);
+    let json = runner.stdout(&command)?;
     let map: HashMap<String, Bottle> = serde_json::from_str(json.as_str())?;
     let mut res = vec![];
     for (_, value) in map {
@@ -66,38 +74,6 @@ fn get_bottles() -> eyre::Result<Vec<Bottle>> {
     Ok(res)
 }
 
-fn get_bottles_output() -> eyre::Result<String> {
-    let output = {
-        #[cfg(not(feature = "flatpak"))]
-        {
-            let mut command = Command::new("flatpak");
-            command
-                .arg("run")
-                .arg("--command=bottles-cli")
-                .arg("com.usebottles.bottles")
-                .arg("-j")
-                .arg("list")
-                .arg("bottles")
-                .output()?
-        }
-        #[cfg(feature = "flatpak")]
-        {
-            let mut command = Command::new("flatpak-spawn");
-            command
-                .arg("--host")
-                .arg("flatpak")
-                .arg("run")
-                .arg("--command=bottles-cli")
-                .arg("com.usebottles.bottles")
-                .arg("-j")
-                .arg("list")
-                .arg("bottles")
-                .output()?
-        }
-    };
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-}
-
 #[derive(Deserialize, Debug)]
 struct Bottle {
     #[serde(alias = "Name")]
@@ -115,7 +91,7 @@ struct Program {
 impl BottlesPlatform {
     fn get_botttles(&self) -> eyre::Result<Vec<BottlesApp>> {
         let mut res = vec![];
-        let bottles = get_bottles()?;
+        let bottles = get_bottles(&SystemRunner::default())?;
         for bottle in bottles {
             for (_id, program) in bottle.external_programs {
                 res.push(BottlesApp {
@@ -153,4 +129,16 @@ impl GamesPlatform for BottlesPlatform {
     fn code_name(&self) -> &str {
         "bottles"
     }
+
+    fn can_need_proton(&self) -> bool {
+        false
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        vec![DiagnosticCheck::command(
+            "Bottles flatpak",
+            "flatpak",
+            &["info", "com.usebottles.bottles"],
+        )]
+    }
 }
@@ -0,0 +1,271 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
+
+use crate::config::get_platform_cache_folder;
+
+use super::{GameMetadata, GamesPlatform, ShortcutToImport};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The games a platform found, and what they were found from
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct CachedDiscovery {
+    settings_hash: u64,
+    dependencies: Vec<DependencyState>,
+    shortcuts: Vec<CachedShortcut>,
+}
+
+/// A file or folder that a platform read, and when it was last changed (None if it did not exist)
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct DependencyState {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl DependencyState {
+    fn read<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        Self {
+            path: path.to_path_buf(),
+            modified: std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok(),
+        }
+    }
+
+    fn is_unchanged(&self) -> bool {
+        *self == Self::read(&self.path)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct CachedShortcut {
+    app_name: String,
+    exe: String,
+    start_dir: String,
+    icon: String,
+    shortcut_path: String,
+    launch_options: String,
+    tags: Vec<String>,
+    needs_proton: bool,
+    needs_symlinks: bool,
+    #[serde(default)]
+    metadata: Option<GameMetadata>,
+}
+
+impl From<&ShortcutToImport> for CachedShortcut {
+    fn from(to_import: &ShortcutToImport) -> Self {
+        let shortcut = &to_import.shortcut;
+        Self {
+            app_name: shortcut.app_name.clone(),
+            exe: shortcut.exe.clone(),
+            start_dir: shortcut.start_dir.clone(),
+            icon: shortcut.icon.clone(),
+            shortcut_path: shortcut.shortcut_path.clone(),
+            launch_options: shortcut.launch_options.clone(),
+            tags: shortcut.tags.clone(),
+            needs_proton: to_import.needs_proton,
+            needs_symlinks: to_import.needs_symlinks,
+            metadata: to_import.metadata.clone(),
+        }
+    }
+}
+
+impl From<CachedShortcut> for ShortcutToImport {
+    fn from(cached: CachedShortcut) -> Self {
+        let mut shortcut: ShortcutOwned = Shortcut::new(
+            "0",
+            &cached.app_name,
+            &cached.exe,
+            &cached.start_dir,
+            &cached.icon,
+            &cached.shortcut_path,
+            &cached.launch_options,
+        )
+        .to_owned();
+        shortcut.tags = cached.tags;
+        Self {
+            shortcut,
+            needs_proton: cached.needs_proton,
+            needs_symlinks: cached.needs_symlinks,
+            metadata: cached.metadata,
+        }
+    }
+}
+
+/// The games of the platform, from the cache if neither its settings nor the files it depends on have changed.
+/// Platforms that do not declare their dependencies are always scanned.
+pub fn get_shortcuts_cached(
+    platform: &dyn GamesPlatform,
+    use_cache: bool,
+) -> eyre::Result<Vec<ShortcutToImport>> {
+    get_shortcuts_cached_in(&get_platform_cache_folder(), platform, use_cache)
+}
+
+fn get_shortcuts_cached_in(
+    cache_folder: &Path,
+    platform: &dyn GamesPlatform,
+    use_cache: bool,
+) -> eyre::Result<Vec<ShortcutToImport>> {
+    let Some(dependencies) = platform.cache_dependencies() else {
+        return platform.get_shortcut_info();
+    };
+    let cache_file = cache_folder.join(format!("{}.json", platform.code_name()));
+    let settings_hash = settings_hash(platform);
+    if use_cache {
+        if let Some(cached) = load_cache(&cache_file) {
+            if cached.settings_hash == settings_hash
+                && cached
+                    .dependencies
+                    .iter()
+                    .all(DependencyState::is_unchanged)
+            {
+                return Ok(cached.shortcuts.into_iter().map(Into::into).collect());
+            }
+        }
+    }
+    //Read the state before scanning, so changes made during the scan are found next time
+    let dependencies = dependencies.iter().map(DependencyState::read).collect();
+    let shortcuts = platform.get_shortcut_info()?;
+    let cached = CachedDiscovery {
+        settings_hash,
+        dependencies,
+        shortcuts: shortcuts.iter().map(Into::into).collect(),
+    };
+    if let Err(err) = save_cache(&cache_file, &cached) {
+        eprintln!("Could not save the games of {}: {err}", platform.name());
+    }
+    Ok(shortcuts)
+}
+
+fn load_cache(path: &Path) -> Option<CachedDiscovery> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_cache(path: &Path, cached: &CachedDiscovery) -> eyre::Result<()> {
+    let content = serde_json::to_string(cached)?;
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// A hash that is the same across runs, of the platform settings and the BoilR version
+fn settings_hash(platform: &dyn GamesPlatform) -> u64 {
+    let input = format!("{VERSION}\n{}", platform.get_settings_serializable());
+    //FNV-1a
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct CountingPlatform {
+        scans: Arc<AtomicUsize>,
+        dependency: PathBuf,
+        settings: String,
+    }
+
+    impl GamesPlatform for CountingPlatform {
+        fn name(&self) -> &str {
+            "Counting"
+        }
+
+        fn code_name(&self) -> &str {
+            "counting"
+        }
+
+        fn enabled(&self) -> bool {
+            true
+        }
+
+        fn get_shortcut_info(&self) -> eyre::Result<Vec<ShortcutToImport>> {
+            self.scans.fetch_add(1, Ordering::SeqCst);
+            let shortcut = Shortcut::new("0", "Game", "/bin/game", "", "", "", "").to_owned();
+            Ok(vec![ShortcutToImport {
+                shortcut,
+                needs_proton: true,
+                needs_symlinks: false,
+                metadata: Some(GameMetadata {
+                    game_id: Some("game".to_string()),
+                    ..Default::default()
+                }),
+            }])
+        }
+
+        fn get_settings_serializable(&self) -> String {
+            self.settings.clone()
+        }
+
+        fn render_ui(&mut self, _ui: &mut egui::Ui) {}
+
+        fn cache_dependencies(&self) -> Option<Vec<PathBuf>> {
+            Some(vec![self.dependency.clone()])
+        }
+    }
+
+    fn test_folder(name: &str) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("boilr-cache-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    #[test]
+    fn unchanged_platform_is_loaded_from_cache() {
+        let folder = test_folder("unchanged");
+        let dependency = folder.join("games.db");
+        std::fs::write(&dependency, "1").unwrap();
+        let platform = CountingPlatform {
+            scans: Default::default(),
+            dependency,
+            settings: "enabled = true".to_string(),
+        };
+        let first = get_shortcuts_cached_in(&folder, &platform, true).unwrap();
+        let second = get_shortcuts_cached_in(&folder, &platform, true).unwrap();
+        assert_eq!(1, platform.scans.load(Ordering::SeqCst));
+        assert_eq!(first.len(), second.len());
+        let cached = second.first().unwrap();
+        assert_eq!("Game", cached.shortcut.app_name);
+        assert_eq!(
+            first.first().unwrap().shortcut.app_id,
+            cached.shortcut.app_id
+        );
+        assert!(cached.needs_proton);
+        assert_eq!(first.first().unwrap().metadata, cached.metadata);
+
+        get_shortcuts_cached_in(&folder, &platform, false).unwrap();
+        assert_eq!(2, platform.scans.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn changes_invalidate_the_cache() {
+        let folder = test_folder("changed");
+        let dependency = folder.join("games.db");
+        let mut platform = CountingPlatform {
+            scans: Default::default(),
+            dependency: dependency.clone(),
+            settings: "enabled = true".to_string(),
+        };
+        get_shortcuts_cached_in(&folder, &platform, true).unwrap();
+        //The dependency did not exist, now it does
+        std::fs::write(&dependency, "1").unwrap();
+        get_shortcuts_cached_in(&folder, &platform, true).unwrap();
+        assert_eq!(2, platform.scans.load(Ordering::SeqCst));
+
+        platform.settings = "enabled = false".to_string();
+        get_shortcuts_cached_in(&folder, &platform, true).unwrap();
+        assert_eq!(3, platform.scans.load(Ordering::SeqCst));
+    }
+}
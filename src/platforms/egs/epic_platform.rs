@@ -1,13 +1,20 @@
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+#[cfg(target_family = "unix")]
+use crate::platforms::executable_needs_proton;
 use crate::platforms::{
-    load_settings, to_shortcuts, FromSettingsString, GamesPlatform, NeedsProton, ShortcutToImport,
+    load_settings, to_shortcuts, FromSettingsString, GameMetadata, GamesPlatform, NeedsProton,
+    ShortcutToImport,
 };
 
-use super::{get_egs_manifests, settings::EpicGamesLauncherSettings, ManifestItem};
+use super::{
+    get_egs_manifests, get_locations, settings::EpicGamesLauncherSettings, EpicPaths, ManifestItem,
+};
 
 #[derive(Clone)]
 pub struct EpicPlatform {
     pub(crate) settings: EpicGamesLauncherSettings,
     pub(crate) epic_manifests: Option<Vec<ManifestItem>>,
+    pub(crate) epic_locations: Option<Vec<EpicPaths>>,
 }
 
 impl FromSettingsString for EpicPlatform {
@@ -15,14 +22,16 @@ impl FromSettingsString for EpicPlatform {
         EpicPlatform {
             settings: load_settings(s),
             epic_manifests: None,
+            epic_locations: None,
         }
     }
 }
 
 impl NeedsProton<EpicPlatform> for ManifestItem {
     fn needs_proton(&self, _platform: &EpicPlatform) -> bool {
+        //The Epic launcher is a Windows program, so games started through it always need Proton
         #[cfg(target_family = "unix")]
-        return true;
+        return self.needs_launcher() || executable_needs_proton(self.exe()).unwrap_or(true);
         #[cfg(target_os = "windows")]
         return false;
     }
@@ -30,6 +39,17 @@ impl NeedsProton<EpicPlatform> for ManifestItem {
     fn create_symlinks(&self, _platform: &EpicPlatform) -> bool {
         false
     }
+
+    fn metadata(&self, _platform: &EpicPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            game_id: Some(self.app_name.clone()),
+            store: Some("egs".to_string()),
+            install_path: Some(self.install_location.clone()),
+            install_size: self.install_size,
+            native: Some(cfg!(target_os = "windows")),
+            ..Default::default()
+        })
+    }
 }
 
 impl GamesPlatform for EpicPlatform {
@@ -56,4 +76,36 @@ impl GamesPlatform for EpicPlatform {
     fn get_settings_serializable(&self) -> String {
         toml::to_string(&self.settings).unwrap_or_default()
     }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let locations = get_locations(&self.settings);
+        if locations.is_empty() {
+            return vec![DiagnosticCheck::message(
+                CheckStatus::Error,
+                "Epic launcher",
+                "No Epic launcher or manifests folder found",
+            )];
+        }
+        let mut checks = vec![];
+        for location in locations {
+            if let Some(compat_folder) = location.compat_folder_path {
+                checks.push(DiagnosticCheck::path(
+                    "Epic wine prefix",
+                    compat_folder,
+                    true,
+                ));
+            }
+            checks.push(DiagnosticCheck::path(
+                "Epic launcher",
+                location.launcher_path,
+                true,
+            ));
+            checks.push(DiagnosticCheck::path(
+                "Epic manifests",
+                location.manifest_folder_path,
+                true,
+            ));
+        }
+        checks
+    }
 }
@@ -1,4 +1,4 @@
-use super::{get_manifests::get_egs_manifests, EpicPlatform};
+use super::{get_locations, get_manifests::get_egs_manifests, EpicPlatform};
 
 impl EpicPlatform {
     pub fn render_epic_settings(&mut self, ui: &mut egui::Ui) {
@@ -6,6 +6,34 @@ impl EpicPlatform {
         ui.heading("Epic Games");
         ui.checkbox(&mut epic_settings.enabled, "Import from Epic Games");
         if epic_settings.enabled {
+            let locations = self
+                .epic_locations
+                .get_or_insert_with(|| get_locations(epic_settings));
+            let locations_header = match locations.len() {
+                0 => "No Epic Games Launcher found".to_string(),
+                1 => "Found one Epic Games Launcher".to_string(),
+                x => format!("Found {x} Epic Games Launchers"),
+            };
+            let mut prefixes_changed = false;
+            egui::CollapsingHeader::new(locations_header)
+            .id_salt("Epic_Launcher_locations")
+            .show(ui, |ui| {
+                for location in locations.iter() {
+                    let label = match location.compat_folder_path.as_ref() {
+                        Some(compat_folder) => compat_folder.to_string_lossy().to_string(),
+                        None => location.launcher_path.to_string_lossy().to_string(),
+                    };
+                    ui.label(label).on_hover_text(location.manifest_folder_path.to_string_lossy());
+                }
+                #[cfg(target_family = "unix")]
+                {
+                    prefixes_changed = crate::platforms::render_extra_prefixes(&mut epic_settings.prefixes, ui);
+                }
+            });
+            if prefixes_changed {
+                self.epic_locations = None;
+                self.epic_manifests = None;
+            }
             let safe_mode_header = match epic_settings.safe_launch.len() {
                 0 => "Force games to launch through Epic Launcher".to_string(),
                 1 => "One game forced to launch through Epic Launcher".to_string(),
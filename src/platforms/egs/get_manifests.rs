@@ -1,5 +1,5 @@
 use super::settings::EpicGamesLauncherSettings;
-use super::ManifestItem;
+use super::{EpicPaths, ManifestItem};
 
 use std::fs::{DirEntry, File};
 use std::io::BufReader;
@@ -8,45 +8,56 @@ use std::path::{Path, PathBuf};
 pub(crate) fn get_egs_manifests(
     settings: &EpicGamesLauncherSettings,
 ) -> eyre::Result<Vec<ManifestItem>> {
-    let locations = super::get_locations();
-    match locations {
-        Some(locations) => {
-            let manifest_dir_path = locations.manifest_folder_path;
-            let manifest_dir_result = std::fs::read_dir(&manifest_dir_path);
-
-            match manifest_dir_result {
-                Ok(manifest_dir) => {
-                    let mut manifests: Vec<ManifestItem> = manifest_dir
-                        .filter_map(|dir| dir.ok())
-                        .filter_map(|dir| {
-                            get_manifest_item(dir, locations.compat_folder_path.clone())
-                        })
-                        .filter(is_game_installed)
-                        .filter(is_game_launchable)
-                        .collect();
+    let locations = super::get_locations(settings);
+    if locations.is_empty() {
+        return Err(eyre::format_err!("Manifests not found"));
+    }
+    let mut manifests: Vec<ManifestItem> = vec![];
+    let mut errors = vec![];
+    for location in &locations {
+        match get_manifests_for_location(location) {
+            Ok(location_manifests) => manifests.extend(location_manifests),
+            Err(err) => errors.push(err),
+        }
+    }
+    if manifests.is_empty() {
+        if let Some(err) = errors.pop() {
+            return Err(err);
+        }
+    }
 
-                    manifests.sort_by_key(|m| m.dedupe_key());
-                    manifests.dedup_by_key(|m| m.dedupe_key());
-                    for  manifest in &mut manifests {
-                        manifest.launcher_path = Some(locations.launcher_path.clone());
-                        manifest.compat_folder = locations.compat_folder_path.clone();
-                        if settings.safe_launch.contains(&manifest.display_name)
-                            || settings.safe_launch.contains(&manifest.get_key())
-                        {
-                            manifest.safe_launch = true;
-                        }
-                    }
-                    Ok(manifests)
-                }
-                Err(err) => Err(eyre::format_err!(
-                    "Could not read dir at: {:?} error: {:?}",
-                    manifest_dir_path,
-                    err
-                )),
-            }
+    manifests.sort_by_key(|m| m.dedupe_key());
+    manifests.dedup_by_key(|m| m.dedupe_key());
+    for manifest in &mut manifests {
+        if settings.safe_launch.contains(&manifest.display_name)
+            || settings.safe_launch.contains(&manifest.get_key())
+        {
+            manifest.safe_launch = true;
         }
-        None => Err(eyre::format_err!("Manifests not found")),
     }
+    Ok(manifests)
+}
+
+fn get_manifests_for_location(locations: &EpicPaths) -> eyre::Result<Vec<ManifestItem>> {
+    let manifest_dir_path = &locations.manifest_folder_path;
+    let manifest_dir = std::fs::read_dir(manifest_dir_path).map_err(|err| {
+        eyre::format_err!(
+            "Could not read dir at: {:?} error: {:?}",
+            manifest_dir_path,
+            err
+        )
+    })?;
+    let mut manifests: Vec<ManifestItem> = manifest_dir
+        .filter_map(|dir| dir.ok())
+        .filter_map(|dir| get_manifest_item(dir, locations.compat_folder_path.clone()))
+        .filter(is_game_installed)
+        .filter(is_game_launchable)
+        .collect();
+    for manifest in &mut manifests {
+        manifest.launcher_path = Some(locations.launcher_path.clone());
+        manifest.compat_folder = locations.compat_folder_path.clone();
+    }
+    Ok(manifests)
 }
 
 fn is_game_installed(manifest: &ManifestItem) -> bool {
@@ -89,8 +100,7 @@ fn replace_with_dosdevices(compat_folder: &Path, location: &str) -> String {
     let drive = location.get(0..2).map(|drive| drive.to_lowercase());
     let rest_path = location.get(3..).map(|rest| rest.replace('\\', "/"));
     if let (Some(drive), Some(rest_path)) = (drive, rest_path) {
-        let path_buf = compat_folder
-            .join("pfx")
+        let path_buf = crate::platforms::wine_prefix(compat_folder)
             .join("dosdevices")
             .join(drive)
             .join(rest_path);
@@ -35,6 +35,9 @@ pub(crate) struct ManifestItem {
     #[serde(alias = "ExpectingDLCInstalled")]
     pub expected_dlc: Option<HashMap<String, bool>>,
 
+    #[serde(alias = "InstallSize", default)]
+    pub install_size: Option<u64>,
+
     #[serde(default)]
     pub safe_launch: bool,
 
@@ -143,7 +146,7 @@ impl From<ManifestItem> for ShortcutOwned {
 }
 
 impl ManifestItem {
-    fn exe(&self) -> String {
+    pub(crate) fn exe(&self) -> String {
         let manifest = self;
         let exe_path = Path::new(&manifest.install_location)
             .join(&manifest.launch_executable)
@@ -174,7 +177,7 @@ impl ManifestItem {
         )
     }
 
-    fn needs_launcher(&self) -> bool {
+    pub(crate) fn needs_launcher(&self) -> bool {
         if self.safe_launch {
             return true;
         }
@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use super::settings::EpicGamesLauncherSettings;
+
 #[derive(Default, Clone, Debug)]
 pub struct EpicPaths {
     pub(crate) launcher_path: PathBuf,
@@ -7,77 +9,86 @@ pub struct EpicPaths {
     pub(crate) manifest_folder_path: PathBuf,
 }
 
-pub fn get_locations() -> Option<EpicPaths> {
+pub fn get_locations(_settings: &EpicGamesLauncherSettings) -> Vec<EpicPaths> {
     #[cfg(target_family = "unix")]
     {
-        unix::get_locations()
+        unix::get_locations(&_settings.prefixes)
     }
     #[cfg(target_os = "windows")]
     {
-        windows::get_locations()
+        windows::get_locations().into_iter().collect()
     }
 }
 
 #[cfg(target_family = "unix")]
 mod unix {
     use super::EpicPaths;
-    use std::path::Path;
-
-    pub fn get_locations() -> Option<EpicPaths> {
-        if let Ok(home) = std::env::var("HOME") {
-            let compat_folder_path = Path::new(&home)
-                .join(".steam")
-                .join("steam")
-                .join("steamapps")
-                .join("compatdata");
+    use crate::platforms::{wine_prefix, wine_prefix_candidates};
+    use std::path::{Path, PathBuf};
 
-            if let Ok(compat_folder) = std::fs::read_dir(compat_folder_path) {
-                for dir in compat_folder.flatten() {
-                    let binary_path = dir
-                        .path()
-                        .join("pfx")
-                        .join("drive_c")
-                        .join("Program Files (x86)")
-                        .join("Epic Games")
-                        .join("Launcher")
-                        .join("Portal")
-                        .join("Binaries");
-                    if binary_path.exists() {
-                        let launcher_path = if binary_path
-                            .join("Win32")
-                            .join("EpicGamesLauncher.exe")
-                            .exists()
-                        {
-                            binary_path.join("Win32").join("EpicGamesLauncher.exe")
-                        } else {
-                            binary_path.join("Win64").join("EpicGamesLauncher.exe")
-                        };
-                        if launcher_path.exists() {
-                            //We found a launcher, lets find the manifests
+    pub fn get_locations(configured_prefixes: &[String]) -> Vec<EpicPaths> {
+        let mut compat_folders: Vec<PathBuf> = configured_prefixes
+            .iter()
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| Path::new(p).to_path_buf())
+            .collect();
+        compat_folders.extend(wine_prefix_candidates());
 
-                            let manifest_folder_path = dir
-                                .path()
-                                .join("pfx")
-                                .join("drive_c")
-                                .join("ProgramData")
-                                .join("Epic")
-                                .join("EpicGamesLauncher")
-                                .join("Data")
-                                .join("Manifests");
-                            if manifest_folder_path.exists() {
-                                //We found all we need
-                                return Some(EpicPaths {
-                                    launcher_path,
-                                    compat_folder_path: Some(dir.path()),
-                                    manifest_folder_path,
-                                });
-                            }
-                        }
-                    }
+        let mut locations: Vec<EpicPaths> = vec![];
+        for compat_folder in compat_folders {
+            if let Some(location) = get_location_in_prefix(compat_folder) {
+                let already_found = locations.iter().any(|l| {
+                    l.manifest_folder_path.canonicalize().ok()
+                        == location.manifest_folder_path.canonicalize().ok()
+                });
+                if !already_found {
+                    locations.push(location);
                 }
             }
         }
-        None
+        locations
+    }
+
+    fn get_location_in_prefix(compat_folder: PathBuf) -> Option<EpicPaths> {
+        let drive_c = wine_prefix(&compat_folder).join("drive_c");
+        let binary_path = drive_c
+            .join("Program Files (x86)")
+            .join("Epic Games")
+            .join("Launcher")
+            .join("Portal")
+            .join("Binaries");
+        if !binary_path.exists() {
+            return None;
+        }
+        let launcher_path = if binary_path
+            .join("Win32")
+            .join("EpicGamesLauncher.exe")
+            .exists()
+        {
+            binary_path.join("Win32").join("EpicGamesLauncher.exe")
+        } else {
+            binary_path.join("Win64").join("EpicGamesLauncher.exe")
+        };
+        if !launcher_path.exists() {
+            return None;
+        }
+        //We found a launcher, lets find the manifests
+        let manifest_folder_path = drive_c
+            .join("ProgramData")
+            .join("Epic")
+            .join("EpicGamesLauncher")
+            .join("Data")
+            .join("Manifests");
+        if manifest_folder_path.exists() {
+            //We found all we need
+            Some(EpicPaths {
+                launcher_path,
+                compat_folder_path: Some(compat_folder),
+                manifest_folder_path,
+            })
+        } else {
+            None
+        }
     }
 }
 
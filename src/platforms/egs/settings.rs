@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 pub struct EpicGamesLauncherSettings {
     pub enabled: bool,
     pub safe_launch: Vec<String>,
+    /// Extra wine prefixes (or Steam compatdata folders) to look for the Epic Games Launcher in
+    #[cfg(target_family = "unix")]
+    #[serde(default)]
+    pub prefixes: Vec<String>,
 }
 
 impl Default for EpicGamesLauncherSettings {
@@ -11,6 +15,8 @@ impl Default for EpicGamesLauncherSettings {
         Self {
             enabled: true,
             safe_launch: Default::default(),
+            #[cfg(target_family = "unix")]
+            prefixes: Default::default(),
         }
     }
 }
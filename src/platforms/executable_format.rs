@@ -0,0 +1,101 @@
+use std::io::Read;
+use std::path::Path;
+
+/// The kind of program a file is, found from the first bytes of the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutableFormat {
+    /// A Linux program
+    Elf,
+    /// A Windows program (.exe), it starts with "MZ"
+    Pe,
+    /// A script that names its interpreter, fx "#!/bin/sh"
+    Shebang,
+    /// A Windows batch file (.bat or .cmd), these have no header so they are found from the extension
+    Batch,
+}
+
+impl ExecutableFormat {
+    pub fn is_windows(&self) -> bool {
+        matches!(self, ExecutableFormat::Pe | ExecutableFormat::Batch)
+    }
+}
+
+/// Reads the header of the file, None if it can not be read or is not a known kind of program
+pub fn probe_executable<P: AsRef<Path>>(path: P) -> Option<ExecutableFormat> {
+    let path = path.as_ref();
+    let mut header = [0u8; 4];
+    let mut file = std::fs::File::open(path).ok()?;
+    let read = file.read(&mut header).ok()?;
+    let header = header.get(..read)?;
+    if header.starts_with(b"\x7fELF") {
+        Some(ExecutableFormat::Elf)
+    } else if header.starts_with(b"MZ") {
+        Some(ExecutableFormat::Pe)
+    } else if header.starts_with(b"#!") {
+        Some(ExecutableFormat::Shebang)
+    } else if has_extension(path, &["bat", "cmd"]) {
+        Some(ExecutableFormat::Batch)
+    } else {
+        None
+    }
+}
+
+/// If the executable has to run through Proton on this system.
+/// None if the file could not be read, then the platform has to make a guess.
+pub fn executable_needs_proton<S: AsRef<str>>(exe: S) -> Option<bool> {
+    let exe = exe.as_ref().trim().trim_matches('"');
+    if exe.is_empty() {
+        return None;
+    }
+    let format = probe_executable(exe)?;
+    Some(cfg!(target_family = "unix") && format.is_windows())
+}
+
+/// A guess for files that could not be read, from their extension alone
+pub fn has_windows_extension<S: AsRef<str>>(exe: S) -> bool {
+    let exe = exe.as_ref().trim().trim_matches('"');
+    has_extension(Path::new(exe), &["exe", "bat", "cmd", "msi"])
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| {
+            extensions
+                .iter()
+                .any(|known| extension.eq_ignore_ascii_case(known))
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FOLDER: &str = "src/testdata/executables";
+
+    #[test]
+    fn formats_are_found_from_the_header() {
+        let probe = |name: &str| probe_executable(Path::new(FOLDER).join(name));
+        assert_eq!(Some(ExecutableFormat::Elf), probe("game.x86_64"));
+        assert_eq!(Some(ExecutableFormat::Pe), probe("Game.EXE"));
+        assert_eq!(Some(ExecutableFormat::Shebang), probe("start.sh"));
+        assert_eq!(Some(ExecutableFormat::Batch), probe("launch.bat"));
+        assert_eq!(None, probe("readme.txt"));
+        assert_eq!(None, probe("does-not-exist.exe"));
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn windows_programs_need_proton() {
+        let quoted = format!("\"{FOLDER}/Game.EXE\"");
+        assert_eq!(Some(true), executable_needs_proton(quoted));
+        assert_eq!(
+            Some(false),
+            executable_needs_proton(format!("{FOLDER}/game.x86_64"))
+        );
+        assert_eq!(None, executable_needs_proton(format!("{FOLDER}/missing")));
+        assert!(has_windows_extension("\"C:\\Games\\Launcher.BAT\""));
+        assert!(!has_windows_extension("/games/game.x86_64"));
+    }
+}
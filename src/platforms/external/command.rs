@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use crate::platforms::{CommandRunner, HostCommand};
+
+/// Runs the command through the shell and returns what it printed to stdout.
+/// The command is killed if it runs for longer than the timeout,
+/// and what it printed to stderr is part of the error if it fails.
+pub(crate) fn run_command(
+    runner: &dyn CommandRunner,
+    command: &str,
+    timeout: Duration,
+) -> eyre::Result<String> {
+    runner.checked_stdout(&HostCommand::shell(command).timeout(timeout))
+}
+
+#[cfg(all(test, target_family = "unix"))]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::platforms::SystemRunner;
+
+    #[test]
+    fn returns_stdout() {
+        let output = run_command(
+            &SystemRunner::default(),
+            "echo '[]'",
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!("[]", output.trim());
+    }
+
+    #[test]
+    fn stderr_is_part_of_the_error() {
+        let err = run_command(
+            &SystemRunner::default(),
+            "echo broken >&2; exit 3",
+            Duration::from_secs(5),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("broken"));
+    }
+
+    #[test]
+    fn slow_commands_time_out() {
+        let err = run_command(
+            &SystemRunner::default(),
+            "sleep 5",
+            Duration::from_millis(200),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("did not finish"));
+    }
+}
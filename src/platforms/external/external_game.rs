@@ -0,0 +1,134 @@
+use serde::Deserialize;
+use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
+
+/// One game in the JSON list that an external command prints, fx:
+/// [{"id":"doom","name":"Doom","exe":"/usr/bin/gzdoom","launch_options":"-iwad doom.wad","tags":["Shooter"]}]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ExternalGame {
+    /// A stable id for the game, it must be unique in the output of the command
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) exe: String,
+    #[serde(default)]
+    pub(crate) start_dir: String,
+    #[serde(default)]
+    pub(crate) launch_options: String,
+    #[serde(default)]
+    pub(crate) icon: String,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    /// If not set, it is found from the header of the exe
+    #[serde(default)]
+    pub(crate) needs_proton: Option<bool>,
+    #[serde(default)]
+    pub(crate) install_path: Option<String>,
+    #[serde(default)]
+    pub(crate) install_size: Option<u64>,
+    #[serde(default)]
+    pub(crate) developer: Option<String>,
+    #[serde(default)]
+    pub(crate) genres: Vec<String>,
+    /// A local image of the cover art
+    #[serde(default)]
+    pub(crate) cover: Option<String>,
+}
+
+impl From<ExternalGame> for ShortcutOwned {
+    fn from(game: ExternalGame) -> Self {
+        let mut shortcut = Shortcut::new(
+            "0",
+            &game.name,
+            &game.exe,
+            &game.start_dir,
+            &game.icon,
+            "",
+            &game.launch_options,
+        )
+        .to_owned();
+        shortcut.tags = game.tags;
+        shortcut
+    }
+}
+
+/// The games that could be read from the output of a command, and a message for each entry that could not
+#[derive(Debug, Default)]
+pub(crate) struct ParsedGames {
+    pub(crate) games: Vec<ExternalGame>,
+    pub(crate) entry_errors: Vec<String>,
+}
+
+pub(crate) fn parse_games(output: &str) -> eyre::Result<ParsedGames> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(output)
+        .map_err(|err| eyre::format_err!("Output is not a JSON list: {err}"))?;
+    let mut result = ParsedGames::default();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let label = entry
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(|name| format!("Entry {index} ({name})"))
+            .unwrap_or_else(|| format!("Entry {index}"));
+        match serde_json::from_value::<ExternalGame>(entry) {
+            Ok(game) if game.name.trim().is_empty() || game.exe.trim().is_empty() => {
+                result
+                    .entry_errors
+                    .push(format!("{label}: name and exe can not be empty"));
+            }
+            Ok(game) if result.games.iter().any(|g| g.id == game.id) => {
+                result
+                    .entry_errors
+                    .push(format!("{label}: the id \"{}\" is used twice", game.id));
+            }
+            Ok(game) => result.games.push(game),
+            Err(err) => result.entry_errors.push(format!("{label}: {err}")),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    #![allow(clippy::indexing_slicing)]
+    use super::*;
+
+    #[test]
+    fn can_parse_games() {
+        let output = r#"[
+            {"id":"doom","name":"Doom","exe":"/usr/bin/gzdoom","launch_options":"-iwad doom.wad","tags":["Shooter"]},
+            {"id":"tunic","name":"Tunic","exe":"/games/tunic/Tunic.exe","start_dir":"/games/tunic","needs_proton":true,"developer":"Andrew Shouldice","genres":["Action","Adventure"]}
+        ]"#;
+        let parsed = parse_games(output).unwrap();
+        assert!(parsed.entry_errors.is_empty());
+        assert_eq!(2, parsed.games.len());
+        let doom: ShortcutOwned = parsed.games.first().cloned().unwrap().into();
+        assert_eq!("-iwad doom.wad", doom.launch_options);
+        assert_eq!(vec!["Shooter".to_string()], doom.tags);
+        let tunic = parsed.games.last().unwrap();
+        assert_eq!(Some(true), tunic.needs_proton);
+        assert_eq!(None, parsed.games.first().unwrap().needs_proton);
+        assert_eq!(Some("Andrew Shouldice".to_string()), tunic.developer);
+        assert_eq!(2, tunic.genres.len());
+    }
+
+    #[test]
+    fn schema_errors_are_reported_per_entry() {
+        let output = r#"[
+            {"id":"doom","name":"Doom","exe":"/usr/bin/gzdoom"},
+            {"id":"quake","name":"Quake"},
+            {"id":"doom","name":"Doom 2","exe":"/usr/bin/gzdoom"},
+            {"id":"hexen","name":"Hexen","exe":"/usr/bin/gzdoom","needs_wine":true}
+        ]"#;
+        let parsed = parse_games(output).unwrap();
+        assert_eq!(1, parsed.games.len());
+        assert_eq!(3, parsed.entry_errors.len());
+        assert!(parsed.entry_errors[0].starts_with("Entry 1 (Quake): missing field `exe`"));
+        assert!(parsed.entry_errors[1].contains("used twice"));
+        assert!(parsed.entry_errors[2].contains("needs_wine"));
+    }
+
+    #[test]
+    fn output_must_be_a_list() {
+        assert!(parse_games("{\"games\":[]}").is_err());
+    }
+}
@@ -0,0 +1,6 @@
+mod command;
+mod external_game;
+mod platform;
+mod settings;
+
+pub use platform::ExternalCommandsPlatform;
@@ -0,0 +1,307 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+use crate::platforms::{
+    executable_needs_proton, load_settings, to_shortcuts, CancelToken, FromSettingsString,
+    GameMetadata, GamesPlatform, NeedsProton, ShortcutToImport, SystemRunner,
+};
+
+use super::{
+    command::run_command,
+    external_game::{parse_games, ExternalGame},
+    settings::ExternalCommandsSettings,
+};
+
+#[derive(Clone)]
+pub struct ExternalCommandsPlatform {
+    pub settings: ExternalCommandsSettings,
+    test_result: Arc<Mutex<TestStatus>>,
+    test_cancel: CancelToken,
+    /// Why entries or commands were skipped the last time the games were read, shared with the clones of the platform
+    last_errors: Arc<Mutex<Vec<String>>>,
+}
+
+#[derive(Default)]
+enum TestStatus {
+    #[default]
+    NotTested,
+    Testing,
+    Tested(Vec<CommandReport>),
+}
+
+/// The outcome of running one of the commands
+struct CommandReport {
+    command: String,
+    result: eyre::Result<(usize, Vec<String>)>,
+}
+
+impl NeedsProton<ExternalCommandsPlatform> for ExternalGame {
+    fn needs_proton(&self, _platform: &ExternalCommandsPlatform) -> bool {
+        self.needs_proton
+            .or_else(|| executable_needs_proton(&self.exe))
+            .unwrap_or_default()
+    }
+
+    fn create_symlinks(&self, _platform: &ExternalCommandsPlatform) -> bool {
+        false
+    }
+
+    fn metadata(&self, platform: &ExternalCommandsPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            game_id: Some(self.id.clone()),
+            store: None,
+            install_path: self.install_path.clone(),
+            install_size: self.install_size,
+            native: Some(!self.needs_proton(platform)),
+            developer: self.developer.clone(),
+            genres: self.genres.clone(),
+            cover_path: self.cover.clone(),
+        })
+    }
+}
+
+fn run_external_command(
+    command: &str,
+    timeout: Duration,
+    skipped: &mut Vec<String>,
+) -> eyre::Result<Vec<ExternalGame>> {
+    let output = run_command(&SystemRunner::default(), command, timeout)?;
+    let parsed = parse_games(&output).map_err(|err| eyre::format_err!("\"{command}\": {err}"))?;
+    for entry_error in &parsed.entry_errors {
+        eprintln!("Skipping game from \"{command}\": {entry_error}");
+        skipped.push(format!("Skipped game from \"{command}\": {entry_error}"));
+    }
+    if parsed.games.is_empty() && !parsed.entry_errors.is_empty() {
+        return Err(eyre::format_err!(
+            "\"{command}\" gave no valid games: {}",
+            parsed.entry_errors.join(", ")
+        ));
+    }
+    Ok(parsed.games)
+}
+
+impl ExternalCommandsPlatform {
+    fn commands(&self) -> Vec<String> {
+        self.settings
+            .commands
+            .iter()
+            .filter(|command| !command.trim().is_empty())
+            .cloned()
+            .collect()
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.settings.timeout_seconds)
+    }
+
+    fn get_external_games(&self) -> eyre::Result<Vec<ExternalGame>> {
+        let commands = self.commands();
+        if commands.is_empty() {
+            return Err(eyre::format_err!("No commands configured"));
+        }
+        let mut games = vec![];
+        let mut errors = vec![];
+        let mut skipped = vec![];
+        for command in commands {
+            match run_external_command(&command, self.timeout(), &mut skipped) {
+                Ok(command_games) => games.extend(command_games),
+                Err(err) => {
+                    eprintln!("{err}");
+                    errors.push(err.to_string());
+                }
+            }
+        }
+        if let Ok(mut last_errors) = self.last_errors.lock() {
+            *last_errors = errors.iter().cloned().chain(skipped).collect();
+        }
+        if games.is_empty() && !errors.is_empty() {
+            return Err(eyre::format_err!("{}", errors.join("\n")));
+        }
+        Ok(games)
+    }
+
+    fn start_test(&mut self, ctx: egui::Context) {
+        let commands = self.commands();
+        let timeout = self.timeout();
+        let test_result = self.test_result.clone();
+        //Stop a test that is still running, its result would replace the result of this one
+        self.test_cancel.cancel();
+        self.test_cancel = CancelToken::default();
+        let cancel = self.test_cancel.clone();
+        if let Ok(mut status) = test_result.lock() {
+            *status = TestStatus::Testing;
+        }
+        std::thread::spawn(move || {
+            let runner = SystemRunner::with_cancel_token(cancel.clone());
+            let reports = commands
+                .into_iter()
+                .map(|command| {
+                    let result = run_command(&runner, &command, timeout).and_then(|output| {
+                        parse_games(&output).map(|parsed| (parsed.games.len(), parsed.entry_errors))
+                    });
+                    CommandReport { command, result }
+                })
+                .collect();
+            if cancel.is_cancelled() {
+                return;
+            }
+            if let Ok(mut status) = test_result.lock() {
+                *status = TestStatus::Tested(reports);
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn render_last_errors(&self, ui: &mut egui::Ui) {
+        let Ok(last_errors) = self.last_errors.lock() else {
+            return;
+        };
+        if last_errors.is_empty() {
+            return;
+        }
+        egui::CollapsingHeader::new(format!("{} problems at the last import", last_errors.len()))
+            .id_salt("External_commands_last_errors")
+            .show(ui, |ui| {
+                for error in last_errors.iter() {
+                    ui.colored_label(ui.visuals().warn_fg_color, error);
+                }
+            });
+    }
+
+    fn render_test_result(&self, ui: &mut egui::Ui) {
+        let status = match self.test_result.lock() {
+            Ok(status) => status,
+            Err(_) => return,
+        };
+        match &*status {
+            TestStatus::NotTested => {}
+            TestStatus::Testing => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Running commands");
+                });
+            }
+            TestStatus::Tested(reports) => {
+                for report in reports {
+                    match &report.result {
+                        Ok((games, entry_errors)) => {
+                            ui.label(format!("{}: found {games} games", report.command));
+                            for entry_error in entry_errors {
+                                ui.colored_label(ui.visuals().warn_fg_color, entry_error);
+                            }
+                        }
+                        Err(err) => {
+                            ui.colored_label(ui.visuals().error_fg_color, format!("{err}"));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FromSettingsString for ExternalCommandsPlatform {
+    fn from_settings_string<S: AsRef<str>>(s: S) -> Self {
+        ExternalCommandsPlatform {
+            settings: load_settings(s),
+            test_result: Default::default(),
+            test_cancel: Default::default(),
+            last_errors: Default::default(),
+        }
+    }
+}
+
+impl GamesPlatform for ExternalCommandsPlatform {
+    fn name(&self) -> &str {
+        "External commands"
+    }
+
+    fn enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    fn get_shortcut_info(&self) -> eyre::Result<Vec<ShortcutToImport>> {
+        to_shortcuts(self, self.get_external_games())
+    }
+
+    fn render_ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("External commands");
+        ui.checkbox(
+            &mut self.settings.enabled,
+            "Import games from your own commands",
+        );
+        if self.settings.enabled {
+            ui.label("Each command must print a JSON list of games, see configuration.md for the format.");
+            let mut to_remove = None;
+            for (index, command) in self.settings.commands.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label("Command: ");
+                    ui.text_edit_singleline(command);
+                    if ui.button("Remove").clicked() {
+                        to_remove = Some(index);
+                    }
+                });
+            }
+            if let Some(index) = to_remove {
+                self.settings.commands.remove(index);
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Add command").clicked() {
+                    self.settings.commands.push(String::new());
+                }
+                if ui.button("Test commands").clicked() {
+                    self.start_test(ui.ctx().clone());
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Timeout in seconds: ");
+                ui.add(egui::DragValue::new(&mut self.settings.timeout_seconds).range(1..=600));
+            });
+            self.render_test_result(ui);
+            self.render_last_errors(ui);
+        }
+    }
+
+    fn get_settings_serializable(&self) -> String {
+        toml::to_string(&self.settings).unwrap_or_default()
+    }
+
+    fn code_name(&self) -> &str {
+        "external_commands"
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let commands = self.commands();
+        if commands.is_empty() {
+            return vec![DiagnosticCheck::message(
+                CheckStatus::Error,
+                "External commands",
+                "No commands configured",
+            )];
+        }
+        commands
+            .into_iter()
+            .map(|command| {
+                let mut skipped = vec![];
+                match run_external_command(&command, self.timeout(), &mut skipped) {
+                    Ok(games) if skipped.is_empty() => DiagnosticCheck::message(
+                        CheckStatus::Ok,
+                        format!("\"{command}\""),
+                        format!("Gave {} games", games.len()),
+                    ),
+                    Ok(games) => DiagnosticCheck::message(
+                        CheckStatus::Warning,
+                        format!("\"{command}\""),
+                        format!("Gave {} games, skipped {}", games.len(), skipped.len()),
+                    ),
+                    Err(err) => DiagnosticCheck::message(
+                        CheckStatus::Error,
+                        format!("\"{command}\""),
+                        format!("{err}"),
+                    ),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ExternalCommandsSettings {
+    pub enabled: bool,
+    /// Commands that print a JSON list of games, they are run through the shell
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// How long a command is allowed to run before BoilR gives up on it
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+fn default_timeout_seconds() -> u64 {
+    30
+}
+
+impl Default for ExternalCommandsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            commands: Default::default(),
+            timeout_seconds: default_timeout_seconds(),
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
+use crate::diagnostics::DiagnosticCheck;
 use crate::platforms::{
-    load_settings, to_shortcuts, FromSettingsString, GamesPlatform, NeedsProton, ShortcutToImport,
+    load_settings, to_shortcuts, CommandRunner, FromSettingsString, GameMetadata, GamesPlatform,
+    HostCommand, NeedsProton, ShortcutToImport, SystemRunner,
 };
 
 use super::FlatpakSettings;
@@ -33,50 +36,38 @@ impl NeedsProton<FlatpakPlatform> for FlatpakApp {
     fn create_symlinks(&self, _platform: &FlatpakPlatform) -> bool {
         false
     }
+
+    fn metadata(&self, _platform: &FlatpakPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            game_id: Some(self.id.clone()),
+            native: Some(true),
+            ..Default::default()
+        })
+    }
 }
 
 impl FlatpakPlatform {
     fn get_flatpak_apps(&self) -> eyre::Result<Vec<FlatpakApp>> {
-        let output = get_flatpak_applications()?;
-        let output_string = String::from_utf8_lossy(&output.stdout).to_string();
-        let mut result = vec![];
-        for line in output_string.lines() {
-            let mut split = line.split('\t');
-            if let Some(name) = split.next() {
-                if let Some(id) = split.next() {
-                    result.push(FlatpakApp {
-                        name: name.to_string(),
-                        id: id.to_string(),
-                    })
-                }
-            }
-        }
-        Ok(result)
+        get_flatpak_apps_with(&SystemRunner::default())
     }
 }
 
-fn get_flatpak_applications() -> std::io::Result<std::process::Output> {
-    use std::process::Command;
-    #[cfg(not(feature = "flatpak"))]
-    {
-        let mut command = Command::new("flatpak");
-        command
-            .arg("list")
-            .arg("--app")
-            .arg("--columns=name,application")
-            .output()
-    }
-    #[cfg(feature = "flatpak")]
-    {
-        let mut command = Command::new("flatpak-spawn");
-        command
-            .arg("--host")
-            .arg("flatpak")
-            .arg("list")
-            .arg("--app")
-            .arg("--columns=name,application")
-            .output()
+fn get_flatpak_apps_with(runner: &dyn CommandRunner) -> eyre::Result<Vec<FlatpakApp>> {
+    let command = HostCommand::new("flatpak").args(["list", "--app", "--columns=name,application"]);
+    let output_string = runner.stdout(&command)?;
+    let mut result = vec![];
+    for line in output_string.lines() {
+        let mut split = line.split('\t');
+        if let Some(name) = split.next() {
+            if let Some(id) = split.next() {
+                result.push(FlatpakApp {
+                    name: name.to_string(),
+                    id: id.to_string(),
+                })
+            }
+        }
     }
+    Ok(result)
 }
 
 impl FromSettingsString for FlatpakPlatform {
@@ -112,4 +103,45 @@ impl GamesPlatform for FlatpakPlatform {
     fn code_name(&self) -> &str {
         "flatpak"
     }
+
+    fn can_need_proton(&self) -> bool {
+        false
+    }
+
+    fn cache_dependencies(&self) -> Option<Vec<PathBuf>> {
+        //Installing or removing an app changes these folders
+        let home = std::env::var("HOME").ok()?;
+        Some(vec![
+            Path::new(&home).join(".local/share/flatpak/app"),
+            PathBuf::from("/var/lib/flatpak/app"),
+        ])
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        vec![DiagnosticCheck::version(
+            "Flatpak",
+            "flatpak",
+            &["--version"],
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::platforms::ReplayRunner;
+
+    #[test]
+    fn reads_apps_from_recorded_output() {
+        let runner = ReplayRunner::default().with_stdout(
+            "flatpak list --app --columns=name,application",
+            include_str!("../../testdata/flatpak/example_output.txt"),
+        );
+        let apps = get_flatpak_apps_with(&runner).unwrap();
+        assert_eq!(3, apps.len());
+        let lutris = apps.last().unwrap();
+        assert_eq!("Lutris", lutris.name);
+        assert_eq!("net.lutris.Lutris", lutris.id);
+    }
 }
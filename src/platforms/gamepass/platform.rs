@@ -1,13 +1,14 @@
 use serde::Serialize;
 
-use crate::platforms::{load_settings, FromSettingsString, GamesPlatform};
+use crate::diagnostics::DiagnosticCheck;
+use crate::platforms::{
+    load_settings, CommandRunner, FromSettingsString, GamesPlatform, HostCommand, SystemRunner,
+};
 use serde::Deserialize;
-use std::io::Error;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use steam_shortcuts_util::Shortcut;
 
-use crate::platforms::ShortcutToImport;
+use crate::platforms::{GameMetadata, ShortcutToImport};
 
 #[derive(Clone, Deserialize, Default)]
 pub struct GamePassPlatForm {
@@ -36,11 +37,8 @@ impl GamesPlatform for GamePassPlatForm {
         let command = include_str!("./game_pass_games.ps1");
         let res = run_powershell_command(command)?;
         let apps: Vec<AppInfo> = serde_json::from_str(&res)?;
-        let windows_dir = std::env::var("WinDir").unwrap_or("C:\\Windows".to_string());
-        let explorer = Path::new(&windows_dir)
-            .join("explorer.exe")
-            .to_string_lossy()
-            .to_string();
+        let windows_dir = windows_dir();
+        let explorer = explorer_path().to_string_lossy().to_string();
 
         let name_getters: [fn(&AppInfo) -> eyre::Result<String>; 3] =
             [get_name_from_game, get_name_from_config, get_name_from_xml];
@@ -70,6 +68,12 @@ impl GamesPlatform for GamePassPlatForm {
                             shortcut: shortcut.to_owned(),
                             needs_proton: false,
                             needs_symlinks: false,
+                            metadata: Some(GameMetadata {
+                                game_id: Some(game.family_name.clone()),
+                                install_path: Some(game.install_location.clone()),
+                                native: Some(true),
+                                ..Default::default()
+                            }),
                         }
                     })
             });
@@ -85,6 +89,26 @@ impl GamesPlatform for GamePassPlatForm {
         ui.heading("Game Pass");
         ui.checkbox(&mut self.settings.enabled, "Import from Game Pass");
     }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        vec![
+            DiagnosticCheck::version(
+                "PowerShell",
+                "powershell",
+                &["/c", "$PSVersionTable.PSVersion.ToString()"],
+            ),
+            DiagnosticCheck::path("Explorer", explorer_path(), true),
+        ]
+    }
+}
+
+fn windows_dir() -> String {
+    std::env::var("WinDir").unwrap_or("C:\\Windows".to_string())
+}
+
+/// Game Pass games are started through explorer, since they can only be launched by their app id
+fn explorer_path() -> PathBuf {
+    Path::new(&windows_dir()).join("explorer.exe")
 }
 
 fn get_name_from_xml(app_info: &AppInfo) -> eyre::Result<String> {
@@ -169,16 +193,9 @@ impl Kind {
     }
 }
 
-fn run_powershell_command(cmd: &str) -> Result<String, Error> {
-    let output = Command::new("powershell").arg("/c").arg(cmd).output()?;
-
-    match output.status.success() {
-        true => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
-        false => Err(Error::new(
-            std::io::ErrorKind::Other,
-            String::from_utf8_lossy(&output.stderr).to_string(),
-        )),
-    }
+fn run_powershell_command(cmd: &str) -> eyre::Result<String> {
+    let command = HostCommand::new("powershell").arg("/c").arg(cmd);
+    SystemRunner::default().checked_stdout(&command)
 }
 
 impl FromSettingsString for GamePassPlatForm {
@@ -1,7 +1,9 @@
 use std::path::{Path, PathBuf};
 
+use crate::diagnostics::DiagnosticCheck;
 use crate::platforms::{
-    load_settings, to_shortcuts, FromSettingsString, GamesPlatform, NeedsProton, ShortcutToImport,
+    load_settings, to_shortcuts, FromSettingsString, GameMetadata, GamesPlatform, NeedsProton,
+    ShortcutToImport,
 };
 
 use super::{
@@ -16,13 +18,16 @@ pub struct GogPlatform {
 }
 
 impl GogPlatform {
-    fn get_shortcuts(&self) -> eyre::Result<Vec<GogShortcut>> {
-        let gog_location = self
-            .settings
+    fn gog_location(&self) -> PathBuf {
+        self.settings
             .location
             .as_ref()
             .map(|location| Path::new(&location).to_path_buf())
-            .unwrap_or_else(default_location);
+            .unwrap_or_else(default_location)
+    }
+
+    fn get_shortcuts(&self) -> eyre::Result<Vec<GogShortcut>> {
+        let gog_location = self.gog_location();
         if !gog_location.exists() {
             return Err(eyre::format_err!("Could not find path: {:?}", gog_location));
         }
@@ -37,10 +42,11 @@ impl GogPlatform {
     }
 }
 
-fn get_shortcuts_from_config(
+/// The folders games are installed to, as paths that can be read from this system
+fn get_game_locations(
     _wine_c_drive: Option<String>,
     config_path: PathBuf,
-) -> eyre::Result<Vec<GogShortcut>> {
+) -> eyre::Result<Vec<String>> {
     let install_locations = get_install_locations(config_path)?;
     #[cfg(target_family = "unix")]
     let install_locations = if let Some(wine_c_drive) = &_wine_c_drive {
@@ -48,6 +54,14 @@ fn get_shortcuts_from_config(
     } else {
         install_locations
     };
+    Ok(install_locations)
+}
+
+fn get_shortcuts_from_config(
+    wine_c_drive: Option<String>,
+    config_path: PathBuf,
+) -> eyre::Result<Vec<GogShortcut>> {
+    let install_locations = get_game_locations(wine_c_drive, config_path)?;
     let mut game_folders = vec![];
     for install_location in install_locations {
         let path = Path::new(&install_location);
@@ -166,7 +180,7 @@ fn get_games_from_game_folders(game_folders: Vec<PathBuf>) -> Vec<(GogGame, Path
 impl NeedsProton<GogPlatform> for GogShortcut {
     #[cfg(target_family = "unix")]
     fn needs_proton(&self, _platform: &GogPlatform) -> bool {
-        true
+        crate::platforms::executable_needs_proton(&self.path).unwrap_or(true)
     }
 
     #[cfg(not(target_family = "unix"))]
@@ -183,6 +197,16 @@ impl NeedsProton<GogPlatform> for GogShortcut {
     fn create_symlinks(&self, platform: &GogPlatform) -> bool {
         platform.settings.create_symlinks
     }
+
+    fn metadata(&self, _platform: &GogPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            game_id: Some(self.game_id.clone()),
+            store: Some("gog".to_string()),
+            install_path: Some(self.game_folder.clone()),
+            native: Some(cfg!(target_os = "windows")),
+            ..Default::default()
+        })
+    }
 }
 
 #[cfg(target_family = "unix")]
@@ -267,4 +291,22 @@ impl GamesPlatform for GogPlatform {
     fn code_name(&self) -> &str {
         "gog"
     }
+
+    fn cache_dependencies(&self) -> Option<Vec<PathBuf>> {
+        let config_path = self.gog_location().join("config.json");
+        let mut dependencies = vec![config_path.clone()];
+        //Adding or removing a game changes the install folder
+        if let Ok(locations) = get_game_locations(self.settings.wine_c_drive.clone(), config_path) {
+            dependencies.extend(locations.into_iter().map(PathBuf::from));
+        }
+        Some(dependencies)
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let gog_location = self.gog_location();
+        vec![
+            DiagnosticCheck::path("GOG Galaxy folder", &gog_location, true),
+            DiagnosticCheck::path("GOG Galaxy config", gog_location.join("config.json"), true),
+        ]
+    }
 }
@@ -10,6 +10,11 @@ pub struct HeroicGame {
     pub install_path: String,
     pub executable: String,
     pub launch_parameters: String,
+    #[serde(default)]
+    pub install_size: Option<u64>,
+    /// The platform the game is built for, fx "Windows" or "Mac"
+    #[serde(default)]
+    pub platform: Option<String>,
 }
 
 impl HeroicGame {
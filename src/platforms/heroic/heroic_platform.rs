@@ -1,8 +1,12 @@
 use serde::Deserialize;
 
 use super::{HeroicGame, HeroicGameType, HeroicSettings};
-use crate::platforms::{load_settings, FromSettingsString, GamesPlatform};
-use crate::platforms::{to_shortcuts, NeedsProton, ShortcutToImport};
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+use crate::platforms::legendary::is_native_platform;
+use crate::platforms::{
+    executable_needs_proton, load_settings, to_shortcuts, FromSettingsString, GameMetadata,
+    GamesPlatform, NeedsProton, ShortcutToImport,
+};
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -95,8 +99,14 @@ impl NeedsProton<HeroicPlatform> for HeroicGameType {
     #[cfg(target_family = "unix")]
     fn needs_proton(&self, _platform: &HeroicPlatform) -> bool {
         match self {
-            HeroicGameType::Epic(_game) => true,
-            HeroicGameType::Gog(_, is_windows) => *is_windows,
+            HeroicGameType::Epic(game) => {
+                let exe = Path::new(&game.install_path).join(&game.executable);
+                executable_needs_proton(exe.to_string_lossy())
+                    .unwrap_or_else(|| !game.platform.as_deref().is_some_and(is_native_platform))
+            }
+            HeroicGameType::Gog(game, is_windows) => {
+                executable_needs_proton(&game.path).unwrap_or(*is_windows)
+            }
             HeroicGameType::Heroic { .. } => false,
         }
     }
@@ -104,6 +114,31 @@ impl NeedsProton<HeroicPlatform> for HeroicGameType {
     fn create_symlinks(&self, _platform: &HeroicPlatform) -> bool {
         false
     }
+
+    fn metadata(&self, _platform: &HeroicPlatform) -> Option<GameMetadata> {
+        let metadata = match self {
+            HeroicGameType::Epic(game) => GameMetadata {
+                game_id: Some(game.app_name.clone()),
+                store: Some("egs".to_string()),
+                install_path: Some(game.install_path.clone()),
+                install_size: game.install_size,
+                native: game.platform.as_deref().map(is_native_platform),
+                ..Default::default()
+            },
+            HeroicGameType::Gog(game, is_windows) => GameMetadata {
+                game_id: Some(game.game_id.clone()),
+                store: Some("gog".to_string()),
+                install_path: Some(game.game_folder.clone()),
+                native: Some(*is_windows == cfg!(target_os = "windows")),
+                ..Default::default()
+            },
+            HeroicGameType::Heroic { app_name, .. } => GameMetadata {
+                game_id: Some(app_name.clone()),
+                ..Default::default()
+            },
+        };
+        Some(metadata)
+    }
 }
 
 impl HeroicPlatform {
@@ -280,4 +315,40 @@ impl GamesPlatform for HeroicPlatform {
     fn code_name(&self) -> &str {
         "heroic"
     }
+
+    fn cache_dependencies(&self) -> Option<Vec<PathBuf>> {
+        let mut dependencies = vec![];
+        for install_mode in [InstallationMode::FlatPak, InstallationMode::UserBin] {
+            dependencies.push(get_installed_json_location(&install_mode));
+            dependencies.push(get_gog_installed_location(&install_mode));
+        }
+        Some(dependencies)
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let mut checks = vec![];
+        for (install_mode, mode_name) in [
+            (InstallationMode::FlatPak, "flatpak"),
+            (InstallationMode::UserBin, "native"),
+        ] {
+            checks.push(DiagnosticCheck::path(
+                format!("Heroic Epic games ({mode_name})"),
+                get_installed_json_location(&install_mode),
+                false,
+            ));
+            checks.push(DiagnosticCheck::path(
+                format!("Heroic GOG games ({mode_name})"),
+                get_gog_installed_location(&install_mode),
+                false,
+            ));
+        }
+        if checks.iter().all(|check| check.status != CheckStatus::Ok) {
+            checks.push(DiagnosticCheck::message(
+                CheckStatus::Error,
+                "Heroic",
+                "No installed.json found, install a game with Heroic first",
+            ));
+        }
+        checks
+    }
 }
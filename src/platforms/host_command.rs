@@ -0,0 +1,428 @@
+use std::cell::RefCell;
+#[cfg(test)]
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a command may run before it is killed, unless the command says otherwise
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Variables that BoilR might have been started with (fx from an AppImage or the Steam overlay),
+/// that break other programs if they inherit them
+const SANITIZED_ENV: [&str; 4] = ["LD_PRELOAD", "LD_LIBRARY_PATH", "PYTHONHOME", "PYTHONPATH"];
+
+/// A command that is run on the host, outside of the flatpak sandbox if BoilR runs in one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostCommand {
+    program: String,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+impl HostCommand {
+    pub fn new<S: Into<String>>(program: S) -> Self {
+        Self {
+            program: program.into(),
+            args: vec![],
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// A command line that is run through the shell
+    #[cfg(target_family = "unix")]
+    pub fn shell<S: Into<String>>(command_line: S) -> Self {
+        Self::new("sh").arg("-c").arg(command_line)
+    }
+
+    /// A command line that is run through the shell
+    #[cfg(target_os = "windows")]
+    pub fn shell<S: Into<String>>(command_line: S) -> Self {
+        Self::new("cmd").arg("/C").arg(command_line)
+    }
+
+    pub fn arg<S: Into<String>>(mut self, arg: S) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Runs the command with the system runner
+    pub fn output(&self) -> eyre::Result<CommandOutput> {
+        SystemRunner::default().run(self)
+    }
+
+    /// Starts the command without waiting for it, for programs that keep running like Steam.
+    /// A caller that waits for the program to exit reaps the returned child, so it does not linger as a zombie.
+    pub fn spawn(&self) -> eyre::Result<Child> {
+        self.to_command(None, false)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| eyre::format_err!("Could not start \"{self}\": {err}"))
+    }
+
+    /// Builds the command, if it is `stoppable` it can be killed together with everything it starts
+    #[cfg_attr(target_os = "windows", allow(unused_variables))]
+    fn to_command(&self, environment: Option<&[(String, String)]>, stoppable: bool) -> Command {
+        #[cfg(not(feature = "flatpak"))]
+        let mut command = {
+            let mut command = Command::new(&self.program);
+            command.args(&self.args);
+            command
+        };
+        #[cfg(feature = "flatpak")]
+        let mut command = {
+            let mut command = Command::new("flatpak-spawn");
+            command.arg("--host");
+            if stoppable {
+                //The command runs outside of the sandbox, so killing flatpak-spawn only reaches it through the bus
+                command.arg("--watch-bus");
+            }
+            command.arg(&self.program).args(&self.args);
+            command
+        };
+        #[cfg(target_family = "unix")]
+        if stoppable {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        if let Some(environment) = environment {
+            command.env_clear();
+            command.envs(environment.iter().map(|(key, value)| (key, value)));
+        }
+        for key in SANITIZED_ENV {
+            command.env_remove(key);
+        }
+        command
+    }
+}
+
+impl std::fmt::Display for HostCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            if arg.contains(' ') {
+                write!(f, " \"{arg}\"")?;
+            } else {
+                write!(f, " {arg}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a command printed and how it exited
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// The exit code, None if the command was stopped by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Runs host commands, this is what lets platforms be tested against recorded output
+pub trait CommandRunner {
+    /// Runs the command, it is not an error if the command exits with an error code
+    fn run(&self, command: &HostCommand) -> eyre::Result<CommandOutput>;
+
+    /// Runs the command and returns what it printed to stdout, even if it exits with an error code
+    fn stdout(&self, command: &HostCommand) -> eyre::Result<String> {
+        let output = self.run(command)?;
+        if !output.success() {
+            eprintln!(
+                "\"{command}\" exited with an error: {}",
+                output.stderr.trim()
+            );
+        }
+        Ok(output.stdout)
+    }
+
+    /// Runs the command and returns what it printed to stdout,
+    /// what it printed to stderr is part of the error if it fails
+    fn checked_stdout(&self, command: &HostCommand) -> eyre::Result<String> {
+        let output = self.run(command)?;
+        if output.success() {
+            Ok(output.stdout)
+        } else {
+            let exit_code = output
+                .exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_else(|| "a signal".to_string());
+            Err(eyre::format_err!(
+                "\"{command}\" failed with {exit_code}: {}",
+                output.stderr.trim()
+            ))
+        }
+    }
+}
+
+/// Stops the commands of a runner, fx when the result is no longer needed
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Runs the function with this token,
+    /// the system runners created by the function are stopped when the token is cancelled
+    pub fn run_with<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT_CANCEL_TOKEN.with(|current| current.replace(Some(self.clone())));
+        let result = f();
+        CURRENT_CANCEL_TOKEN.with(|current| current.replace(previous));
+        result
+    }
+
+    fn current() -> Self {
+        CURRENT_CANCEL_TOKEN
+            .with(|current| current.borrow().clone())
+            .unwrap_or_default()
+    }
+}
+
+thread_local! {
+    static CURRENT_CANCEL_TOKEN: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// Runs commands for real
+#[derive(Debug, Clone)]
+pub struct SystemRunner {
+    cancel: CancelToken,
+    /// Replaces the environment BoilR was started with, for tests
+    environment: Option<Vec<(String, String)>>,
+}
+
+impl Default for SystemRunner {
+    /// A runner that is stopped by the token of [`CancelToken::run_with`], if it is made inside it
+    fn default() -> Self {
+        Self::with_cancel_token(CancelToken::current())
+    }
+}
+
+impl SystemRunner {
+    pub fn with_cancel_token(cancel: CancelToken) -> Self {
+        Self {
+            cancel,
+            environment: None,
+        }
+    }
+
+    #[cfg(all(test, target_family = "unix", not(feature = "flatpak")))]
+    fn with_environment(mut self, environment: &[(&str, &str)]) -> Self {
+        self.environment = Some(
+            environment
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        );
+        self
+    }
+}
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, command: &HostCommand) -> eyre::Result<CommandOutput> {
+        let mut child = command
+            .to_command(self.environment.as_deref(), true)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| eyre::format_err!("Could not start \"{command}\": {err}"))?;
+
+        //Read the output on other threads, so a command that prints a lot does not block on a full pipe
+        let stdout = child.stdout.take().map(read_in_background);
+        let stderr = child.stderr.take().map(read_in_background);
+
+        let start = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if self.cancel.is_cancelled() {
+                kill_process_group(&mut child);
+                return Err(eyre::format_err!("\"{command}\" was cancelled"));
+            }
+            if start.elapsed() > command.timeout {
+                kill_process_group(&mut child);
+                return Err(eyre::format_err!(
+                    "\"{command}\" did not finish within {} seconds",
+                    command.timeout.as_secs()
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        };
+
+        let stdout = stdout
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default();
+        let stderr = stderr
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default();
+        Ok(CommandOutput {
+            exit_code: status.code(),
+            stdout,
+            stderr,
+        })
+    }
+}
+
+/// Kills the command and what it started, a shell would otherwise leave the programs it runs behind.
+/// The command is the leader of its own process group, see [`HostCommand::to_command`].
+fn kill_process_group(child: &mut Child) {
+    #[cfg(target_family = "unix")]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: kill only sends a signal, a negative pid sends it to the process group
+        unsafe { libc::kill(-pid, libc::SIGKILL) };
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn read_in_background<R: Read + Send + 'static>(mut reader: R) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buffer = vec![];
+        let _ = reader.read_to_end(&mut buffer);
+        String::from_utf8_lossy(&buffer).to_string()
+    })
+}
+
+/// Answers commands with recorded output instead of running them, for tests
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct ReplayRunner {
+    outputs: HashMap<String, CommandOutput>,
+}
+
+#[cfg(test)]
+impl ReplayRunner {
+    /// Records the output for a command, the command is matched on how it is displayed
+    pub fn with_output<S: Into<String>>(mut self, command: S, output: CommandOutput) -> Self {
+        self.outputs.insert(command.into(), output);
+        self
+    }
+
+    pub fn with_stdout<S: Into<String>, O: Into<String>>(self, command: S, stdout: O) -> Self {
+        self.with_output(
+            command,
+            CommandOutput {
+                exit_code: Some(0),
+                stdout: stdout.into(),
+                stderr: String::new(),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for ReplayRunner {
+    fn run(&self, command: &HostCommand) -> eyre::Result<CommandOutput> {
+        self.outputs
+            .get(&command.to_string())
+            .cloned()
+            .ok_or_else(|| eyre::format_err!("No recorded output for \"{command}\""))
+    }
+}
+
+#[cfg(all(test, target_family = "unix", not(feature = "flatpak")))]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn cancelled_commands_are_stopped() {
+        let cancel = CancelToken::default();
+        cancel.cancel();
+        let runner = SystemRunner::with_cancel_token(cancel);
+        let err = runner.run(&HostCommand::shell("sleep 5")).unwrap_err();
+        assert!(err.to_string().contains("cancelled"));
+    }
+
+    #[test]
+    fn commands_made_inside_a_token_are_cancelled() {
+        let cancel = CancelToken::default();
+        cancel.cancel();
+        let err = cancel
+            .run_with(|| HostCommand::shell("sleep 5").output())
+            .unwrap_err();
+        assert!(err.to_string().contains("cancelled"));
+        assert!(HostCommand::shell("true").output().is_ok());
+    }
+
+    #[test]
+    fn programs_started_by_a_command_are_stopped() {
+        let pid_file =
+            std::env::temp_dir().join(format!("boilr-host-command-{}", std::process::id()));
+        let command = HostCommand::shell(format!(
+            "sleep 30 & echo $! > \"{}\"; wait",
+            pid_file.to_string_lossy()
+        ))
+        .timeout(Duration::from_millis(500));
+        assert!(SystemRunner::default().run(&command).is_err());
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        std::fs::remove_file(&pid_file).unwrap();
+        //The sleep is gone or a zombie waiting to be reaped once the kill has been delivered
+        let stopped = (0..20).any(|_| {
+            std::thread::sleep(Duration::from_millis(50));
+            let state =
+                std::fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+            state.is_empty() || state.contains(") Z ")
+        });
+        assert!(stopped);
+    }
+
+    #[test]
+    fn environment_is_sanitized() {
+        let runner = SystemRunner::default().with_environment(&[
+            ("PATH", "/usr/bin:/bin"),
+            ("PYTHONHOME", "/tmp/appimage/usr"),
+            ("HOME", "/home/deck"),
+        ]);
+        let command = HostCommand::shell("echo \"$HOME:${PYTHONHOME}\"");
+        let output = runner.stdout(&command).unwrap();
+        assert_eq!("/home/deck:", output.trim());
+    }
+
+    #[test]
+    fn stdout_is_kept_when_a_command_fails() {
+        let command = HostCommand::shell("echo '[]'; exit 1");
+        let runner = SystemRunner::default();
+        assert_eq!("[]", runner.stdout(&command).unwrap().trim());
+        assert!(runner.checked_stdout(&command).is_err());
+    }
+
+    #[test]
+    fn replay_matches_displayed_command() {
+        let runner = ReplayRunner::default().with_stdout("lutris --json -lo", "[]");
+        let command = HostCommand::new("lutris").args(["--json", "-lo"]);
+        assert_eq!("[]", runner.stdout(&command).unwrap());
+        assert!(runner.stdout(&HostCommand::new("lutris")).is_err());
+    }
+}
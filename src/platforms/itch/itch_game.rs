@@ -3,7 +3,7 @@ use std::path::Path;
 use serde::{Deserialize, Serialize};
 use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
 
-use crate::platforms::NeedsProton;
+use crate::platforms::{executable_needs_proton, has_windows_extension, GameMetadata, NeedsProton};
 
 use super::ItchPlatform;
 
@@ -13,6 +13,12 @@ pub struct ItchGame {
     pub install_path: String,
     pub executable: String,
     pub title: String,
+    #[serde(default)]
+    pub game_id: Option<u64>,
+    #[serde(default)]
+    pub developer: Option<String>,
+    #[serde(default)]
+    pub install_size: Option<u64>,
 }
 
 impl From<ItchGame> for ShortcutOwned {
@@ -38,9 +44,18 @@ impl From<ItchGame> for ShortcutOwned {
     }
 }
 
+impl ItchGame {
+    fn runs_with_proton(&self) -> bool {
+        let exe = Path::new(&self.install_path).join(&self.executable);
+        executable_needs_proton(exe.to_string_lossy()).unwrap_or_else(|| {
+            cfg!(target_family = "unix") && has_windows_extension(&self.executable)
+        })
+    }
+}
+
 impl NeedsProton<ItchPlatform> for ItchGame {
     fn needs_proton(&self, _platform: &ItchPlatform) -> bool {
-        self.executable.ends_with("exe")
+        self.runs_with_proton()
     }
 
     #[cfg(target_family = "unix")]
@@ -52,4 +67,15 @@ impl NeedsProton<ItchPlatform> for ItchGame {
     fn create_symlinks(&self, _platform: &ItchPlatform) -> bool {
         false
     }
+
+    fn metadata(&self, _platform: &ItchPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            game_id: self.game_id.map(|id| id.to_string()),
+            install_path: Some(self.install_path.clone()),
+            install_size: self.install_size,
+            native: Some(!self.runs_with_proton()),
+            developer: self.developer.clone(),
+            ..Default::default()
+        })
+    }
 }
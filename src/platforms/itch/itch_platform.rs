@@ -2,6 +2,7 @@ use super::butler_db_parser::*;
 use super::itch_game::ItchGame;
 use super::receipt::Receipt;
 use super::ItchSettings;
+use crate::diagnostics::DiagnosticCheck;
 use crate::platforms::{
     load_settings, to_shortcuts, FromSettingsString, GamesPlatform, ShortcutToImport,
 };
@@ -9,7 +10,7 @@ use flate2::read::GzDecoder;
 use is_executable::IsExecutable;
 use std::collections::HashSet;
 use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Clone)]
 pub struct ItchPlatform {
@@ -17,9 +18,13 @@ pub struct ItchPlatform {
 }
 
 impl ItchPlatform {
-    fn get_itch_games(&self) -> eyre::Result<Vec<ItchGame>> {
+    fn itch_location(&self) -> String {
         let itch_location = self.settings.location.clone();
-        let itch_location = itch_location.unwrap_or_else(get_default_location);
+        itch_location.unwrap_or_else(get_default_location)
+    }
+
+    fn get_itch_games(&self) -> eyre::Result<Vec<ItchGame>> {
+        let itch_location = self.itch_location();
 
         let itch_db_location = Path::new(&itch_location).join("db").join("butler.db-wal");
         if !itch_db_location.exists() {
@@ -64,10 +69,20 @@ fn dbpath_to_game(paths: &DbPaths) -> Option<ItchGame> {
                 let mut s = String::new();
                 if d.read_to_string(&mut s).is_ok() {
                     let receipt_op: Option<Receipt> = serde_json::from_str(&s).ok();
-                    return receipt_op.map(|re| ItchGame {
-                        install_path: paths.base_path.to_owned(),
-                        executable: executable.to_owned(),
-                        title: re.game.title,
+                    return receipt_op.map(|re| {
+                        let developer = re.game.user.and_then(|user| {
+                            user.display_name
+                                .filter(|name| !name.is_empty())
+                                .or(user.username)
+                        });
+                        ItchGame {
+                            install_path: paths.base_path.to_owned(),
+                            executable: executable.to_owned(),
+                            title: re.game.title,
+                            game_id: re.game.id,
+                            developer,
+                            install_size: re.upload.and_then(|upload| upload.size),
+                        }
                     });
                 }
             }
@@ -154,4 +169,23 @@ impl GamesPlatform for ItchPlatform {
     fn code_name(&self) -> &str {
         "itch"
     }
+
+    fn cache_dependencies(&self) -> Option<Vec<PathBuf>> {
+        let itch_location = self.itch_location();
+        Some(vec![Path::new(&itch_location)
+            .join("db")
+            .join("butler.db-wal")])
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let itch_location = self.itch_location();
+        vec![
+            DiagnosticCheck::path("Itch folder", &itch_location, true),
+            DiagnosticCheck::path(
+                "Itch database",
+                Path::new(&itch_location).join("db").join("butler.db-wal"),
+                true,
+            ),
+        ]
+    }
 }
@@ -3,9 +3,29 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct Receipt {
     pub game: Game,
+    #[serde(default)]
+    pub upload: Option<Upload>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct Game {
     pub title: String,
+    #[serde(default)]
+    pub id: Option<u64>,
+    #[serde(default)]
+    pub user: Option<User>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct User {
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(alias = "displayName", default)]
+    pub display_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Upload {
+    #[serde(default)]
+    pub size: Option<u64>,
 }
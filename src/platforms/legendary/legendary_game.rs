@@ -9,6 +9,16 @@ pub struct LegendaryGame {
     pub is_dlc: bool,
     pub install_path: String,
     pub executable: String,
+    #[serde(default)]
+    pub install_size: Option<u64>,
+    /// The platform the game is built for, fx "Windows" or "Mac"
+    #[serde(default)]
+    pub platform: Option<String>,
+}
+
+/// If a game built for the platform (as legendary names it) runs without Proton on this system
+pub(crate) fn is_native_platform(platform: &str) -> bool {
+    platform.eq_ignore_ascii_case("windows") == cfg!(target_os = "windows")
 }
 
 impl From<LegendaryGame> for ShortcutOwned {
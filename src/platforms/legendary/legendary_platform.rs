@@ -1,10 +1,12 @@
-use super::legendary_game::LegendaryGame;
+use super::legendary_game::{is_native_platform, LegendaryGame};
 use super::LegendarySettings;
+use crate::diagnostics::DiagnosticCheck;
 use crate::platforms::{
-    load_settings, to_shortcuts_simple, FromSettingsString, GamesPlatform, ShortcutToImport,
+    load_settings, to_shortcuts, CommandRunner, FromSettingsString, GameMetadata, GamesPlatform,
+    HostCommand, NeedsProton, ShortcutToImport, SystemRunner,
 };
 use serde_json::from_str;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 
 #[derive(Clone)]
 pub struct LegendaryPlatform {
@@ -12,27 +14,52 @@ pub struct LegendaryPlatform {
 }
 
 impl LegendaryPlatform {
-    fn get_shortcuts(&self) -> eyre::Result<Vec<LegendaryGame>> {
-        let legendary_string = self
-            .settings
+    fn legendary_executable(&self) -> String {
+        self.settings
             .executable
             .clone()
-            .unwrap_or_else(|| "legendary".to_string());
+            .unwrap_or_else(|| "legendary".to_string())
+    }
+
+    fn get_shortcuts(&self) -> eyre::Result<Vec<LegendaryGame>> {
+        let legendary_string = self.legendary_executable();
         let legendary = legendary_string.as_str();
-        execute_legendary_command(legendary)
+        execute_legendary_command(legendary, &SystemRunner::default())
     }
 }
 
-fn execute_legendary_command(program: &str) -> eyre::Result<Vec<LegendaryGame>> {
-    let legendary_command = Command::new(program)
-        .arg("list-installed")
-        .arg("--json")
-        .output()?;
-    let json = String::from_utf8_lossy(&legendary_command.stdout);
+fn execute_legendary_command(
+    program: &str,
+    runner: &dyn CommandRunner,
+) -> eyre::Result<Vec<LegendaryGame>> {
+    let command = HostCommand::new(program).args(["list-installed", "--json"]);
+    let json = runner.stdout(&command)?;
     let games = from_str(&json)?;
     Ok(games)
 }
 
+impl NeedsProton<LegendaryPlatform> for LegendaryGame {
+    //Legendary sets up wine itself when it launches a game
+    fn needs_proton(&self, _platform: &LegendaryPlatform) -> bool {
+        false
+    }
+
+    fn create_symlinks(&self, _platform: &LegendaryPlatform) -> bool {
+        false
+    }
+
+    fn metadata(&self, _platform: &LegendaryPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            game_id: Some(self.app_name.clone()),
+            store: Some("egs".to_string()),
+            install_path: Some(self.install_path.clone()),
+            install_size: self.install_size,
+            native: self.platform.as_deref().map(is_native_platform),
+            ..Default::default()
+        })
+    }
+}
+
 impl GamesPlatform for LegendaryPlatform {
     fn name(&self) -> &str {
         "Legendary"
@@ -43,7 +70,7 @@ impl GamesPlatform for LegendaryPlatform {
     }
 
     fn get_shortcut_info(&self) -> eyre::Result<Vec<ShortcutToImport>> {
-        to_shortcuts_simple(self.get_shortcuts())
+        to_shortcuts(self, self.get_shortcuts())
     }
 
     fn render_ui(&mut self, ui: &mut egui::Ui) {
@@ -73,6 +100,26 @@ impl GamesPlatform for LegendaryPlatform {
     fn code_name(&self) -> &str {
         "legendary"
     }
+
+    fn can_need_proton(&self) -> bool {
+        false
+    }
+
+    fn cache_dependencies(&self) -> Option<Vec<PathBuf>> {
+        let config_folder = match std::env::var("LEGENDARY_CONFIG_PATH") {
+            Ok(config_folder) => PathBuf::from(config_folder),
+            Err(_) => Path::new(&std::env::var("HOME").ok()?).join(".config/legendary"),
+        };
+        Some(vec![config_folder.join("installed.json")])
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        vec![DiagnosticCheck::version(
+            "Legendary",
+            &self.legendary_executable(),
+            &["--version"],
+        )]
+    }
 }
 
 impl FromSettingsString for LegendaryPlatform {
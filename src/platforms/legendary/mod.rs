@@ -2,5 +2,6 @@ mod legendary_game;
 mod legendary_platform;
 mod settings;
 
+pub(crate) use legendary_game::is_native_platform;
 pub use legendary_platform::LegendaryPlatform;
 pub use settings::LegendarySettings;
@@ -11,12 +11,24 @@ pub struct LutrisGame {
     pub name: String,
     pub service: Option<String>,
     pub runner: Option<String>,
-    // pub platform: String,
+    #[serde(default)]
+    pub platform: Option<String>,
     // pub installed: bool,
-    // pub details: String,
+    /// What the service (fx gog) knows about the game, as a json string
+    #[serde(default)]
+    pub details: Option<String>,
+    /// The folder the game is installed in, only listed for installed games
+    #[serde(default)]
+    pub directory: Option<String>,
     pub settings: Option<LutrisSettings>,
 }
 
+#[derive(Deserialize)]
+struct ServiceDetails {
+    #[serde(default)]
+    category: Option<String>,
+}
+
 impl From<LutrisGame> for ShortcutOwned {
     fn from(game: LutrisGame) -> Self {
         let options = game.get_options();
@@ -35,6 +47,13 @@ impl From<LutrisGame> for ShortcutOwned {
 }
 
 impl LutrisGame {
+    /// The genre the service puts the game in
+    pub fn category(&self) -> Option<String> {
+        let details = self.details.as_ref()?;
+        let details: ServiceDetails = serde_json::from_str(details).ok()?;
+        details.category.filter(|category| !category.is_empty())
+    }
+
     pub fn get_options(&self) -> String {
         let is_flatpak = self
             .settings
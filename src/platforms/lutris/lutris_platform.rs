@@ -1,10 +1,13 @@
 use super::game_list_parser::parse_lutris_games;
 use super::lutris_game::LutrisGame;
 use super::settings::LutrisSettings;
+use std::path::{Path, PathBuf};
+
+use crate::diagnostics::DiagnosticCheck;
 use crate::platforms::{
-    load_settings, to_shortcuts_simple, FromSettingsString, GamesPlatform, ShortcutToImport,
+    load_settings, to_shortcuts, CommandRunner, FromSettingsString, GameMetadata, GamesPlatform,
+    HostCommand, NeedsProton, ShortcutToImport, SystemRunner,
 };
-use std::process::Command;
 
 #[derive(Clone)]
 pub struct LutrisPlatform {
@@ -13,7 +16,11 @@ pub struct LutrisPlatform {
 
 impl LutrisPlatform {
     fn get_shortcuts(&self) -> eyre::Result<Vec<LutrisGame>> {
-        let output = get_lutris_command_output(&self.settings)?;
+        self.get_shortcuts_with(&SystemRunner::default())
+    }
+
+    fn get_shortcuts_with(&self, runner: &dyn CommandRunner) -> eyre::Result<Vec<LutrisGame>> {
+        let output = runner.stdout(&lutris_command(&self.settings))?;
         let games = parse_lutris_games(output.as_str());
         let installed = self.settings.installed;
         let mut res = vec![];
@@ -26,47 +33,61 @@ impl LutrisPlatform {
         }
         Ok(res)
     }
+
+    fn data_folder(&self) -> Option<PathBuf> {
+        let home = std::env::var("HOME").ok()?;
+        let data_folder = if self.settings.flatpak {
+            Path::new(&home)
+                .join(".var/app")
+                .join(&self.settings.flatpak_image)
+                .join("data/lutris")
+        } else {
+            Path::new(&home).join(".local/share/lutris")
+        };
+        Some(data_folder)
+    }
 }
 
-fn get_lutris_command_output(settings: &LutrisSettings) -> eyre::Result<String> {
-    let output = if settings.flatpak {
-        let flatpak_image = &settings.flatpak_image;
-        #[cfg(not(feature = "flatpak"))]
-        {
-            let mut command = Command::new("flatpak");
-            command.arg("run").arg(flatpak_image).arg("--json");
-            if settings.installed {
-                command.arg("-lo").output()?
-            } else {
-                command.arg("-a").output()?
-            }
-        }
-        #[cfg(feature = "flatpak")]
-        {
-            let mut command = Command::new("flatpak-spawn");
-            command
-                .arg("--host")
-                .arg("flatpak")
-                .arg("run")
-                .arg(flatpak_image);
-            command.arg("run").arg(flatpak_image).arg("--json");
-            if settings.installed {
-                command.arg("-lo").output()?
-            } else {
-                command.arg("-a").output()?
-            }
-        }
+impl NeedsProton<LutrisPlatform> for LutrisGame {
+    //Lutris runs the game with the runner it is set up with
+    fn needs_proton(&self, _platform: &LutrisPlatform) -> bool {
+        false
+    }
+
+    fn create_symlinks(&self, _platform: &LutrisPlatform) -> bool {
+        false
+    }
+
+    fn metadata(&self, platform: &LutrisPlatform) -> Option<GameMetadata> {
+        let cover_path = platform
+            .data_folder()
+            .map(|folder| folder.join("coverart").join(format!("{}.jpg", self.slug)))
+            .filter(|cover| cover.exists())
+            .map(|cover| cover.to_string_lossy().to_string());
+        Some(GameMetadata {
+            game_id: Some(self.slug.clone()),
+            install_path: self.directory.clone().filter(|d| !d.is_empty()),
+            native: self
+                .platform
+                .as_deref()
+                .map(|platform| !platform.eq_ignore_ascii_case("windows")),
+            genres: self.category().into_iter().collect(),
+            cover_path,
+            ..Default::default()
+        })
+    }
+}
+
+fn lutris_command(settings: &LutrisSettings) -> HostCommand {
+    let command = if settings.flatpak {
+        HostCommand::new("flatpak")
+            .arg("run")
+            .arg(&settings.flatpak_image)
     } else {
-        let mut command = Command::new(&settings.executable);
-        command.arg("--json");
-        if settings.installed {
-            command.arg("-lo").output()?
-        } else {
-            command.arg("-a").output()?
-        }
+        HostCommand::new(&settings.executable)
     };
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    let list_argument = if settings.installed { "-lo" } else { "-a" };
+    command.arg("--json").arg(list_argument)
 }
 
 impl FromSettingsString for LutrisPlatform {
@@ -87,7 +108,7 @@ impl GamesPlatform for LutrisPlatform {
     }
 
     fn get_shortcut_info(&self) -> eyre::Result<Vec<ShortcutToImport>> {
-        to_shortcuts_simple(self.get_shortcuts())
+        to_shortcuts(self, self.get_shortcuts())
     }
 
     fn render_ui(&mut self, ui: &mut egui::Ui) {
@@ -119,4 +140,53 @@ impl GamesPlatform for LutrisPlatform {
     fn code_name(&self) -> &str {
         "lutris"
     }
+
+    fn can_need_proton(&self) -> bool {
+        false
+    }
+
+    fn cache_dependencies(&self) -> Option<Vec<PathBuf>> {
+        //Lutris keeps its games in this database
+        Some(vec![self.data_folder()?.join("pga.db")])
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let check = if self.settings.flatpak {
+            DiagnosticCheck::command(
+                "Lutris flatpak",
+                "flatpak",
+                &["info", &self.settings.flatpak_image],
+            )
+        } else {
+            DiagnosticCheck::version("Lutris", &self.settings.executable, &["--version"])
+        };
+        vec![check]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::platforms::ReplayRunner;
+
+    #[test]
+    fn finds_games_from_recorded_output() {
+        let platform = LutrisPlatform {
+            settings: LutrisSettings {
+                installed: false,
+                ..Default::default()
+            },
+        };
+        let runner = ReplayRunner::default().with_stdout(
+            "flatpak run net.lutris.Lutris --json -a",
+            include_str!("test_output.txt"),
+        );
+        let games = platform.get_shortcuts_with(&runner).unwrap();
+        //One of the games is from Steam, so it is not imported
+        assert_eq!(5, games.len());
+        let metadata = games.first().unwrap().metadata(&platform).unwrap();
+        assert_eq!(Some("dishonored_2".to_string()), metadata.game_id);
+        assert_eq!(vec!["Action".to_string()], metadata.genres);
+    }
 }
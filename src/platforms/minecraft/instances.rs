@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+/// A launcher from the MultiMC family, they all share the same data layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MinecraftLauncher {
+    //~/.local/share/PrismLauncher
+    pub(crate) data_folder: PathBuf,
+    //prismlauncher.cfg
+    pub(crate) config_file_name: &'static str,
+    //prismlauncher or the flatpak id org.prismlauncher.PrismLauncher
+    pub(crate) command: &'static str,
+    pub(crate) flatpak: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MinecraftInstance {
+    /// The folder name of the instance, this is what the launcher wants in --launch
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) icon: Option<PathBuf>,
+}
+
+pub(crate) fn get_launchers() -> Vec<MinecraftLauncher> {
+    let mut result = vec![];
+    if let Ok(home) = std::env::var("HOME") {
+        let home = Path::new(&home);
+        let local_share = home.join(".local").join("share");
+        let flatpak_data = |id: &str, folder: &str| {
+            home.join(".var")
+                .join("app")
+                .join(id)
+                .join("data")
+                .join(folder)
+        };
+        let candidates = [
+            MinecraftLauncher {
+                data_folder: local_share.join("PrismLauncher"),
+                config_file_name: "prismlauncher.cfg",
+                command: "prismlauncher",
+                flatpak: false,
+            },
+            MinecraftLauncher {
+                data_folder: flatpak_data("org.prismlauncher.PrismLauncher", "PrismLauncher"),
+                config_file_name: "prismlauncher.cfg",
+                command: "org.prismlauncher.PrismLauncher",
+                flatpak: true,
+            },
+            MinecraftLauncher {
+                data_folder: local_share.join("PolyMC"),
+                config_file_name: "polymc.cfg",
+                command: "polymc",
+                flatpak: false,
+            },
+            MinecraftLauncher {
+                data_folder: flatpak_data("org.polymc.PolyMC", "PolyMC"),
+                config_file_name: "polymc.cfg",
+                command: "org.polymc.PolyMC",
+                flatpak: true,
+            },
+            MinecraftLauncher {
+                data_folder: local_share.join("multimc"),
+                config_file_name: "multimc.cfg",
+                command: "multimc",
+                flatpak: false,
+            },
+        ];
+        result.extend(
+            candidates
+                .into_iter()
+                .filter(|launcher| launcher.data_folder.exists()),
+        );
+    }
+    result
+}
+
+pub(crate) fn get_instances(launcher: &MinecraftLauncher) -> eyre::Result<Vec<MinecraftInstance>> {
+    let instances_folder = get_instances_folder(launcher);
+    let mut folders: Vec<PathBuf> = instances_folder
+        .read_dir()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    folders.sort();
+    let mut result = vec![];
+    for folder in folders {
+        let config = match std::fs::read_to_string(folder.join("instance.cfg")) {
+            Ok(config) => config,
+            //Folders without a config are groups, temp folders and the like
+            Err(_) => continue,
+        };
+        let id = folder
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = get_config_value(&config, "name").unwrap_or_else(|| id.clone());
+        let icon = get_config_value(&config, "iconKey")
+            .and_then(|icon_key| find_icon(&launcher.data_folder.join("icons"), &icon_key));
+        result.push(MinecraftInstance { id, name, icon });
+    }
+    Ok(result)
+}
+
+/// The launchers lets the user move the instances folder, the path is then saved in their config file
+pub(crate) fn get_instances_folder(launcher: &MinecraftLauncher) -> PathBuf {
+    let configured = std::fs::read_to_string(launcher.data_folder.join(launcher.config_file_name))
+        .ok()
+        .and_then(|config| get_config_value(&config, "InstanceDir"));
+    match configured {
+        Some(instance_dir) => launcher.data_folder.join(instance_dir),
+        None => launcher.data_folder.join("instances"),
+    }
+}
+
+/// Built in icons (like "grass") are compiled into the launcher, so only custom icons can be found on disk
+fn find_icon(icons_folder: &Path, icon_key: &str) -> Option<PathBuf> {
+    let icons = icons_folder.read_dir().ok()?;
+    icons.flatten().map(|entry| entry.path()).find(|path| {
+        path.file_stem()
+            .map(|stem| stem == icon_key)
+            .unwrap_or(false)
+    })
+}
+
+fn get_config_value(config: &str, key: &str) -> Option<String> {
+    config
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(line_key, _)| line_key.trim() == key)
+        .map(|(_, value)| {
+            let value = value.trim();
+            value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value)
+                .to_string()
+        })
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn test_launcher() -> MinecraftLauncher {
+        MinecraftLauncher {
+            data_folder: Path::new("src/testdata/minecraft/PrismLauncher").to_path_buf(),
+            config_file_name: "prismlauncher.cfg",
+            command: "prismlauncher",
+            flatpak: false,
+        }
+    }
+
+    #[test]
+    fn can_find_instances() {
+        let launcher = test_launcher();
+        let instances = get_instances(&launcher).unwrap();
+        assert_eq!(
+            vec![
+                MinecraftInstance {
+                    id: "1.20.1".to_string(),
+                    name: "1.20.1".to_string(),
+                    icon: None,
+                },
+                MinecraftInstance {
+                    id: "Create Mod".to_string(),
+                    name: "Create: Above & Beyond".to_string(),
+                    icon: Some(launcher.data_folder.join("icons").join("create.png")),
+                },
+            ],
+            instances
+        );
+    }
+}
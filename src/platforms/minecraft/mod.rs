@@ -0,0 +1,5 @@
+mod instances;
+mod platform;
+mod settings;
+
+pub use platform::MinecraftPlatform;
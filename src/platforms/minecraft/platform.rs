@@ -0,0 +1,208 @@
+use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
+
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+use crate::platforms::{
+    load_settings, to_shortcuts_simple, FromSettingsString, GamesPlatform, ShortcutToImport,
+};
+
+use super::{
+    instances::{
+        get_instances, get_instances_folder, get_launchers, MinecraftInstance, MinecraftLauncher,
+    },
+    settings::MinecraftSettings,
+};
+
+#[derive(Clone)]
+pub struct MinecraftPlatform {
+    pub settings: MinecraftSettings,
+}
+
+#[derive(Clone, Debug)]
+pub struct MinecraftGame {
+    pub(crate) instance: MinecraftInstance,
+    pub(crate) launcher: MinecraftLauncher,
+}
+
+impl From<MinecraftGame> for ShortcutOwned {
+    fn from(game: MinecraftGame) -> Self {
+        let (exe, launch) = if game.launcher.flatpak {
+            (
+                "flatpak",
+                format!(
+                    "run {} --launch \"{}\"",
+                    game.launcher.command, game.instance.id
+                ),
+            )
+        } else {
+            (
+                game.launcher.command,
+                format!("--launch \"{}\"", game.instance.id),
+            )
+        };
+        let icon = game
+            .instance
+            .icon
+            .map(|icon| icon.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Shortcut::new("0", &game.instance.name, exe, "", &icon, "", &launch).to_owned()
+    }
+}
+
+impl MinecraftPlatform {
+    fn get_minecraft_games(&self) -> eyre::Result<Vec<MinecraftGame>> {
+        let launchers = get_launchers();
+        if launchers.is_empty() {
+            return Err(eyre::format_err!(
+                "Could not find Prism Launcher, PolyMC or MultiMC"
+            ));
+        }
+        let mut games = vec![];
+        for launcher in launchers {
+            //A broken launcher folder must not hide the instances of the other launchers
+            let instances = match get_instances(&launcher) {
+                Ok(instances) => instances,
+                Err(err) => {
+                    eprintln!(
+                        "Could not read the instances of {:?}: {err}",
+                        launcher.data_folder
+                    );
+                    continue;
+                }
+            };
+            for instance in instances {
+                games.push(MinecraftGame {
+                    instance,
+                    launcher: launcher.clone(),
+                });
+            }
+        }
+        Ok(games)
+    }
+}
+
+impl FromSettingsString for MinecraftPlatform {
+    fn from_settings_string<S: AsRef<str>>(s: S) -> Self {
+        MinecraftPlatform {
+            settings: load_settings(s),
+        }
+    }
+}
+
+impl GamesPlatform for MinecraftPlatform {
+    fn name(&self) -> &str {
+        "Minecraft"
+    }
+
+    fn enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    fn get_shortcut_info(&self) -> eyre::Result<Vec<ShortcutToImport>> {
+        to_shortcuts_simple(self.get_minecraft_games())
+    }
+
+    fn render_ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Minecraft");
+        ui.checkbox(
+            &mut self.settings.enabled,
+            "Import instances from Prism Launcher, PolyMC and MultiMC",
+        );
+    }
+
+    fn get_settings_serializable(&self) -> String {
+        toml::to_string(&self.settings).unwrap_or_default()
+    }
+
+    fn code_name(&self) -> &str {
+        "minecraft"
+    }
+
+    fn can_need_proton(&self) -> bool {
+        false
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let launchers = get_launchers();
+        if launchers.is_empty() {
+            return vec![DiagnosticCheck::message(
+                CheckStatus::Error,
+                "Minecraft launcher",
+                "Could not find Prism Launcher, PolyMC or MultiMC",
+            )];
+        }
+        let mut checks = vec![];
+        for launcher in launchers {
+            let instances_folder = get_instances_folder(&launcher);
+            checks.push(DiagnosticCheck::path(
+                format!("{} data", launcher.command),
+                launcher.data_folder.clone(),
+                true,
+            ));
+            checks.push(match get_instances(&launcher) {
+                Ok(instances) => DiagnosticCheck::message(
+                    CheckStatus::Ok,
+                    format!("{} instances", launcher.command),
+                    format!(
+                        "Found {} instances in {}",
+                        instances.len(),
+                        instances_folder.to_string_lossy()
+                    ),
+                ),
+                Err(err) => DiagnosticCheck::message(
+                    CheckStatus::Error,
+                    format!("{} instances", launcher.command),
+                    format!(
+                        "Could not read {}: {err}",
+                        instances_folder.to_string_lossy()
+                    ),
+                ),
+            });
+        }
+        checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn game(flatpak: bool) -> MinecraftGame {
+        MinecraftGame {
+            instance: MinecraftInstance {
+                id: "Create Mod".to_string(),
+                name: "Create: Above & Beyond".to_string(),
+                icon: Some(Path::new("/icons/create.png").to_path_buf()),
+            },
+            launcher: MinecraftLauncher {
+                data_folder: Path::new("/data").to_path_buf(),
+                config_file_name: "prismlauncher.cfg",
+                command: if flatpak {
+                    "org.prismlauncher.PrismLauncher"
+                } else {
+                    "prismlauncher"
+                },
+                flatpak,
+            },
+        }
+    }
+
+    #[test]
+    fn launches_instance() {
+        let shortcut: ShortcutOwned = game(false).into();
+        assert_eq!("Create: Above & Beyond", shortcut.app_name);
+        assert_eq!("prismlauncher", shortcut.exe);
+        assert_eq!("--launch \"Create Mod\"", shortcut.launch_options);
+        assert_eq!("/icons/create.png", shortcut.icon);
+    }
+
+    #[test]
+    fn launches_instance_through_flatpak() {
+        let shortcut: ShortcutOwned = game(true).into();
+        assert_eq!("flatpak", shortcut.exe);
+        assert_eq!(
+            "run org.prismlauncher.PrismLauncher --launch \"Create Mod\"",
+            shortcut.launch_options
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MinecraftSettings {
+    pub enabled: bool,
+}
+
+impl Default for MinecraftSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use crate::platforms::{GamesPlatform, FromSettingsString, load_settings, GogShortcut, NeedsProton};
+use crate::diagnostics::DiagnosticCheck;
+use crate::platforms::{GamesPlatform, FromSettingsString, load_settings, GameMetadata, GogShortcut, NeedsProton};
 
 #[derive(Clone)]
 pub struct MiniGalaxyPlatform {
@@ -28,9 +29,8 @@ impl Default for Settings{
 impl NeedsProton<MiniGalaxyPlatform> for GogShortcut{
     #[cfg(target_family = "unix")]
     fn needs_proton(&self, _platform: &MiniGalaxyPlatform) -> bool {
-        //TODO check if we can do better than just always true
-        //this might be a linux game
-        true
+        //Minigalaxy installs Linux versions of games when there are any
+        crate::platforms::executable_needs_proton(&self.path).unwrap_or(true)
     }
 
     #[cfg(not(target_family = "unix"))]
@@ -47,6 +47,15 @@ impl NeedsProton<MiniGalaxyPlatform> for GogShortcut{
     fn create_symlinks(&self, platform: &MiniGalaxyPlatform) -> bool {
         platform.settings.create_symlinks
     }
+
+    fn metadata(&self, _platform: &MiniGalaxyPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            game_id: Some(self.game_id.clone()),
+            store: Some("gog".to_string()),
+            install_path: Some(self.game_folder.clone()),
+            ..Default::default()
+        })
+    }
 }
 
 impl FromSettingsString for MiniGalaxyPlatform {
@@ -57,6 +66,16 @@ impl FromSettingsString for MiniGalaxyPlatform {
     }
 }
 
+impl MiniGalaxyPlatform {
+    /// The folder Mini Galaxy installs games into, the one from the settings or ~/GOG Games
+    fn games_folder(&self) -> eyre::Result<std::path::PathBuf> {
+        match &self.settings.games_folder {
+            Some(custom_folder) => Ok(std::path::Path::new(&custom_folder).to_path_buf()),
+            None => get_default_folder_path(),
+        }
+    }
+}
+
 impl GamesPlatform for MiniGalaxyPlatform{
     fn name(&self) -> &str {
         "Mini Galaxy"
@@ -72,12 +91,7 @@ impl GamesPlatform for MiniGalaxyPlatform{
 
     fn get_shortcut_info(&self) -> eyre::Result<Vec<crate::platforms::ShortcutToImport>> {
 
-        let games_folder = match &self.settings.games_folder{
-            Some(custom_folder) => std::path::Path::new(&custom_folder).to_path_buf(),
-            None => {
-                get_default_folder_path()?
-            },
-        };
+        let games_folder = self.games_folder()?;
         let dirs = games_folder.read_dir()?;
         let mut game_folders = vec![];
         for game_folder in dirs.flatten(){
@@ -108,6 +122,10 @@ impl GamesPlatform for MiniGalaxyPlatform{
             ui.checkbox(&mut self.settings.create_symlinks, "Create symlinks");
         }
     }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        vec![DiagnosticCheck::located("Mini Galaxy games folder", self.games_folder())]
+    }
 }
 
 fn get_default_folder_string() -> String {
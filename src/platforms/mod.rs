@@ -1,4 +1,6 @@
 #[cfg(target_family = "unix")]
+mod battlenet;
+#[cfg(target_family = "unix")]
 mod bottles;
 #[cfg(target_family = "unix")]
 mod flatpak;
@@ -9,7 +11,13 @@ mod legendary;
 #[cfg(target_family = "unix")]
 mod lutris;
 #[cfg(target_family = "unix")]
+mod minecraft;
+#[cfg(target_family = "unix")]
 mod minigalaxy;
+#[cfg(target_family = "unix")]
+mod startmenu;
+#[cfg(target_family = "unix")]
+mod wine;
 
 #[cfg(not(target_family = "unix"))]
 mod amazon;
@@ -23,23 +31,37 @@ mod gamepass;
 
 
 
+mod external;
 mod gog;
+mod host_command;
 mod itch;
 mod origin;
 mod platform;
+mod platform_instance;
 mod platforms_load;
 mod uplay;
 
+mod discovery_cache;
 mod egs;
+mod executable_format;
+pub(crate) use executable_format::{executable_needs_proton, has_windows_extension};
 pub(crate) use platform::*;
+pub(crate) use host_command::{CancelToken, CommandRunner, HostCommand, SystemRunner};
+#[cfg(test)]
+pub(crate) use host_command::ReplayRunner;
 
 #[cfg(target_family = "unix")]
 pub(crate) use gog::get_gog_shortcuts_from_game_folders;
 #[cfg(target_family = "unix")]
 pub(crate) use gog::GogShortcut;
+#[cfg(target_family = "unix")]
+pub(crate) use wine::{render_extra_prefixes, wine_prefix, wine_prefix_candidates};
 
 
+pub use discovery_cache::get_shortcuts_cached;
+pub use platforms_load::{get_platform_registry, PlatformRegistry};
 pub use platforms_load::get_platforms;
+pub use platforms_load::is_valid_instance_name;
 pub(crate) use platforms_load::load_settings;
 pub(crate) use platforms_load::FromSettingsString;
 pub use platforms_load::Platforms;
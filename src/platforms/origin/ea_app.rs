@@ -0,0 +1,284 @@
+use nom::bytes::complete::{tag, take_until};
+use std::path::{Path, PathBuf};
+
+use super::origin_game::{OriginGame, OriginLauncher};
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+
+#[derive(Default)]
+struct EaAppPathData {
+    //~/.steam/steam/steamapps/compatdata/X/pfx/drive_c/Program Files/Electronic Arts/EA Desktop/EA Desktop/EADesktop.exe
+    exe_path: PathBuf,
+    //The folders the EA app installs games into, by default C:/Program Files/EA Games
+    library_paths: Vec<PathBuf>,
+    //~/.steam/steam/steamapps/compatdata/X
+    compat_folder: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct InstallerData {
+    content_id: String,
+    title: Option<String>,
+}
+
+pub(crate) fn get_ea_app_games() -> eyre::Result<Vec<OriginGame>> {
+    let paths = get_default_locations().ok_or(eyre::format_err!("EA app not found"))?;
+    let mut games = vec![];
+    for library_path in &paths.library_paths {
+        if let Ok(game_folders) = library_path.read_dir() {
+            for game_folder in game_folders.flatten() {
+                let installer_data_path = game_folder
+                    .path()
+                    .join("__Installer")
+                    .join("installerdata.xml");
+                let installer_data = std::fs::read_to_string(installer_data_path)
+                    .ok()
+                    .and_then(|content| parse_installer_data(&content));
+                if let Some(installer_data) = installer_data {
+                    let title = installer_data
+                        .title
+                        .unwrap_or_else(|| game_folder.file_name().to_string_lossy().to_string());
+                    games.push(OriginGame {
+                        id: installer_data.content_id,
+                        title,
+                        origin_location: paths.exe_path.clone(),
+                        origin_compat_folder: paths.compat_folder.clone(),
+                        launcher: OriginLauncher::EaApp,
+                    });
+                }
+            }
+        }
+    }
+    Ok(games)
+}
+
+/// The EA app and the folders it installs games into, or an info message when it is not installed
+pub(crate) fn diagnose_ea_app() -> Vec<DiagnosticCheck> {
+    let Some(paths) = get_default_locations() else {
+        return vec![DiagnosticCheck::message(
+            CheckStatus::Info,
+            "EA app",
+            "EA app not found",
+        )];
+    };
+    let mut checks = vec![];
+    if let Some(compat_folder) = paths.compat_folder {
+        checks.push(DiagnosticCheck::path(
+            "EA app wine prefix",
+            compat_folder,
+            true,
+        ));
+    }
+    checks.push(DiagnosticCheck::path("EA app", paths.exe_path, true));
+    for library_path in paths.library_paths {
+        checks.push(DiagnosticCheck::path("EA app library", library_path, false));
+    }
+    checks
+}
+
+fn parse_installer_data(xml: &str) -> Option<InstallerData> {
+    let content_id = parse_tag_content(xml, "<contentID>", "</contentID>")
+        .ok()
+        .map(|(_, id)| id.trim().to_string())
+        .filter(|id| !id.is_empty())?;
+    let title = parse_tag_content(xml, "<gameTitle locale=\"en_US\">", "</gameTitle>")
+        .or_else(|_| parse_any_game_title(xml))
+        .or_else(|_| parse_tag_content(xml, "<title>", "</title>"))
+        .ok()
+        .map(|(_, title)| unescape_xml(title.trim()))
+        .filter(|title| !title.is_empty());
+    Some(InstallerData { content_id, title })
+}
+
+fn parse_tag_content<'a>(i: &'a str, open: &str, close: &str) -> nom::IResult<&'a str, &'a str> {
+    let (i, _) = take_until(open)(i)?;
+    let (i, _) = tag(open)(i)?;
+    take_until(close)(i)
+}
+
+fn parse_any_game_title(i: &str) -> nom::IResult<&str, &str> {
+    let (i, _) = take_until("<gameTitle")(i)?;
+    let (i, _) = take_until(">")(i)?;
+    let (i, _) = tag(">")(i)?;
+    take_until("</gameTitle>")(i)
+}
+
+fn unescape_xml(input: &str) -> String {
+    input
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// The EA app remembers where the user chose to install games in its user_<id>.ini files
+fn parse_download_folders(ini: &str) -> Vec<String> {
+    ini.lines()
+        .filter_map(|line| line.trim().strip_prefix("user.downloadinplacedir="))
+        .map(|folder| folder.trim().to_string())
+        .filter(|folder| !folder.is_empty())
+        .collect()
+}
+
+fn get_download_folders_from_ini_files(ea_desktop_folder: &Path) -> Vec<String> {
+    let mut result = vec![];
+    if let Ok(files) = ea_desktop_folder.read_dir() {
+        for file in files.flatten() {
+            let file_name = file.file_name().to_string_lossy().to_string();
+            if file_name.starts_with("user_") && file_name.ends_with(".ini") {
+                if let Ok(content) = std::fs::read_to_string(file.path()) {
+                    result.extend(parse_download_folders(&content));
+                }
+            }
+        }
+    }
+    result
+}
+
+#[cfg(target_family = "unix")]
+fn get_default_locations() -> Option<EaAppPathData> {
+    let compat_folders = crate::steam::detected_compatdata_folders()
+        .into_iter()
+        .filter_map(|folder| std::fs::read_dir(folder).ok());
+    for dir in compat_folders.flat_map(|entries| entries.flatten()) {
+        let prefix = dir.path().join("pfx");
+        let drive_c = prefix.join("drive_c");
+        let exe_path = drive_c
+            .join("Program Files")
+            .join("Electronic Arts")
+            .join("EA Desktop")
+            .join("EA Desktop")
+            .join("EADesktop.exe");
+        if !exe_path.exists() {
+            continue;
+        }
+        let mut library_paths = vec![drive_c.join("Program Files").join("EA Games")];
+        if let Ok(users) = drive_c.join("users").read_dir() {
+            for user in users.flatten() {
+                let ea_desktop_folder = user
+                    .path()
+                    .join("AppData")
+                    .join("Local")
+                    .join("Electronic Arts")
+                    .join("EA Desktop");
+                for folder in get_download_folders_from_ini_files(&ea_desktop_folder) {
+                    library_paths.push(windows_path_in_prefix(&prefix, &folder));
+                }
+            }
+        }
+        library_paths.sort();
+        library_paths.dedup();
+        return Some(EaAppPathData {
+            exe_path,
+            library_paths,
+            compat_folder: Some(dir.path()),
+        });
+    }
+    None
+}
+
+#[cfg(target_family = "unix")]
+fn windows_path_in_prefix(prefix: &Path, location: &str) -> PathBuf {
+    let drive = location.get(0..2).map(|drive| drive.to_lowercase());
+    let rest_path = location
+        .get(3..)
+        .map(|rest| rest.replace('\\', "/").trim_end_matches('/').to_string());
+    match (drive, rest_path) {
+        (Some(drive), Some(rest_path)) => prefix.join("dosdevices").join(drive).join(rest_path),
+        _ => Path::new(location).to_path_buf(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn get_default_locations() -> Option<EaAppPathData> {
+    let exe_path = get_exe_path()?;
+    let program_files = std::env::var("PROGRAMFILES").unwrap_or_default();
+    let mut library_paths = vec![Path::new(&program_files).join("EA Games")];
+    if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+        let ea_desktop_folder = Path::new(&local_app_data)
+            .join("Electronic Arts")
+            .join("EA Desktop");
+        for folder in get_download_folders_from_ini_files(&ea_desktop_folder) {
+            library_paths.push(Path::new(&folder).to_path_buf());
+        }
+    }
+    library_paths.sort();
+    library_paths.dedup();
+    Some(EaAppPathData {
+        exe_path,
+        library_paths,
+        compat_folder: None,
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn get_exe_path() -> Option<PathBuf> {
+    use winreg::enums::*;
+    use winreg::RegKey;
+    //Computer\HKEY_LOCAL_MACHINE\SOFTWARE\Electronic Arts\EA Desktop
+    let from_registry = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey("SOFTWARE\\Electronic Arts\\EA Desktop")
+        .and_then(|key| key.get_value("ClientPath"))
+        .ok()
+        .map(|value: String| Path::new(&value).to_path_buf())
+        .filter(|path| path.exists());
+    from_registry.or_else(|| {
+        let program_files = std::env::var("PROGRAMFILES").ok()?;
+        let path = Path::new(&program_files)
+            .join("Electronic Arts")
+            .join("EA Desktop")
+            .join("EA Desktop")
+            .join("EADesktop.exe");
+        if path.exists() {
+            Some(path)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_installer_data() {
+        let content = include_str!("../../testdata/ea/installerdata.xml");
+        let installer_data = parse_installer_data(content);
+        assert_eq!(
+            Some(InstallerData {
+                content_id: "1055074".to_string(),
+                title: Some("Unravel Two".to_string()),
+            }),
+            installer_data
+        );
+    }
+
+    #[test]
+    fn can_parse_old_installer_data() {
+        let content = include_str!("../../testdata/ea/installerdata_v2.xml");
+        let installer_data = parse_installer_data(content);
+        assert_eq!(
+            Some(InstallerData {
+                content_id: "71500".to_string(),
+                title: Some("Mass Effect™ 2".to_string()),
+            }),
+            installer_data
+        );
+    }
+
+    #[test]
+    fn no_content_id_is_not_a_game() {
+        let installer_data = parse_installer_data("<DiPManifest></DiPManifest>");
+        assert_eq!(None, installer_data);
+    }
+
+    #[test]
+    fn can_parse_download_folders() {
+        let ini = "user.gamecommandline.origin.ofr.50.0002694=\nuser.downloadinplacedir=D:\\EA Games\\\nuser.locale=en_US";
+        assert_eq!(
+            vec!["D:\\EA Games\\".to_string()],
+            parse_download_folders(ini)
+        );
+    }
+}
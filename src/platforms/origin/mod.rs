@@ -1,3 +1,4 @@
+mod ea_app;
 mod origin_game;
 mod origin_platform;
 mod settings;
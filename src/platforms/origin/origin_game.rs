@@ -2,21 +2,44 @@ use std::path::PathBuf;
 
 use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OriginLauncher {
+    /// The retired Origin client
+    Origin,
+    /// The EA app (EA Desktop) that replaced Origin
+    EaApp,
+}
+
 #[derive(Clone)]
 pub struct OriginGame {
     pub id: String,
     pub title: String,
     pub origin_location: PathBuf,
     pub origin_compat_folder: Option<PathBuf>,
+    pub launcher: OriginLauncher,
+}
+
+impl OriginGame {
+    fn launch_url(&self) -> String {
+        match self.launcher {
+            OriginLauncher::Origin => format!(
+                "origin2://game/launch?offerIds={}&autoDownload=1&authCode=&cmdParams=",
+                self.id
+            ),
+            OriginLauncher::EaApp => format!("link2ea://launchgame/{}?platform=origin", self.id),
+        }
+    }
 }
 
 impl From<OriginGame> for ShortcutOwned {
     fn from(game: OriginGame) -> Self {
-        let launch = match game.origin_compat_folder{
-            Some(compat_folder) => format!
-            ("STEAM_COMPAT_DATA_PATH=\"{}\" %command% \"origin2://game/launch?offerIds={}&autoDownload=1&authCode=&cmdParams=\"",
-             compat_folder.to_string_lossy(), game.id),
-            None => format!("\"origin2://game/launch?offerIds={}&autoDownload=1&authCode=&cmdParams=\"",game.id)
+        let url = game.launch_url();
+        let launch = match &game.origin_compat_folder {
+            Some(compat_folder) => format!(
+                "STEAM_COMPAT_DATA_PATH=\"{}\" %command% \"{url}\"",
+                compat_folder.to_string_lossy()
+            ),
+            None => format!("\"{url}\""),
         };
         let origin_location = format!("\"{}\"", game.origin_location.to_string_lossy());
         let mut owned_shortcut = Shortcut::new(
@@ -29,10 +52,52 @@ impl From<OriginGame> for ShortcutOwned {
             launch.as_str(),
         )
         .to_owned();
-        owned_shortcut.tags.push("Origin".to_owned());
+        let tag = match game.launcher {
+            OriginLauncher::Origin => "Origin",
+            OriginLauncher::EaApp => "EA",
+        };
+        owned_shortcut.tags.push(tag.to_owned());
         owned_shortcut.tags.push("Ready TO Play".to_owned());
         owned_shortcut.tags.push("Installed".to_owned());
 
         owned_shortcut
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origin_launch_is_unchanged() {
+        let game = OriginGame {
+            id: "Origin.OFR.50.0001000".to_string(),
+            title: "Some game".to_string(),
+            origin_location: PathBuf::from("Origin.exe"),
+            origin_compat_folder: None,
+            launcher: OriginLauncher::Origin,
+        };
+        let shortcut: ShortcutOwned = game.into();
+        assert_eq!(
+            shortcut.launch_options,
+            "\"origin2://game/launch?offerIds=Origin.OFR.50.0001000&autoDownload=1&authCode=&cmdParams=\""
+        );
+    }
+
+    #[test]
+    fn ea_app_launches_through_link() {
+        let game = OriginGame {
+            id: "1055074".to_string(),
+            title: "Unravel Two".to_string(),
+            origin_location: PathBuf::from("/pfx/EADesktop.exe"),
+            origin_compat_folder: Some(PathBuf::from("/compatdata/42")),
+            launcher: OriginLauncher::EaApp,
+        };
+        let shortcut: ShortcutOwned = game.into();
+        assert_eq!(
+            shortcut.launch_options,
+            "STEAM_COMPAT_DATA_PATH=\"/compatdata/42\" %command% \"link2ea://launchgame/1055074?platform=origin\""
+        );
+        assert!(shortcut.tags.contains(&"EA".to_string()));
+    }
+}
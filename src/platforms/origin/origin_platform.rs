@@ -1,5 +1,7 @@
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
 use crate::platforms::{
-    load_settings, to_shortcuts, FromSettingsString, GamesPlatform, NeedsProton, ShortcutToImport,
+    load_settings, to_shortcuts, FromSettingsString, GameMetadata, GamesPlatform, NeedsProton,
+    ShortcutToImport,
 };
 use nom::bytes::complete::take_until;
 use std::{
@@ -7,7 +9,11 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use super::{origin_game::OriginGame, OriginSettings};
+use super::{
+    ea_app::{diagnose_ea_app, get_ea_app_games},
+    origin_game::{OriginGame, OriginLauncher},
+    OriginSettings,
+};
 
 #[derive(Clone)]
 pub struct OriginPlatform {
@@ -22,41 +28,91 @@ impl NeedsProton<OriginPlatform> for OriginGame {
 
     #[cfg(target_family = "unix")]
     fn needs_proton(&self, _platform: &OriginPlatform) -> bool {
-        true
+        crate::platforms::executable_needs_proton(self.origin_location.to_string_lossy())
+            .unwrap_or(true)
     }
 
     fn create_symlinks(&self, _platform: &OriginPlatform) -> bool {
         false
     }
+
+    fn metadata(&self, _platform: &OriginPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            game_id: Some(self.id.clone()),
+            store: Some("origin".to_string()),
+            native: Some(cfg!(target_os = "windows")),
+            ..Default::default()
+        })
+    }
 }
 
 impl OriginPlatform {
     fn get_shortcuts(&self) -> eyre::Result<Vec<OriginGame>> {
-        let origin_folders =
-            get_default_locations().ok_or(eyre::format_err!("Default path not found"))?;
-        let origin_folder = origin_folders.local_content_path;
-        let origin_exe = origin_folders.exe_path;
-        let game_folders = origin_folder.join("LocalContent").read_dir()?;
-        let games = game_folders
-            .filter_map(|folder| folder.ok())
-            .filter_map(|game_folder| {
-                let game_title = game_folder.file_name().to_string_lossy().to_string();
-                let mfst_content = get_folder_mfst_file_content(&game_folder.path());
-                let id = match mfst_content {
-                    Some(c) => parse_id_from_file(c.as_str())
-                        .ok()
-                        .map(|(_, id_str)| String::from(id_str)),
-                    None => None,
-                };
-                id.map(|id| OriginGame {
-                    id,
-                    title: game_title,
-                    origin_location: origin_exe.clone(),
-                    origin_compat_folder: origin_folders.compat_folder.clone(),
-                })
-            });
-        Ok(games.collect())
+        let ea_app_games = get_ea_app_games();
+        let origin_games = get_origin_games();
+        if let (Err(ea_app_error), Err(origin_error)) = (&ea_app_games, &origin_games) {
+            return Err(eyre::format_err!(
+                "Could not find games from EA app ({ea_app_error}) or Origin ({origin_error})"
+            ));
+        }
+        Ok(merge_games(
+            ea_app_games.unwrap_or_default(),
+            origin_games.unwrap_or_default(),
+        ))
+    }
+}
+
+/// Games installed through the EA app are preferred, since Origin is no longer maintained.
+/// The EA app and Origin use different ids for the same game, so they are matched by title.
+fn merge_games(ea_app_games: Vec<OriginGame>, origin_games: Vec<OriginGame>) -> Vec<OriginGame> {
+    let mut games = ea_app_games;
+    for game in origin_games {
+        let title = normalized_title(&game.title);
+        if !games
+            .iter()
+            .any(|existing| normalized_title(&existing.title) == title)
+        {
+            games.push(game);
+        }
     }
+    games
+}
+
+/// The title without case, symbols like ™ and whitespace
+fn normalized_title(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn get_origin_games() -> eyre::Result<Vec<OriginGame>> {
+    let origin_folders =
+        get_default_locations().ok_or(eyre::format_err!("Default path not found"))?;
+    let origin_folder = origin_folders.local_content_path;
+    let origin_exe = origin_folders.exe_path;
+    let game_folders = origin_folder.join("LocalContent").read_dir()?;
+    let games = game_folders
+        .filter_map(|folder| folder.ok())
+        .filter_map(|game_folder| {
+            let game_title = game_folder.file_name().to_string_lossy().to_string();
+            let mfst_content = get_folder_mfst_file_content(&game_folder.path());
+            let id = match mfst_content {
+                Some(c) => parse_id_from_file(c.as_str())
+                    .ok()
+                    .map(|(_, id_str)| String::from(id_str)),
+                None => None,
+            };
+            id.map(|id| OriginGame {
+                id,
+                title: game_title,
+                origin_location: origin_exe.clone(),
+                origin_compat_folder: origin_folders.compat_folder.clone(),
+                launcher: OriginLauncher::Origin,
+            })
+        });
+    Ok(games.collect())
 }
 
 fn get_folder_mfst_file_content(game_folder_path: &Path) -> Option<String> {
@@ -101,13 +157,7 @@ struct OriginPathData {
 #[cfg(target_family = "unix")]
 fn get_default_locations() -> Option<OriginPathData> {
     let mut res = OriginPathData::default();
-    if let Ok(home) = std::env::var("HOME") {
-        let compat_folder_path = Path::new(&home)
-            .join(".steam")
-            .join("steam")
-            .join("steamapps")
-            .join("compatdata");
-
+    for compat_folder_path in crate::steam::detected_compatdata_folders() {
         if let Ok(compat_folder) = std::fs::read_dir(compat_folder_path) {
             for dir in compat_folder.flatten() {
                 let origin_exe_path = dir
@@ -179,6 +229,7 @@ fn get_exe_path() -> Option<PathBuf> {
 
 impl GamesPlatform for OriginPlatform {
     fn name(&self) -> &str {
+        //Existing collections are kept by this name
         "Origin"
     }
 
@@ -191,8 +242,8 @@ impl GamesPlatform for OriginPlatform {
     }
 
     fn render_ui(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Origin");
-        ui.checkbox(&mut self.settings.enabled, "Import from Origin");
+        ui.heading("EA app & Origin");
+        ui.checkbox(&mut self.settings.enabled, "Import from EA app & Origin");
     }
 
     fn get_settings_serializable(&self) -> String {
@@ -202,6 +253,40 @@ impl GamesPlatform for OriginPlatform {
     fn code_name(&self) -> &str {
         "origin"
     }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let mut checks = diagnose_ea_app();
+        match get_default_locations() {
+            Some(paths) => {
+                if let Some(compat_folder) = paths.compat_folder {
+                    checks.push(DiagnosticCheck::path(
+                        "Origin wine prefix",
+                        compat_folder,
+                        true,
+                    ));
+                }
+                checks.push(DiagnosticCheck::path("Origin", paths.exe_path, true));
+                checks.push(DiagnosticCheck::path(
+                    "Origin games",
+                    paths.local_content_path.join("LocalContent"),
+                    true,
+                ));
+            }
+            None => checks.push(DiagnosticCheck::message(
+                CheckStatus::Info,
+                "Origin",
+                "Origin not found",
+            )),
+        }
+        if checks.iter().all(|check| check.status != CheckStatus::Ok) {
+            checks.push(DiagnosticCheck::message(
+                CheckStatus::Error,
+                "EA app & Origin",
+                "Neither the EA app nor Origin was found",
+            ));
+        }
+        checks
+    }
 }
 
 impl FromSettingsString for OriginPlatform {
@@ -211,3 +296,34 @@ impl FromSettingsString for OriginPlatform {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(id: &str, title: &str, launcher: OriginLauncher) -> OriginGame {
+        OriginGame {
+            id: id.to_string(),
+            title: title.to_string(),
+            origin_location: PathBuf::from("Origin.exe"),
+            origin_compat_folder: None,
+            launcher,
+        }
+    }
+
+    #[test]
+    fn games_in_both_launchers_are_imported_once() {
+        let ea_app_games = vec![game("71500", "Mass Effect™ 2", OriginLauncher::EaApp)];
+        let origin_games = vec![
+            game(
+                "Origin.OFR.50.0000500",
+                "Mass Effect 2",
+                OriginLauncher::Origin,
+            ),
+            game("Origin.OFR.50.0001000", "Unravel", OriginLauncher::Origin),
+        ];
+        let games = merge_games(ea_app_games, origin_games);
+        let ids: Vec<&str> = games.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(vec!["71500", "Origin.OFR.50.0001000"], ids);
+    }
+}
@@ -1,6 +1,11 @@
+use std::path::PathBuf;
+
 use dyn_clone::DynClone;
+use serde::{Deserialize, Serialize};
 use steam_shortcuts_util::shortcut::ShortcutOwned;
 
+use crate::diagnostics::DiagnosticCheck;
+
 pub trait GamesPlatform
 where
     Self: std::marker::Send,
@@ -18,6 +23,32 @@ where
     fn get_settings_serializable(&self) -> String;
 
     fn render_ui(&mut self, ui: &mut egui::Ui);
+
+    /// The name of the Steam collection the games from this platform are put in
+    fn collection_name(&self) -> &str {
+        self.name()
+    }
+
+    /// False for platforms whose games never run through Proton, because they are native or the platform runs them with its own Wine
+    fn can_need_proton(&self) -> bool {
+        true
+    }
+
+    /// The name of an extra instance of a platform, the default instance has no name
+    fn instance_name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Checks that explain why the platform might not find any games, fx the paths and commands it uses
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        vec![]
+    }
+
+    /// The files and folders the games are found from, while none of them change the games are loaded from a cache.
+    /// Platforms that return None are scanned every time.
+    fn cache_dependencies(&self) -> Option<Vec<PathBuf>> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(GamesPlatform);
@@ -27,6 +58,74 @@ pub struct ShortcutToImport {
     pub shortcut: ShortcutOwned,
     pub needs_proton: bool,
     pub needs_symlinks: bool,
+    /// What the platform knows about the game, it is never written to the shortcut in Steam
+    pub metadata: Option<GameMetadata>,
+}
+
+/// Information a platform has about a game, beyond what is needed to launch it
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameMetadata {
+    /// The id of the game on the platform, fx the GOG game id or the Epic app name
+    pub game_id: Option<String>,
+    /// The store the game is from, named like SteamGridDB names it (fx "egs" or "gog")
+    pub store: Option<String>,
+    pub install_path: Option<String>,
+    /// Size of the installed game in bytes
+    pub install_size: Option<u64>,
+    /// True if the game is built for the system it is installed on, false if it is a Windows game
+    pub native: Option<bool>,
+    pub developer: Option<String>,
+    pub genres: Vec<String>,
+    /// A local image of the game's cover art
+    pub cover_path: Option<String>,
+}
+
+impl GameMetadata {
+    /// The metadata as label and value pairs, for showing to the user
+    pub fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = vec![];
+        if let Some(game_id) = &self.game_id {
+            details.push(("Id", game_id.clone()));
+        }
+        if let Some(developer) = &self.developer {
+            details.push(("Developer", developer.clone()));
+        }
+        if !self.genres.is_empty() {
+            details.push(("Genres", self.genres.join(", ")));
+        }
+        if let Some(native) = self.native {
+            let build = if native { "Native" } else { "Windows" };
+            details.push(("Build", build.to_string()));
+        }
+        if let Some(install_path) = &self.install_path {
+            details.push(("Installed in", install_path.clone()));
+        }
+        if let Some(install_size) = self.install_size {
+            details.push(("Size", format_size(install_size)));
+        }
+        if let Some(cover_path) = &self.cover_path {
+            details.push(("Cover", cover_path.clone()));
+        }
+        details
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for next_unit in UNITS {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{size:.1} {unit}")
 }
 
 pub(crate) fn to_shortcuts<T, P>(
@@ -42,11 +141,13 @@ where
     for m in shortcuts {
         let needs_proton = m.needs_proton(platform);
         let needs_symlinks = m.create_symlinks(platform);
+        let metadata = m.metadata(platform);
         let shortcut = m.into();
         shortcut_info.push(ShortcutToImport {
             shortcut,
             needs_proton,
             needs_symlinks,
+            metadata,
         });
     }
     Ok(shortcut_info)
@@ -68,6 +169,7 @@ where
             shortcut,
             needs_proton,
             needs_symlinks,
+            metadata: None,
         });
     }
     Ok(shortcut_info)
@@ -77,4 +179,20 @@ pub trait NeedsProton<P> {
     fn needs_proton(&self, platform: &P) -> bool;
 
     fn create_symlinks(&self, platform: &P) -> bool;
+
+    fn metadata(&self, _platform: &P) -> Option<GameMetadata> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_are_shown_in_the_largest_unit() {
+        assert_eq!("512 B", format_size(512));
+        assert_eq!("1.5 KB", format_size(1536));
+        assert_eq!("75.6 GB", format_size(81199814148));
+    }
 }
@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostics::DiagnosticCheck;
+
+use super::{load_settings, GamesPlatform, ShortcutToImport};
+
+/// An extra instance of a platform, loaded from a config section like [gog.secondary].
+/// It has its own settings, and can have its own name and collection in Steam.
+#[derive(Clone)]
+pub struct PlatformInstance {
+    instance_name: String,
+    section_name: String,
+    default_display_name: String,
+    settings: InstanceSettings,
+    platform: Box<dyn GamesPlatform>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+struct InstanceSettings {
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    collection_name: Option<String>,
+}
+
+impl PlatformInstance {
+    pub fn new(
+        instance_name: &str,
+        settings_string: &str,
+        platform: Box<dyn GamesPlatform>,
+    ) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            section_name: format!("{}.{}", platform.code_name(), instance_name),
+            default_display_name: format!("{} ({})", platform.name(), instance_name),
+            settings: load_settings(settings_string),
+            platform,
+        }
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|v| !v.trim().is_empty())
+}
+
+impl GamesPlatform for PlatformInstance {
+    fn name(&self) -> &str {
+        non_empty(&self.settings.display_name).unwrap_or(&self.default_display_name)
+    }
+
+    fn code_name(&self) -> &str {
+        &self.section_name
+    }
+
+    fn enabled(&self) -> bool {
+        self.platform.enabled()
+    }
+
+    fn get_shortcut_info(&self) -> eyre::Result<Vec<ShortcutToImport>> {
+        self.platform.get_shortcut_info()
+    }
+
+    fn get_settings_serializable(&self) -> String {
+        let mut result = toml::to_string(&self.settings).unwrap_or_default();
+        result.push_str(&self.platform.get_settings_serializable());
+        result
+    }
+
+    fn render_ui(&mut self, ui: &mut egui::Ui) {
+        //The platform draws its own heading, the ids are kept apart from the other instances of the platform
+        ui.push_id(&self.section_name, |ui| self.platform.render_ui(ui));
+        ui.label(format!("Instance \"{}\"", self.instance_name));
+        ui.horizontal(|ui| {
+            ui.label("Name: ");
+            let mut display_name = self.settings.display_name.clone().unwrap_or_default();
+            if ui.text_edit_singleline(&mut display_name).changed() {
+                self.settings.display_name = Some(display_name).filter(|n| !n.trim().is_empty());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Collection: ");
+            let mut collection_name = self.settings.collection_name.clone().unwrap_or_default();
+            if ui.text_edit_singleline(&mut collection_name).changed() {
+                self.settings.collection_name =
+                    Some(collection_name).filter(|n| !n.trim().is_empty());
+            }
+        });
+    }
+
+    fn can_need_proton(&self) -> bool {
+        self.platform.can_need_proton()
+    }
+
+    fn collection_name(&self) -> &str {
+        non_empty(&self.settings.collection_name).unwrap_or_else(|| self.name())
+    }
+
+    fn instance_name(&self) -> Option<&str> {
+        Some(&self.instance_name)
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        self.platform.diagnose()
+    }
+
+    fn cache_dependencies(&self) -> Option<Vec<PathBuf>> {
+        self.platform.cache_dependencies()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use crate::platforms::get_platform_registry;
+
+    #[test]
+    fn instance_has_its_own_section_and_names() {
+        let settings = "display_name = \"Work GOG\"\nenabled = true\ncreate_symlinks = false\n";
+        let instance = get_platform_registry()
+            .load_instance("gog", "secondary", settings)
+            .unwrap();
+        assert_eq!("gog.secondary", instance.code_name());
+        assert_eq!("Work GOG", instance.name());
+        assert_eq!("Work GOG", instance.collection_name());
+        assert_eq!(Some("secondary"), instance.instance_name());
+        let serialized = instance.get_settings_serializable();
+        assert!(serialized.starts_with("display_name = \"Work GOG\"\n"));
+        assert!(serialized.contains("enabled = true"));
+    }
+
+    #[test]
+    fn instance_without_names_uses_platform_name() {
+        let instance = get_platform_registry()
+            .load_instance("itch", "second_library", "")
+            .unwrap();
+        assert_eq!("Itch (second_library)", instance.name());
+        assert_eq!("Itch (second_library)", instance.collection_name());
+    }
+}
@@ -1,84 +1,167 @@
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 
-use super::GamesPlatform;
+use super::{platform_instance::PlatformInstance, GamesPlatform};
 
 use crate::settings::load_setting_sections;
-const PLATFORM_NAMES: [&str; 14] = [
-    "amazon",
-    "bottles",
-    "epic_games",
-    "flatpak",
-    "gog",
-    "heroic",
-    "itch",
-    "legendary",
-    "lutris",
-    "origin",
-    "uplay",
-    "minigalaxy",
-    "playnite",
-    "gamepass"
-];
-
 pub type Platforms = Vec<Box<dyn GamesPlatform>>;
 
-pub fn load_platform<A: AsRef<str>, B: AsRef<str>>(
-    name: A,
-    settings_string: B,
-) -> eyre::Result<Box<dyn GamesPlatform>> {
-    let name = name.as_ref();
-    let s = settings_string.as_ref();
+type PlatformFactory = fn(&str) -> Box<dyn GamesPlatform>;
+
+/// A kind of platform that BoilR knows how to load, fx "gog"
+#[derive(Clone)]
+pub struct PlatformType {
+    pub code_name: &'static str,
+    /// The name shown to the user, it is the name of a platform with default settings
+    display_name: String,
+    factory: PlatformFactory,
+}
+
+impl PlatformType {
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+}
+
+/// All the platform types, in the order they are shown.
+/// Each type has a default instance that uses the [code_name] section of the config file,
+/// extra instances are loaded from sections named [code_name.instance_name].
+pub struct PlatformRegistry {
+    types: Vec<PlatformType>,
+}
+
+impl PlatformRegistry {
+    fn new() -> Self {
+        Self { types: vec![] }
+    }
+
+    fn register<T>(&mut self, code_name: &'static str)
+    where
+        T: FromSettingsString,
+        T: GamesPlatform,
+        T: 'static,
+    {
+        let factory: PlatformFactory = load::<T>;
+        self.types.push(PlatformType {
+            code_name,
+            display_name: factory("").name().to_string(),
+            factory,
+        });
+    }
+
+    pub fn types(&self) -> &[PlatformType] {
+        &self.types
+    }
+
+    pub fn load_platform<A: AsRef<str>, B: AsRef<str>>(
+        &self,
+        code_name: A,
+        settings_string: B,
+    ) -> eyre::Result<Box<dyn GamesPlatform>> {
+        let code_name = code_name.as_ref();
+        let platform_type = self
+            .types
+            .iter()
+            .find(|t| t.code_name == code_name)
+            .ok_or_else(|| eyre::format_err!("Unknown platform named {code_name}"))?;
+        Ok((platform_type.factory)(settings_string.as_ref()))
+    }
+
+    pub fn load_instance<A: AsRef<str>, B: AsRef<str>, C: AsRef<str>>(
+        &self,
+        code_name: A,
+        instance_name: B,
+        settings_string: C,
+    ) -> eyre::Result<Box<dyn GamesPlatform>> {
+        let settings_string = settings_string.as_ref();
+        let platform = self.load_platform(code_name, settings_string)?;
+        Ok(Box::new(PlatformInstance::new(
+            instance_name.as_ref(),
+            settings_string,
+            platform,
+        )))
+    }
+}
+
+pub fn get_platform_registry() -> PlatformRegistry {
+    let mut registry = PlatformRegistry::new();
 
     #[cfg(not(target_family = "unix"))]
     {
         //Windows only platforms
         use super::amazon::AmazonPlatform;
-        use super::playnite::PlaynitePlatform;
-        use super::gamepass::GamePassPlatForm;
-        match name {
-            "amazon" => return load::<AmazonPlatform>(s),
-            "playnite" => return load::<PlaynitePlatform>(s),
-            "gamepass" => return load::<GamePassPlatForm>(s),
-            _ => {}
-        }
+        registry.register::<AmazonPlatform>("amazon");
     }
 
     #[cfg(target_family = "unix")]
     {
+        //Linux only platforms
+        use super::battlenet::BattleNetPlatform;
         use super::bottles::BottlesPlatform;
+        registry.register::<BattleNetPlatform>("battlenet");
+        registry.register::<BottlesPlatform>("bottles");
+    }
+
+    use super::egs::EpicPlatform;
+    registry.register::<EpicPlatform>("epic_games");
+
+    #[cfg(target_family = "unix")]
+    {
         use super::flatpak::FlatpakPlatform;
+        registry.register::<FlatpakPlatform>("flatpak");
+    }
+
+    use super::gog::GogPlatform;
+    registry.register::<GogPlatform>("gog");
+
+    #[cfg(target_family = "unix")]
+    {
         use super::heroic::HeroicPlatform;
+        registry.register::<HeroicPlatform>("heroic");
+    }
+
+    use super::itch::ItchPlatform;
+    registry.register::<ItchPlatform>("itch");
+
+    #[cfg(target_family = "unix")]
+    {
         use super::legendary::LegendaryPlatform;
         use super::lutris::LutrisPlatform;
-        use super::minigalaxy::MiniGalaxyPlatform;
-        //Linux only platforms
-        match name {
-            "bottles" => return load::<BottlesPlatform>(s),
-            "flatpak" => return load::<FlatpakPlatform>(s),
-            "minigalaxy" => return load::<MiniGalaxyPlatform>(s),
-            "legendary" => return load::<LegendaryPlatform>(s),
-            "lutris" => return load::<LutrisPlatform>(s),
-            "heroic" => return load::<HeroicPlatform>(s),
-            _ => {}
-        }
+        registry.register::<LegendaryPlatform>("legendary");
+        registry.register::<LutrisPlatform>("lutris");
     }
 
-    //Common platforms
-    use super::egs::EpicPlatform;
-    use super::gog::GogPlatform;
-    use super::itch::ItchPlatform;
     use super::origin::OriginPlatform;
     use super::uplay::UplayPlatform;
+    registry.register::<OriginPlatform>("origin");
+    registry.register::<UplayPlatform>("uplay");
+
+    #[cfg(target_family = "unix")]
+    {
+        use super::minecraft::MinecraftPlatform;
+        use super::minigalaxy::MiniGalaxyPlatform;
+        registry.register::<MiniGalaxyPlatform>("minigalaxy");
+        registry.register::<MinecraftPlatform>("minecraft");
+    }
 
-    match name {
-        "epic_games" => load::<EpicPlatform>(s),
-        "uplay" => load::<UplayPlatform>(s),
-        "itch" => load::<ItchPlatform>(s),
-        "gog" => load::<GogPlatform>(s),
-        "origin" => load::<OriginPlatform>(s),
-        _ => Err(eyre::format_err!("Unknown platform named {name}")),
+    #[cfg(not(target_family = "unix"))]
+    {
+        use super::gamepass::GamePassPlatForm;
+        use super::playnite::PlaynitePlatform;
+        registry.register::<PlaynitePlatform>("playnite");
+        registry.register::<GamePassPlatForm>("gamepass");
     }
+
+    #[cfg(target_family = "unix")]
+    {
+        use super::startmenu::StartMenuPlatform;
+        registry.register::<StartMenuPlatform>("wine_start_menu");
+    }
+
+    use super::external::ExternalCommandsPlatform;
+    registry.register::<ExternalCommandsPlatform>("external_commands");
+
+    registry
 }
 
 pub fn get_platforms() -> Platforms {
@@ -86,25 +169,48 @@ pub fn get_platforms() -> Platforms {
     let sections = match sections {
         Ok(s) => s,
         Err(err) => {
-            eprintln!(
-                "Could not load platform settings, using defaults: Error: {err:?}"
-            );
+            eprintln!("Could not load platform settings, using defaults: Error: {err:?}");
             HashMap::new()
         }
     };
 
+    let registry = get_platform_registry();
     let mut platforms = vec![];
-    for name in PLATFORM_NAMES {
+    for platform_type in registry.types() {
+        let name = platform_type.code_name;
         let default = String::from("");
         let settings = sections.get(name).unwrap_or(&default);
-        match load_platform(name, settings) {
+        match registry.load_platform(name, settings) {
             Ok(platform) => platforms.push(platform),
             Err(e) => eprintln!("Could not load platform {name}, gave error: {e}"),
         }
+
+        let mut instance_names: Vec<&str> = sections
+            .keys()
+            .filter_map(|section| section.strip_prefix(name)?.strip_prefix('.'))
+            .filter(|instance_name| is_valid_instance_name(instance_name))
+            .collect();
+        instance_names.sort();
+        for instance_name in instance_names {
+            let section = format!("{name}.{instance_name}");
+            let settings = sections.get(&section).unwrap_or(&default);
+            match registry.load_instance(name, instance_name, settings) {
+                Ok(platform) => platforms.push(platform),
+                Err(e) => eprintln!("Could not load platform {section}, gave error: {e}"),
+            }
+        }
     }
     platforms
 }
 
+/// Instance names become part of a section name in the config file, so they are kept simple
+pub fn is_valid_instance_name(instance_name: &str) -> bool {
+    !instance_name.is_empty()
+        && instance_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
 pub fn load_settings<Setting, S: AsRef<str>>(input: S) -> Setting
 where
     Setting: Default,
@@ -122,13 +228,13 @@ where
     }
 }
 
-fn load<T>(s: &str) -> eyre::Result<Box<dyn GamesPlatform>>
+fn load<T>(s: &str) -> Box<dyn GamesPlatform>
 where
     T: FromSettingsString,
     T: GamesPlatform,
     T: 'static,
 {
-    Ok(Box::new(T::from_settings_string(s)))
+    Box::new(T::from_settings_string(s))
 }
 
 pub trait FromSettingsString {
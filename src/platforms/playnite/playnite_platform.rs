@@ -5,6 +5,7 @@ use std::{
 };
 use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
 
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
 use crate::platforms::{load_settings, to_shortcuts_simple, FromSettingsString, GamesPlatform};
 
 use super::playnite_parser::parse_db;
@@ -62,6 +63,20 @@ impl GamesPlatform for PlaynitePlatform {
         toml::to_string(&self.settings).unwrap_or_default()
     }
 
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        match self.find_paths() {
+            Ok((launcher_path, games_file_path)) => vec![
+                DiagnosticCheck::path("Playnite launcher", launcher_path, true),
+                DiagnosticCheck::path("Playnite games database", games_file_path, true),
+            ],
+            Err(err) => vec![DiagnosticCheck::message(
+                CheckStatus::Error,
+                "Playnite",
+                format!("Could not find Playnite: {err}"),
+            )],
+        }
+    }
+
     fn render_ui(&mut self, ui: &mut egui::Ui) {
         ui.heading("Playnite");
         ui.checkbox(&mut self.settings.enabled, "Import from Playnite");
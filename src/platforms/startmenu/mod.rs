@@ -0,0 +1,5 @@
+mod platform;
+mod settings;
+mod shell_link;
+
+pub use platform::StartMenuPlatform;
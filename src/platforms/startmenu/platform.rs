@@ -0,0 +1,378 @@
+use std::path::{Path, PathBuf};
+
+use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
+
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+use crate::platforms::{
+    executable_needs_proton, load_settings, to_shortcuts, wine_prefix, FromSettingsString,
+    GameMetadata, GamesPlatform, NeedsProton, ShortcutToImport,
+};
+
+use super::{
+    settings::StartMenuSettings,
+    shell_link::{parse_shell_link, ShellLink},
+};
+
+/// Words in the name of a shortcut (or its target) that tells us it is not the game itself
+const NOT_GAME_WORDS: [&str; 8] = [
+    "uninstall",
+    "unins0",
+    "readme",
+    "read me",
+    "manual",
+    "license",
+    "website",
+    "support",
+];
+
+#[derive(Clone)]
+pub struct StartMenuPlatform {
+    pub settings: StartMenuSettings,
+}
+
+#[derive(Clone, Debug)]
+pub struct StartMenuGame {
+    pub name: String,
+    pub exe: PathBuf,
+    pub start_dir: PathBuf,
+    pub arguments: Option<String>,
+    pub icon: Option<PathBuf>,
+    pub compat_folder: PathBuf,
+}
+
+impl From<StartMenuGame> for ShortcutOwned {
+    fn from(game: StartMenuGame) -> Self {
+        let mut launch = format!(
+            "STEAM_COMPAT_DATA_PATH=\"{}\" %command%",
+            game.compat_folder.to_string_lossy()
+        );
+        if let Some(arguments) = game.arguments {
+            launch = format!("{launch} {arguments}");
+        }
+        let exe = format!("\"{}\"", game.exe.to_string_lossy());
+        let start_dir = game.start_dir.to_string_lossy();
+        let icon = game
+            .icon
+            .map(|icon| icon.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Shortcut::new("0", &game.name, &exe, &start_dir, &icon, "", &launch).to_owned()
+    }
+}
+
+impl NeedsProton<StartMenuPlatform> for StartMenuGame {
+    fn needs_proton(&self, _platform: &StartMenuPlatform) -> bool {
+        executable_needs_proton(self.exe.to_string_lossy()).unwrap_or(true)
+    }
+
+    fn create_symlinks(&self, _platform: &StartMenuPlatform) -> bool {
+        false
+    }
+
+    fn metadata(&self, _platform: &StartMenuPlatform) -> Option<GameMetadata> {
+        Some(GameMetadata {
+            install_path: Some(self.start_dir.to_string_lossy().to_string()),
+            native: Some(false),
+            ..Default::default()
+        })
+    }
+}
+
+impl StartMenuPlatform {
+    fn get_start_menu_games(&self) -> eyre::Result<Vec<StartMenuGame>> {
+        let prefixes: Vec<&String> = self
+            .settings
+            .prefixes
+            .iter()
+            .filter(|p| !p.trim().is_empty())
+            .collect();
+        if prefixes.is_empty() {
+            return Err(eyre::format_err!("No wine prefixes configured"));
+        }
+        let mut games: Vec<StartMenuGame> = vec![];
+        for configured in prefixes {
+            let configured = Path::new(configured);
+            let prefix = wine_prefix(configured);
+            let compat_folder = compat_data_path(configured);
+            for link_path in find_start_menu_links(&prefix) {
+                let name = link_path
+                    .file_stem()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let link = match std::fs::read(&link_path)
+                    .map_err(eyre::Report::from)
+                    .and_then(|content| parse_shell_link(&content))
+                {
+                    Ok(link) => link,
+                    Err(err) => {
+                        eprintln!("Could not read shortcut {link_path:?}: {err}");
+                        continue;
+                    }
+                };
+                if !is_game_link(&name, &link) {
+                    continue;
+                }
+                let exe = windows_path_in_prefix(&prefix, &link.target);
+                let start_dir = match &link.working_dir {
+                    Some(working_dir) => windows_path_in_prefix(&prefix, working_dir),
+                    None => exe.parent().unwrap_or_else(|| Path::new("")).to_path_buf(),
+                };
+                let icon = link
+                    .icon_location
+                    .as_ref()
+                    .filter(|icon| has_extension(icon, "ico"))
+                    .map(|icon| windows_path_in_prefix(&prefix, icon));
+                let already_found = games
+                    .iter()
+                    .any(|g| g.exe == exe && g.arguments == link.arguments);
+                if !already_found {
+                    games.push(StartMenuGame {
+                        name,
+                        exe,
+                        start_dir,
+                        arguments: link.arguments,
+                        icon,
+                        compat_folder: compat_folder.clone(),
+                    });
+                }
+            }
+        }
+        Ok(games)
+    }
+}
+
+/// Proton wants the folder that contains the "pfx" folder in STEAM_COMPAT_DATA_PATH
+fn compat_data_path(configured: &Path) -> PathBuf {
+    if configured.file_name().map(|n| n == "pfx").unwrap_or(false) {
+        if let Some(parent) = configured.parent() {
+            return parent.to_path_buf();
+        }
+    }
+    configured.to_path_buf()
+}
+
+fn find_start_menu_links(prefix: &Path) -> Vec<PathBuf> {
+    let drive_c = prefix.join("drive_c");
+    let mut start_menus = vec![drive_c
+        .join("ProgramData")
+        .join("Microsoft")
+        .join("Windows")
+        .join("Start Menu")];
+    if let Ok(users) = drive_c.join("users").read_dir() {
+        for user in users.flatten() {
+            start_menus.push(
+                user.path()
+                    .join("AppData")
+                    .join("Roaming")
+                    .join("Microsoft")
+                    .join("Windows")
+                    .join("Start Menu"),
+            );
+        }
+    }
+    let mut result = vec![];
+    for start_menu in start_menus {
+        find_links_in_folder(&start_menu, &mut result);
+    }
+    result
+}
+
+fn find_links_in_folder(folder: &Path, result: &mut Vec<PathBuf>) {
+    if let Ok(entries) = folder.read_dir() {
+        let mut entries: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() {
+                find_links_in_folder(&entry, result);
+            } else if has_extension(&entry.to_string_lossy(), "lnk") {
+                result.push(entry);
+            }
+        }
+    }
+}
+
+fn has_extension(path: &str, extension: &str) -> bool {
+    path.rsplit_once('.')
+        .map(|(_, ext)| ext.eq_ignore_ascii_case(extension))
+        .unwrap_or(false)
+}
+
+fn is_game_link(name: &str, link: &ShellLink) -> bool {
+    if !has_extension(&link.target, "exe") {
+        //Readmes, manuals and websites are not started through an exe
+        return false;
+    }
+    let target = link.target.to_lowercase().replace('/', "\\");
+    if target.starts_with("c:\\windows\\") {
+        //Links to notepad, winhlp32 and the like
+        return false;
+    }
+    let target_file_name = target.rsplit('\\').next().unwrap_or_default();
+    let name = name.to_lowercase();
+    !NOT_GAME_WORDS
+        .iter()
+        .any(|word| name.contains(word) || target_file_name.contains(word))
+}
+
+/// Maps a path like C:\Games\game.exe to the same file inside the wine prefix
+fn windows_path_in_prefix(prefix: &Path, windows_path: &str) -> PathBuf {
+    let drive = windows_path
+        .get(0..2)
+        .filter(|d| d.ends_with(':'))
+        .map(|d| d.to_lowercase());
+    let rest = windows_path
+        .get(2..)
+        .unwrap_or_default()
+        .trim_start_matches(['\\', '/'])
+        .replace('\\', "/");
+    match drive {
+        Some(drive) => {
+            let dos_device = prefix.join("dosdevices").join(&drive);
+            if drive == "c:" && !dos_device.exists() {
+                prefix.join("drive_c").join(rest)
+            } else {
+                dos_device.join(rest)
+            }
+        }
+        None => Path::new(windows_path).to_path_buf(),
+    }
+}
+
+impl FromSettingsString for StartMenuPlatform {
+    fn from_settings_string<S: AsRef<str>>(s: S) -> Self {
+        StartMenuPlatform {
+            settings: load_settings(s),
+        }
+    }
+}
+
+impl GamesPlatform for StartMenuPlatform {
+    fn name(&self) -> &str {
+        "Wine Start Menu"
+    }
+
+    fn enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    fn get_shortcut_info(&self) -> eyre::Result<Vec<ShortcutToImport>> {
+        to_shortcuts(self, self.get_start_menu_games())
+    }
+
+    fn render_ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Wine Start Menu");
+        ui.checkbox(
+            &mut self.settings.enabled,
+            "Import Start Menu shortcuts from wine prefixes",
+        );
+        if self.settings.enabled {
+            ui.label("Games installed into these prefixes will be launched with Proton, add a Steam compatdata folder or a wine prefix.");
+            let mut to_remove = None;
+            for (index, prefix) in self.settings.prefixes.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label("Prefix: ");
+                    ui.text_edit_singleline(prefix);
+                    if ui.button("Remove").clicked() {
+                        to_remove = Some(index);
+                    }
+                });
+            }
+            if let Some(index) = to_remove {
+                self.settings.prefixes.remove(index);
+            }
+            if ui.button("Add prefix").clicked() {
+                self.settings.prefixes.push(String::new());
+            }
+        }
+    }
+
+    fn get_settings_serializable(&self) -> String {
+        toml::to_string(&self.settings).unwrap_or_default()
+    }
+
+    fn code_name(&self) -> &str {
+        "wine_start_menu"
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let prefixes: Vec<&String> = self
+            .settings
+            .prefixes
+            .iter()
+            .filter(|p| !p.trim().is_empty())
+            .collect();
+        if prefixes.is_empty() {
+            return vec![DiagnosticCheck::message(
+                CheckStatus::Error,
+                "Wine prefixes",
+                "No wine prefixes configured",
+            )];
+        }
+        let mut checks = vec![];
+        for configured in prefixes {
+            let prefix = wine_prefix(Path::new(configured));
+            let drive_c = prefix.join("drive_c");
+            let found = drive_c.exists();
+            checks.push(DiagnosticCheck::path("Wine prefix", drive_c, true));
+            if found {
+                let links = find_start_menu_links(&prefix).len();
+                let status = if links == 0 {
+                    CheckStatus::Warning
+                } else {
+                    CheckStatus::Ok
+                };
+                checks.push(DiagnosticCheck::message(
+                    status,
+                    "Start Menu shortcuts",
+                    format!("Found {links} shortcuts in {}", prefix.to_string_lossy()),
+                ));
+            }
+        }
+        checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(target: &str) -> ShellLink {
+        ShellLink {
+            target: target.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn skips_uninstallers_and_readmes() {
+        assert!(is_game_link("Hollow Knight", &link("C:\\Games\\hk.exe")));
+        assert!(!is_game_link(
+            "Uninstall Hollow Knight",
+            &link("C:\\Games\\hk.exe")
+        ));
+        assert!(!is_game_link(
+            "Hollow Knight",
+            &link("C:\\Games\\unins000.exe")
+        ));
+        assert!(!is_game_link("Readme", &link("C:\\Games\\readme.txt")));
+        assert!(!is_game_link("Readme", &link("C:\\windows\\notepad.exe")));
+    }
+
+    #[test]
+    fn maps_windows_paths_into_the_prefix() {
+        let prefix = Path::new("/this/prefix/does/not/exist/pfx");
+        assert_eq!(
+            prefix
+                .join("drive_c")
+                .join("GOG Games/Hollow Knight/hk.exe"),
+            windows_path_in_prefix(prefix, "C:\\GOG Games\\Hollow Knight\\hk.exe")
+        );
+        assert_eq!(
+            prefix.join("dosdevices").join("d:").join("Games/hk.exe"),
+            windows_path_in_prefix(prefix, "D:\\Games\\hk.exe")
+        );
+        assert_eq!(
+            Path::new("/this/prefix/does/not/exist"),
+            compat_data_path(prefix)
+        );
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct StartMenuSettings {
+    pub enabled: bool,
+    /// The wine prefixes (or Steam compatdata folders) to import Start Menu shortcuts from
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+}
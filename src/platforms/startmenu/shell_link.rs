@@ -0,0 +1,197 @@
+//! A reader for the parts of Windows Shell Link (.lnk) files that we need to launch the target.
+//! The format is described in [MS-SHLLINK]:
+//! https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-shllink/
+
+const HEADER_SIZE: usize = 0x4C;
+
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x1;
+const HAS_LINK_INFO: u32 = 0x2;
+const HAS_NAME: u32 = 0x4;
+const HAS_RELATIVE_PATH: u32 = 0x8;
+const HAS_WORKING_DIR: u32 = 0x10;
+const HAS_ARGUMENTS: u32 = 0x20;
+const HAS_ICON_LOCATION: u32 = 0x40;
+const IS_UNICODE: u32 = 0x80;
+
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x1;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub(crate) struct ShellLink {
+    /// The windows path of the target, fx C:\Games\Game\game.exe
+    pub(crate) target: String,
+    pub(crate) arguments: Option<String>,
+    pub(crate) working_dir: Option<String>,
+    pub(crate) icon_location: Option<String>,
+}
+
+pub(crate) fn parse_shell_link(data: &[u8]) -> eyre::Result<ShellLink> {
+    let header_size = read_u32(data, 0)?;
+    if usize::try_from(header_size)? != HEADER_SIZE {
+        return Err(eyre::format_err!("Not a shell link file"));
+    }
+    let flags = read_u32(data, 0x14)?;
+    let mut offset = HEADER_SIZE;
+
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        let id_list_size = read_u16(data, offset)?;
+        offset += 2 + usize::from(id_list_size);
+    }
+
+    let mut target = None;
+    if flags & HAS_LINK_INFO != 0 {
+        let link_info = data
+            .get(offset..)
+            .ok_or(eyre::format_err!("Shell link is missing its link info"))?;
+        let link_info_size = usize::try_from(read_u32(link_info, 0)?)?;
+        let link_info = link_info
+            .get(..link_info_size)
+            .ok_or(eyre::format_err!("Shell link info is too short"))?;
+        target = parse_link_info(link_info)?;
+        offset += link_info_size;
+    }
+
+    let unicode = flags & IS_UNICODE != 0;
+    let mut read_string_data = |flag: u32| -> eyre::Result<Option<String>> {
+        if flags & flag == 0 {
+            return Ok(None);
+        }
+        let (value, read) = read_counted_string(data, offset, unicode)?;
+        offset += read;
+        Ok(Some(value))
+    };
+    let _name = read_string_data(HAS_NAME)?;
+    let relative_path = read_string_data(HAS_RELATIVE_PATH)?;
+    let working_dir = read_string_data(HAS_WORKING_DIR)?;
+    let arguments = read_string_data(HAS_ARGUMENTS)?;
+    let icon_location = read_string_data(HAS_ICON_LOCATION)?;
+
+    let target = target
+        .or(relative_path)
+        .ok_or(eyre::format_err!("Shell link has no target"))?;
+    Ok(ShellLink {
+        target,
+        arguments: arguments.filter(|a| !a.is_empty()),
+        working_dir: working_dir.filter(|w| !w.is_empty()),
+        icon_location: icon_location.filter(|i| !i.is_empty()),
+    })
+}
+
+fn parse_link_info(link_info: &[u8]) -> eyre::Result<Option<String>> {
+    let header_size = read_u32(link_info, 4)?;
+    let link_info_flags = read_u32(link_info, 8)?;
+    if link_info_flags & VOLUME_ID_AND_LOCAL_BASE_PATH == 0 {
+        return Ok(None);
+    }
+    //Newer links also contain the paths as unicode
+    let (base_path, suffix) = if header_size >= 0x24 {
+        let base_path_offset = usize::try_from(read_u32(link_info, 0x1C)?)?;
+        let suffix_offset = usize::try_from(read_u32(link_info, 0x20)?)?;
+        (
+            read_null_terminated_unicode(link_info, base_path_offset)?,
+            read_null_terminated_unicode(link_info, suffix_offset)?,
+        )
+    } else {
+        let base_path_offset = usize::try_from(read_u32(link_info, 0x10)?)?;
+        let suffix_offset = usize::try_from(read_u32(link_info, 0x18)?)?;
+        (
+            read_null_terminated_ansi(link_info, base_path_offset)?,
+            read_null_terminated_ansi(link_info, suffix_offset)?,
+        )
+    };
+    Ok(Some(format!("{base_path}{suffix}")))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> eyre::Result<u16> {
+    let bytes = data
+        .get(offset..offset + 2)
+        .and_then(|b| <[u8; 2]>::try_from(b).ok())
+        .ok_or(eyre::format_err!("Unexpected end of shell link"))?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> eyre::Result<u32> {
+    let bytes = data
+        .get(offset..offset + 4)
+        .and_then(|b| <[u8; 4]>::try_from(b).ok())
+        .ok_or(eyre::format_err!("Unexpected end of shell link"))?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Reads a string that starts with its length in characters, returns the string and the number of bytes read
+fn read_counted_string(data: &[u8], offset: usize, unicode: bool) -> eyre::Result<(String, usize)> {
+    let count = usize::from(read_u16(data, offset)?);
+    let start = offset + 2;
+    if unicode {
+        let bytes = data
+            .get(start..start + count * 2)
+            .ok_or(eyre::format_err!("Unexpected end of shell link"))?;
+        Ok((decode_utf16(bytes), 2 + count * 2))
+    } else {
+        let bytes = data
+            .get(start..start + count)
+            .ok_or(eyre::format_err!("Unexpected end of shell link"))?;
+        Ok((String::from_utf8_lossy(bytes).to_string(), 2 + count))
+    }
+}
+
+fn read_null_terminated_ansi(data: &[u8], offset: usize) -> eyre::Result<String> {
+    let bytes = data
+        .get(offset..)
+        .ok_or(eyre::format_err!("Unexpected end of shell link"))?;
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    let (value, _) = bytes.split_at(end);
+    Ok(String::from_utf8_lossy(value).to_string())
+}
+
+fn read_null_terminated_unicode(data: &[u8], offset: usize) -> eyre::Result<String> {
+    let bytes = data
+        .get(offset..)
+        .ok_or(eyre::format_err!("Unexpected end of shell link"))?;
+    let end = bytes
+        .chunks_exact(2)
+        .position(|c| c == [0, 0])
+        .map(|p| p * 2)
+        .unwrap_or(bytes.len());
+    let (value, _) = bytes.split_at(end);
+    Ok(decode_utf16(value))
+}
+
+fn decode_utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| {
+            u16::from_le_bytes([
+                c.first().copied().unwrap_or(0),
+                c.last().copied().unwrap_or(0),
+            ])
+        })
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn can_parse_shell_link() {
+        let content = include_bytes!("../../testdata/startmenu/Hollow Knight.lnk");
+        let link = parse_shell_link(content).unwrap();
+        assert_eq!(
+            "C:\\GOG Games\\Hollow Knight\\hollow_knight.exe",
+            link.target
+        );
+        assert_eq!(Some("-popupwindow".to_string()), link.arguments);
+        assert_eq!(
+            Some("C:\\GOG Games\\Hollow Knight".to_string()),
+            link.working_dir
+        );
+        assert_eq!(None, link.icon_location);
+    }
+
+    #[test]
+    fn not_a_shell_link_is_an_error() {
+        assert!(parse_shell_link(b"[InternetShortcut]\nURL=https://example.com").is_err());
+    }
+}
@@ -3,6 +3,7 @@
 use std::path::Path;
 use std::path::PathBuf;
 
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
 use crate::platforms::load_settings;
 use crate::platforms::to_shortcuts_simple;
 use crate::platforms::FromSettingsString;
@@ -59,13 +60,7 @@ struct UplayPathData {
 #[cfg(target_family = "unix")]
 fn get_launcher_path() -> eyre::Result<UplayPathData> {
     let mut res = UplayPathData::default();
-    if let Ok(home) = std::env::var("HOME") {
-        let compat_folder_path = Path::new(&home)
-            .join(".steam")
-            .join("steam")
-            .join("steamapps")
-            .join("compatdata");
-
+    for compat_folder_path in crate::steam::detected_compatdata_folders() {
         if let Ok(compat_folder) = std::fs::read_dir(compat_folder_path) {
             for dir in compat_folder.flatten() {
                 let uplay_exe_path = dir
@@ -173,11 +168,7 @@ fn get_games_from_proton() -> eyre::Result<Vec<UplayGame>> {
         .exe_path
         .parent()
         .unwrap_or_else(|| Path::new("/"));
-    let file = parent
-        .join("cache")
-        .join("configuration")
-        .join("configurations");
-    let buffer = std::fs::read(file)?;
+    let buffer = std::fs::read(configurations_path(&launcher_path.exe_path))?;
     let splits = get_file_splits(&buffer);
     let configurations = splits.iter().filter(|s| is_valid_game_config(s));
     let parsed_configurations= configurations.flat_map(|config| parse_game_config(config));
@@ -199,6 +190,17 @@ fn get_games_from_proton() -> eyre::Result<Vec<UplayGame>> {
     Ok(games.collect())
 }
 
+/// The file the launcher caches the configuration of every Ubisoft game in
+#[cfg(target_family = "unix")]
+fn configurations_path(exe_path: &Path) -> PathBuf {
+    exe_path
+        .parent()
+        .unwrap_or_else(|| Path::new("/"))
+        .join("cache")
+        .join("configuration")
+        .join("configurations")
+}
+
 struct GameConfig<'a> {
     icon_image: &'a str,
     shortcut_name: &'a str,
@@ -303,6 +305,44 @@ impl GamesPlatform for UplayPlatform {
     fn code_name(&self) -> &str {
         "uplay"
     }
+
+    fn can_need_proton(&self) -> bool {
+        false
+    }
+
+    fn diagnose(&self) -> Vec<DiagnosticCheck> {
+        let launcher_path = match get_launcher_path() {
+            Ok(launcher_path) => launcher_path,
+            Err(err) => {
+                return vec![DiagnosticCheck::message(
+                    CheckStatus::Error,
+                    "Uplay launcher",
+                    format!("{err}"),
+                )]
+            }
+        };
+        let mut checks = vec![];
+        #[cfg(target_family = "unix")]
+        if let Some(compat_folder) = launcher_path.compat_folder {
+            checks.push(DiagnosticCheck::path(
+                "Uplay wine prefix",
+                compat_folder,
+                true,
+            ));
+        }
+        #[cfg(target_family = "unix")]
+        checks.push(DiagnosticCheck::path(
+            "Uplay game configurations",
+            configurations_path(&launcher_path.exe_path),
+            true,
+        ));
+        checks.push(DiagnosticCheck::path(
+            "Uplay launcher",
+            launcher_path.exe_path,
+            true,
+        ));
+        checks
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+/// The folders where BoilR looks for wine prefixes without being told about them.
+/// These are the Steam compatdata folders and the default prefix locations of Lutris, Bottles and Wine.
+pub fn wine_prefix_candidates() -> Vec<PathBuf> {
+    let mut result = vec![];
+    if let Ok(home) = std::env::var("HOME") {
+        let home = Path::new(&home);
+        let mut parent_folders = crate::steam::detected_compatdata_folders();
+        parent_folders.extend([
+            home.join("Games"),
+            home.join(".local")
+                .join("share")
+                .join("bottles")
+                .join("bottles"),
+            home.join(".var")
+                .join("app")
+                .join("com.usebottles.bottles")
+                .join("data")
+                .join("bottles")
+                .join("bottles"),
+        ]);
+        for parent_folder in parent_folders {
+            if let Ok(dirs) = std::fs::read_dir(parent_folder) {
+                let mut dirs: Vec<PathBuf> = dirs.flatten().map(|d| d.path()).collect();
+                dirs.sort();
+                result.extend(dirs);
+            }
+        }
+        result.push(home.join(".wine"));
+    }
+    result
+}
+
+/// Steam compatdata folders keep the wine prefix in a "pfx" sub folder,
+/// prefixes from Lutris, Bottles and plain Wine are the folder itself.
+pub fn wine_prefix(compat_folder: &Path) -> PathBuf {
+    let pfx = compat_folder.join("pfx");
+    if pfx.exists() {
+        pfx
+    } else {
+        compat_folder.to_path_buf()
+    }
+}
+
+/// Lets the user add wine prefixes to the ones BoilR finds by itself.
+/// Returns true if the prefixes changed.
+pub fn render_extra_prefixes(prefixes: &mut Vec<String>, ui: &mut egui::Ui) -> bool {
+    let mut prefixes_changed = false;
+    ui.label("BoilR looks in the Steam compatdata folders and the default Lutris, Bottles and Wine prefixes, add other prefixes below.");
+    let mut to_remove = None;
+    for (index, prefix) in prefixes.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label("Prefix: ");
+            if ui.text_edit_singleline(prefix).lost_focus() {
+                prefixes_changed = true;
+            }
+            if ui.button("Remove").clicked() {
+                to_remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = to_remove {
+        prefixes.remove(index);
+        prefixes_changed = true;
+    }
+    if ui.button("Add prefix").clicked() {
+        prefixes.push(String::new());
+    }
+    prefixes_changed
+}
@@ -7,13 +7,27 @@ use config::{Config, ConfigError, Environment, File};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, env};
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct Settings {
     pub debug: bool,
     pub config_version: Option<usize>,
     pub blacklisted_games: Vec<u32>,
     pub steamgrid_db: SteamGridDbSettings,
     pub steam: SteamSettings,
+    /// Choices for single Steam users, by their user id
+    #[serde(default)]
+    pub steam_users: HashMap<String, SteamUserSettings>,
+}
+
+/// Choices for a single Steam user, on top of the choices for everyone
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SteamUserSettings {
+    /// The platforms that are not synced for the user, by their code name
+    #[serde(default)]
+    pub disabled_platforms: Vec<String>,
+    /// Games that are not synced for the user, the games in blacklisted_games are not synced for anyone
+    #[serde(default)]
+    pub blacklisted_games: Vec<u32>,
 }
 
 impl Settings {
@@ -42,6 +56,23 @@ impl Settings {
         sanitize_auth_key(&mut settings);
         Ok(settings)
     }
+
+    /// If a game is left out for the user, either for everyone or just for them
+    pub fn is_blacklisted_for(&self, user_id: &str, app_id: u32) -> bool {
+        self.blacklisted_games.contains(&app_id)
+            || self
+                .steam_users
+                .get(user_id)
+                .is_some_and(|user| user.blacklisted_games.contains(&app_id))
+    }
+
+    pub fn is_platform_enabled_for(&self, user_id: &str, code_name: &str) -> bool {
+        !self.steam_users.get(user_id).is_some_and(|user| {
+            user.disabled_platforms
+                .iter()
+                .any(|platform| platform == code_name)
+        })
+    }
 }
 
 pub fn load_setting_sections() -> eyre::Result<HashMap<String, String>> {
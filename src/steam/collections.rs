@@ -1,13 +1,17 @@
-use nom::FindSubstring;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+
+use super::{parse_vdf, SteamInstallation};
+
 use rusty_leveldb::{LdbIterator, Options, WriteBatch, DB};
 
 const BOILR_TAG: &str = "boilr";
+const USER_COLLECTIONS_PREFIX: &str = "user-collections.";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
@@ -44,15 +48,12 @@ struct ActualSteamCollection {
 impl ActualSteamCollection {
     fn new<B: AsRef<str>>(name: B, ids: &[usize]) -> Self {
         let name = name.as_ref();
-        let key = format!("user-collections.{}", name_to_key(name));
+        let key = format!("{USER_COLLECTIONS_PREFIX}{}", name_to_key(name));
         let value = serialize_collection_value(name, ids);
-        let start = SystemTime::now();
-        let since_the_epoch = start.duration_since(UNIX_EPOCH).unwrap_or_default();
-        let timestamp = since_the_epoch.as_secs();
 
         ActualSteamCollection {
             key,
-            timestamp,
+            timestamp: now(),
             value,
             conflict_resolution_method: Some("custom".to_string()),
             str_method_id: Some("union-collections".to_string()),
@@ -62,10 +63,17 @@ impl ActualSteamCollection {
 
     pub fn is_boilr_collection(&self) -> bool {
         self.key
-            .contains(&format!("user-collections.{BOILR_TAG}"))
+            .contains(&format!("{USER_COLLECTIONS_PREFIX}{BOILR_TAG}"))
     }
 }
 
+fn now() -> u64 {
+    let since_the_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    since_the_epoch.as_secs()
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct ValueCollection {
     id: String,
@@ -92,121 +100,294 @@ impl ValueCollection {
 
 struct DeletedCollection {
     key: String,
-    timestamp: usize,
+    timestamp: u64,
     is_deleted: bool,
     version: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Collection {
     pub name: String,
     pub game_ids: Vec<usize>,
 }
 
+/// A collection as Steam keeps it for a user, fx "Favorites" or one that BoilR made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SteamCollectionInfo {
+    /// The id Steam knows the collection by, the ids of BoilR's collections start with "boilr-"
+    pub id: String,
+    pub name: String,
+    pub game_ids: Vec<usize>,
+    /// Steam fills dynamic collections from a filter, their games can not be chosen
+    pub is_dynamic: bool,
+    /// Only in localconfig.vdf, which keeps no name for the collection, so it can not be renamed
+    pub is_local_only: bool,
+}
+
+impl SteamCollectionInfo {
+    pub fn is_boilr_collection(&self) -> bool {
+        self.id.starts_with(BOILR_TAG)
+    }
+
+    /// Favorites and Hidden are part of Steam, they can not be renamed or deleted
+    pub fn is_built_in(&self) -> bool {
+        matches!(self.id.as_str(), "favorite" | "hidden")
+    }
+}
+
+pub enum CollectionChange {
+    Update { name: String, game_ids: Vec<usize> },
+    Delete,
+}
+
+/// The part of the value of a collection that is shown, Steam keeps more in it
+#[derive(Deserialize)]
+struct CollectionValue {
+    name: String,
+    #[serde(default)]
+    added: Vec<usize>,
+    #[serde(rename = "filterSpec")]
+    filter_spec: Option<serde_json::Value>,
+}
+
 pub fn write_collections<S: AsRef<str>>(
+    installation: &SteamInstallation,
     steam_user_id: S,
     collections_to_add: &[Collection],
 ) -> Result<(), Box<dyn Error>> {
     let steam_user_id = steam_user_id.as_ref();
-    let new_collections: Vec<(String, SteamCollection)> = collections_to_add
-        .iter()
-        .map(|c| {
-            let collection = ActualSteamCollection::new(&c.name, &c.game_ids);
-            (collection.key.clone(), SteamCollection::Actual(collection))
-        })
-        .collect();
-
-    let mut db = open_db()?;
+    let mut db = open_db(installation)?;
 
     let current_categories = get_categories(steam_user_id, &mut db)?;
     //this is a collection of collections, known as a category
     let mut write_batch = WriteBatch::new();
 
     for (category_key, mut collections) in current_categories {
-        collections.retain(|(_key, collection)| !collection.is_boilr_collection());
-        collections.extend(new_collections.clone());
+        merge_boilr_collections(&mut collections, collections_to_add);
         save_category(category_key, collections, &mut write_batch)?;
+    }
 
-        if let Some(path) = get_vdf_path(steam_user_id) {
-            let content = std::fs::read_to_string(&path)
-                .ok()
-                .and_then(parse_vdf_collection);
-            if let Some(mut vdf_collections) = content {
-                let boilr_keys: Vec<String> = vdf_collections
-                    .keys()
-                    .filter(|k| k.contains(BOILR_TAG))
-                    .cloned()
-                    .collect();
-                for key in boilr_keys {
-                    vdf_collections.remove(&key);
-                }
+    edit_localconfig_collections(installation, steam_user_id, |vdf_collections| {
+        vdf_collections.retain(|key, _| !key.contains(BOILR_TAG));
+        for collection in collections_to_add {
+            let key = name_to_key(&collection.name);
+            let new_vdf = VdfCollection {
+                id: key.clone(),
+                added: collection.game_ids.clone(),
+                removed: vec![],
+            };
+            vdf_collections.insert(key, new_vdf);
+        }
+    })?;
 
-                let new_vdfs = collections_to_add.iter().map(|collection| {
-                    let key = name_to_key(&collection.name);
+    db.write(write_batch, true)?;
 
-                    VdfCollection {
-                        id: key,
-                        added: collection.game_ids.clone(),
-                        removed: vec![],
-                    }
-                });
-                for new_vdf in new_vdfs {
-                    vdf_collections.insert(new_vdf.id.clone(), new_vdf.clone());
-                }
+    Ok(())
+}
 
-                let new_string = write_vdf_collection_to_string(
-                    path.clone().to_string_lossy(),
-                    &vdf_collections,
-                );
-                if let Some(new_string) = new_string {
-                    std::fs::write(path, new_string)?;
-                }
+/// The collections of a user, both the ones in the level database and the ones only in localconfig.vdf
+pub fn read_collections<S: AsRef<str>>(
+    installation: &SteamInstallation,
+    steam_user_id: S,
+) -> Result<Vec<SteamCollectionInfo>, Box<dyn Error>> {
+    let steam_user_id = steam_user_id.as_ref();
+    let mut db = open_db(installation)?;
+    let mut collections: Vec<SteamCollectionInfo> = vec![];
+    for (_category_key, category) in get_categories(steam_user_id, &mut db)? {
+        for collection in collection_infos(&category) {
+            if !collections.iter().any(|c| c.id == collection.id) {
+                collections.push(collection);
             }
         }
     }
+    let localconfig = std::fs::read_to_string(installation.localconfig_path(steam_user_id));
+    let vdf_collections = localconfig.ok().and_then(read_vdf_collections);
+    for (id, vdf_collection) in vdf_collections.unwrap_or_default() {
+        if !collections.iter().any(|c| c.id == id) {
+            collections.push(SteamCollectionInfo {
+                name: id.clone(),
+                id,
+                game_ids: vdf_collection.added,
+                is_dynamic: false,
+                is_local_only: true,
+            });
+        }
+    }
+    collections.sort_by_key(|c| c.name.to_lowercase());
+    Ok(collections)
+}
+
+/// Renames, changes the games of or deletes a collection of a user, Steam must not be running
+pub fn change_collection<S: AsRef<str>>(
+    installation: &SteamInstallation,
+    steam_user_id: S,
+    collection_id: &str,
+    change: &CollectionChange,
+) -> Result<(), Box<dyn Error>> {
+    let steam_user_id = steam_user_id.as_ref();
+    let mut db = open_db(installation)?;
+    let mut write_batch = WriteBatch::new();
+    for (category_key, mut category) in get_categories(steam_user_id, &mut db)? {
+        if change_category(&mut category, collection_id, change) {
+            save_category(category_key, category, &mut write_batch)?;
+        }
+    }
 
-    db.write(write_batch, true)?;
+    edit_localconfig_collections(
+        installation,
+        steam_user_id,
+        |vdf_collections| match change {
+            CollectionChange::Update { game_ids, .. } => {
+                if let Some(vdf_collection) = vdf_collections.get_mut(collection_id) {
+                    vdf_collection.added = game_ids.clone();
+                }
+            }
+            CollectionChange::Delete => {
+                vdf_collections.remove(collection_id);
+            }
+        },
+    )?;
 
+    db.write(write_batch, true)?;
     Ok(())
 }
 
-#[cfg(target_family = "unix")]
-fn get_vdf_path<S: AsRef<str>>(steamid: S) -> Option<PathBuf> {
-    match std::env::var("HOME") {
-        Ok(home) => {
-            let path = Path::new(&home)
-                .join(".steam")
-                .join("steam")
-                .join("userdata")
-                .join(steamid.as_ref())
-                .join("config")
-                .join("localconfig.vdf");
-            if path.exists() {
-                return Some(path);
+/// Puts BoilR's collections into a category, the BoilR collections that are no longer made are taken out.
+/// A collection that is already there keeps the name the user gave it in Steam, only its games are changed.
+/// The tombstone of a deleted collection is replaced, Steam must not find the same key twice.
+fn merge_boilr_collections(
+    category: &mut Vec<(String, SteamCollection)>,
+    collections_to_add: &[Collection],
+) {
+    let new_collections: Vec<ActualSteamCollection> = collections_to_add
+        .iter()
+        .map(|c| ActualSteamCollection::new(&c.name, &c.game_ids))
+        .collect();
+    let is_new_key = |key: &String| new_collections.iter().any(|new| &new.key == key);
+    category.retain(|(key, collection)| match collection {
+        SteamCollection::Deleted(_) => !is_new_key(key),
+        SteamCollection::Actual(_) => !collection.is_boilr_collection() || is_new_key(key),
+    });
+    for (new, to_add) in new_collections.into_iter().zip(collections_to_add) {
+        let existing = category
+            .iter_mut()
+            .find_map(|(key, collection)| match collection {
+                SteamCollection::Actual(actual) if *key == new.key => Some(actual),
+                _ => None,
+            });
+        let Some(existing) = existing else {
+            category.push((new.key.clone(), SteamCollection::Actual(new)));
+            continue;
+        };
+        let name = serde_json::from_str::<CollectionValue>(&existing.value)
+            .map(|value| value.name)
+            .unwrap_or_else(|_| to_add.name.clone());
+        match update_collection_value(&existing.value, &name, &to_add.game_ids) {
+            Some(value) => {
+                existing.value = value;
+                existing.timestamp = now();
             }
-            None
+            None => *existing = new,
         }
-        Err(_e) => None,
     }
 }
 
-#[cfg(target_os = "windows")]
-fn get_vdf_path<S: AsRef<str>>(steamid: S) -> Option<PathBuf> {
-    match std::env::var("PROGRAMFILES(X86)") {
-        Ok(program_files) => {
-            let path = Path::new(&program_files)
-                .join("Steam")
-                .join("userdata")
-                .join(steamid.as_ref())
-                .join("config")
-                .join("localconfig.vdf");
-            if path.exists() {
-                Some(path)
-            } else {
-                None
+fn collection_infos(category: &[(String, SteamCollection)]) -> Vec<SteamCollectionInfo> {
+    category
+        .iter()
+        .filter_map(|(key, collection)| {
+            let id = key.strip_prefix(USER_COLLECTIONS_PREFIX)?;
+            let SteamCollection::Actual(actual) = collection else {
+                return None;
+            };
+            let value: CollectionValue = serde_json::from_str(&actual.value).ok()?;
+            Some(SteamCollectionInfo {
+                id: id.to_string(),
+                name: value.name,
+                game_ids: value.added,
+                is_dynamic: value.filter_spec.is_some(),
+                is_local_only: false,
+            })
+        })
+        .collect()
+}
+
+/// Changes the collection in a category, true if the category had the collection
+fn change_category(
+    category: &mut [(String, SteamCollection)],
+    collection_id: &str,
+    change: &CollectionChange,
+) -> bool {
+    let key = format!("{USER_COLLECTIONS_PREFIX}{collection_id}");
+    let mut had_collection = false;
+    for (_key, collection) in category.iter_mut().filter(|(k, _)| k == &key) {
+        let SteamCollection::Actual(actual) = collection else {
+            continue;
+        };
+        had_collection = true;
+        match change {
+            CollectionChange::Update { name, game_ids } => {
+                if let Some(value) = update_collection_value(&actual.value, name, game_ids) {
+                    actual.value = value;
+                    actual.timestamp = now();
+                }
+            }
+            CollectionChange::Delete => {
+                //Steam syncs the collections with the cloud, without a tombstone the collection comes back from there
+                *collection = SteamCollection::Deleted(DeletedCollection {
+                    key: key.clone(),
+                    timestamp: now(),
+                    is_deleted: true,
+                    version: actual.version.clone().unwrap_or_default(),
+                });
             }
         }
-        Err(_e) => None,
     }
+    had_collection
+}
+
+/// Sets the name and games in the value of a collection, the games that are taken out are marked as removed
+fn update_collection_value(value: &str, name: &str, game_ids: &[usize]) -> Option<String> {
+    let mut value: serde_json::Value = serde_json::from_str(value).ok()?;
+    let object = value.as_object_mut()?;
+    let ids_in = |field: &str| -> Vec<usize> {
+        object
+            .get(field)
+            .and_then(|ids| serde_json::from_value(ids.clone()).ok())
+            .unwrap_or_default()
+    };
+    let mut removed: Vec<usize> = ids_in("added");
+    removed.extend(ids_in("removed"));
+    removed.retain(|id| !game_ids.contains(id));
+    removed.sort_unstable();
+    removed.dedup();
+    object.insert("name".to_string(), name.into());
+    object.insert("added".to_string(), game_ids.into());
+    object.insert("removed".to_string(), removed.into());
+    serde_json::to_string(&value).ok()
+}
+
+/// Lets the collections in localconfig.vdf be changed, nothing happens if the user has none there
+fn edit_localconfig_collections<F>(
+    installation: &SteamInstallation,
+    steam_user_id: &str,
+    edit: F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: FnOnce(&mut HashMap<String, VdfCollection>),
+{
+    let path = installation.localconfig_path(steam_user_id);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let Some(mut vdf_collections) = read_vdf_collections(&content) else {
+        return Ok(());
+    };
+    edit(&mut vdf_collections);
+    if let Some(new_string) = write_vdf_collection_to_string(&content, &vdf_collections) {
+        std::fs::write(path, new_string)?;
+    }
+    Ok(())
 }
 
 fn save_category<S: AsRef<str>>(
@@ -242,9 +423,75 @@ fn get_categories<S: AsRef<str>>(
     Ok(res)
 }
 
-fn open_db() -> eyre::Result<DB> {
+/// Collections can only be written if the level database exists and Steam does not hold its lock.
+/// The database is not opened, so this is safe to run while Steam starts.
+pub fn diagnose_collections_db(installation: &SteamInstallation) -> DiagnosticCheck {
+    let description = "Steam collections database";
+    let location = installation.level_db_folder();
+    if !location.exists() {
+        return DiagnosticCheck::message(
+            CheckStatus::Error,
+            description,
+            "Not found, try to open and close steam once",
+        );
+    }
+    let read_only = std::fs::metadata(&location)
+        .map(|metadata| metadata.permissions().readonly())
+        .unwrap_or(true);
+    if read_only || std::fs::read_dir(&location).is_err() {
+        return DiagnosticCheck::message(
+            CheckStatus::Error,
+            description,
+            format!("BoilR is not allowed to change {location:?}"),
+        );
+    }
+    if is_lock_file_held(&location.join("LOCK")) {
+        return DiagnosticCheck::message(
+            CheckStatus::Warning,
+            description,
+            "Steam is using it, collections are written when Steam is closed",
+        );
+    }
+    DiagnosticCheck::path(description, location, true)
+}
+
+/// Asks the system if another program holds the lock of a level database, without taking the lock.
+/// Steam locks the LOCK file with fcntl, those locks are separate from flock locks on Linux.
+#[cfg(target_family = "unix")]
+fn is_lock_file_held(lock_file: &Path) -> bool {
+    use std::os::fd::AsRawFd;
+    let Ok(file) = std::fs::File::open(lock_file) else {
+        return false;
+    };
+    // SAFETY: flock is a plain C struct, all zeroes is a valid value for it
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = libc::F_WRLCK as libc::c_short;
+    lock.l_whence = libc::SEEK_SET as libc::c_short;
+    // SAFETY: the file descriptor is open for as long as the file lives, and lock is a valid flock
+    let result = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) };
+    result == 0 && lock.l_type != libc::F_UNLCK as libc::c_short
+}
+
+/// Windows has no way to ask for a lock without taking it, so the lock is taken and given back right away
+#[cfg(target_os = "windows")]
+fn is_lock_file_held(lock_file: &Path) -> bool {
+    match std::fs::File::open(lock_file) {
+        Ok(file) => matches!(file.try_lock(), Err(std::fs::TryLockError::WouldBlock)),
+        Err(err) => err.kind() != std::io::ErrorKind::NotFound,
+    }
+}
+
+/// Steam holds the lock of the collections database while it runs, and for a little while after its window closes
+pub fn is_collections_db_locked(installation: &SteamInstallation) -> bool {
+    is_lock_file_held(&installation.level_db_folder().join("LOCK"))
+}
+
+fn open_db(installation: &SteamInstallation) -> eyre::Result<DB> {
     use eyre::eyre;
-    let location = get_level_db_location().ok_or(eyre!("Collections db not found"))?;
+    let location = installation.level_db_folder();
+    if !location.exists() {
+        return Err(eyre!("Collections db not found at {location:?}"));
+    }
     let options = Options::default();
     let open_res = DB::open(location, options);
     open_res.map_err(|e|{
@@ -279,45 +526,6 @@ fn get_namespaces(db: &mut DB, key_bytes: &[u8]) -> Option<Vec<(i32, String)>> {
     }
 }
 
-#[cfg(target_family = "unix")]
-fn get_level_db_location() -> Option<PathBuf> {
-    match std::env::var("HOME") {
-        Ok(home) => {
-            let path = Path::new(&home)
-                .join(".steam")
-                .join("steam")
-                .join("config")
-                .join("htmlcache")
-                .join("Local Storage")
-                .join("leveldb");
-            if path.exists() {
-                return Some(path);
-            }
-            None
-        }
-        Err(_e) => None,
-    }
-}
-
-#[cfg(target_os = "windows")]
-fn get_level_db_location() -> Option<PathBuf> {
-    match std::env::var("LOCALAPPDATA") {
-        Ok(localdata) => {
-            let path = Path::new(&localdata)
-                .join("Steam")
-                .join("htmlcache")
-                .join("Local Storage")
-                .join("leveldb");
-            if path.exists() {
-                Some(path)
-            } else {
-                None
-            }
-        }
-        Err(_e) => None,
-    }
-}
-
 fn serialize_collection_value<S: AsRef<str>>(name: S, game_ids: &[usize]) -> String {
     let value = ValueCollection::new(name, game_ids);
     serde_json::to_string(&value).unwrap_or_default()
@@ -351,34 +559,30 @@ fn get_steam_user_prefix<S: AsRef<str>>(steamid: S) -> String {
     keyprefix
 }
 
+const USER_COLLECTIONS_KEY: &str = "user-collections";
+
 pub fn parse_vdf_collection<S: AsRef<str>>(input: S) -> Option<HashMap<String, VdfCollection>> {
     let input = input.as_ref();
     serde_json::from_str(input).ok()
 }
 
+/// The collections that are stored as json in the user-collections value of localconfig.vdf
+fn read_vdf_collections<S: AsRef<str>>(localconfig: S) -> Option<HashMap<String, VdfCollection>> {
+    let vdf = parse_vdf(localconfig).ok()?;
+    vdf.find(USER_COLLECTIONS_KEY)
+        .and_then(|entry| entry.as_str())
+        .and_then(parse_vdf_collection)
+}
+
+/// Replaces the user-collections value in the content of localconfig.vdf
 pub fn write_vdf_collection_to_string<S: AsRef<str>>(
     input: S,
     vdf: &HashMap<String, VdfCollection>,
 ) -> Option<String> {
-    let input = input.as_ref();
-    if let Ok(str) = serde_json::to_string(vdf) {
-        let encoded_json = format!("\"{}\"", str.replace('\\', "\\\""));
-        let key = "\t\"user-collections\"\t\t";
-        if let Some(start_index) = input.find_substring(key) {
-            let start_index_plus_key = start_index + key.len();
-            if let Some(line_index) = input.get(start_index_plus_key..).and_then(|i| i.find('\n')) {
-                let end_index_in_full = line_index + start_index_plus_key;
-                if let (Some(before), Some(after)) = (
-                    input.get(..start_index_plus_key),
-                    input.get(end_index_in_full..),
-                ) {
-                    let result = format!("{before}{encoded_json}{after}");
-                    return Some(result);
-                }
-            }
-        }
-    }
-    None
+    let json = serde_json::to_string(vdf).ok()?;
+    let mut localconfig = parse_vdf(input).ok()?;
+    let entry = localconfig.find_mut(USER_COLLECTIONS_KEY)?;
+    entry.set_str(json).then(|| localconfig.to_string())
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -420,4 +624,132 @@ mod tests {
         let collection = parse_steam_collections(input).unwrap();
         assert_eq!(28, collection.len())
     }
+
+    #[test]
+    fn lists_the_collections_of_a_category() {
+        let input = include_str!("../testdata/leveldb/testcollections.json");
+        let category = parse_steam_collections(input).unwrap();
+        let collections = collection_infos(&category);
+        assert_eq!(11, collections.len());
+        let favorites = collections.iter().find(|c| c.id == "favorite").unwrap();
+        assert_eq!("Favorites", favorites.name);
+        assert_eq!(12, favorites.game_ids.len());
+        assert!(favorites.is_built_in());
+        let installed = collections.iter().find(|c| c.name == "Installed").unwrap();
+        assert!(installed.is_dynamic);
+    }
+
+    #[test]
+    fn can_rename_and_delete_collections() {
+        let input = include_str!("../testdata/leveldb/testcollections.json");
+        let mut category = parse_steam_collections(input).unwrap();
+        let rename = CollectionChange::Update {
+            name: "Itch.io".to_string(),
+            game_ids: vec![42],
+        };
+        assert!(change_category(&mut category, "uc-VLUd605CHPd+", &rename));
+        let itch = collection_infos(&category)
+            .into_iter()
+            .find(|c| c.id == "uc-VLUd605CHPd+")
+            .unwrap();
+        assert_eq!("Itch.io", itch.name);
+        assert_eq!(vec![42], itch.game_ids);
+
+        assert!(change_category(
+            &mut category,
+            "uc-VLUd605CHPd+",
+            &CollectionChange::Delete
+        ));
+        assert_eq!(28, category.len());
+        let (_key, deleted) = category
+            .iter()
+            .find(|(key, _)| key == "user-collections.uc-VLUd605CHPd+")
+            .unwrap();
+        assert!(matches!(deleted, SteamCollection::Deleted(d) if d.is_deleted));
+        assert!(!collection_infos(&category)
+            .iter()
+            .any(|c| c.id == "uc-VLUd605CHPd+"));
+        assert!(!change_category(
+            &mut category,
+            "uc-VLUd605CHPd+",
+            &CollectionChange::Delete
+        ));
+    }
+
+    #[test]
+    fn boilr_collections_are_written_once_and_keep_their_steam_names() {
+        let input = include_str!("../testdata/leveldb/testcollections.json");
+        let mut category = parse_steam_collections(input).unwrap();
+        let collection = |name: &str, game_ids: Vec<usize>| Collection {
+            name: name.to_string(),
+            game_ids,
+        };
+        let lutris_id = name_to_key("Lutris");
+        let gog_id = name_to_key("GOG");
+
+        merge_boilr_collections(
+            &mut category,
+            &[collection("Lutris", vec![1]), collection("GOG", vec![2])],
+        );
+        let rename = CollectionChange::Update {
+            name: "My games".to_string(),
+            game_ids: vec![1],
+        };
+        assert!(change_category(&mut category, &lutris_id, &rename));
+        assert!(change_category(
+            &mut category,
+            &gog_id,
+            &CollectionChange::Delete
+        ));
+        merge_boilr_collections(
+            &mut category,
+            &[collection("Lutris", vec![3]), collection("GOG", vec![4])],
+        );
+
+        let keys: HashSet<&String> = category.iter().map(|(key, _)| key).collect();
+        assert_eq!(30, category.len());
+        assert_eq!(category.len(), keys.len());
+        let collections = collection_infos(&category);
+        let lutris = collections.iter().find(|c| c.id == lutris_id).unwrap();
+        assert_eq!("My games", lutris.name);
+        assert_eq!(vec![3], lutris.game_ids);
+        let gog = collections.iter().find(|c| c.id == gog_id).unwrap();
+        assert_eq!("GOG", gog.name);
+        assert_eq!(vec![4], gog.game_ids);
+
+        merge_boilr_collections(&mut category, &[collection("GOG", vec![4])]);
+        assert_eq!(29, category.len());
+    }
+
+    #[test]
+    fn games_taken_out_of_a_collection_are_marked_as_removed() {
+        let value = serialize_collection_value("Itch", &[1, 2, 3]);
+        let updated = update_collection_value(&value, "Itch", &[1, 4]).unwrap();
+        let updated: serde_json::Value = serde_json::from_str(&updated).unwrap();
+        assert_eq!(Some(&serde_json::json!([1, 4])), updated.get("added"));
+        assert_eq!(Some(&serde_json::json!([2, 3])), updated.get("removed"));
+    }
+
+    #[test]
+    fn can_write_vdf_collections() {
+        let json: serde_json::Value =
+            serde_json::from_str(include_str!("../testdata/vdf/user-collections.json")).unwrap();
+        let escaped = json.to_string().replace('"', "\\\"");
+        let localconfig = format!(
+            "\"UserLocalConfigStore\"\n{{\n\t\"WebStorage\"\n\t{{\n\t\t\"user-collections\"\t\t\"{escaped}\"\n\t}}\n\t\"friends\"\n\t{{\n\t}}\n}}\n"
+        );
+        let mut collections = read_vdf_collections(&localconfig).unwrap();
+        assert_eq!(4, collections.len());
+
+        collections.remove("from-tag-Heroic");
+        let written = write_vdf_collection_to_string(&localconfig, &collections).unwrap();
+        let read_back = read_vdf_collections(&written).unwrap();
+        assert_eq!(3, read_back.len());
+        assert_eq!(
+            vec![3601140154],
+            read_back.get("from-tag-Itch").unwrap().added
+        );
+        assert!(written.starts_with("\"UserLocalConfigStore\"\n{\n\t\"WebStorage\"\n\t{\n\t\t\"user-collections\"\t\t\"{\\\""));
+        assert!(written.ends_with("\"\n\t}\n\t\"friends\"\n\t{\n\t}\n}\n"));
+    }
 }
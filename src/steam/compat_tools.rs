@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::{parse_vdf, SteamInstallation, SteamSettings};
+
+/// The tool Steam uses for games that need Proton, when neither the game nor its platform has one chosen
+pub const DEFAULT_COMPAT_TOOL: &str = "proton_experimental";
+
+/// A Proton version or another compatibility tool that Steam can run games with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatTool {
+    /// The name Steam uses in CompatToolMapping, fx "proton_8" or "GE-Proton9-5"
+    pub name: String,
+    pub display_name: String,
+}
+
+/// The compat tool chosen for a game, the choice for the game wins over the choice for its platform.
+/// None if neither has a choice, then the game gets the default tool if Steam has no tool for it yet.
+pub fn choose_compat_tool<'a>(
+    settings: &'a SteamSettings,
+    game_tools: &'a HashMap<u32, String>,
+    platform_code_name: &str,
+    app_id: u32,
+) -> Option<&'a str> {
+    game_tools
+        .get(&app_id)
+        .or_else(|| settings.platform_compat_tools.get(platform_code_name))
+        .map(String::as_str)
+        .filter(|tool| !tool.trim().is_empty())
+}
+
+/// The compat tools installed for the Steam installations, both the ones Valve ships and custom ones like GE-Proton
+pub fn get_compat_tools(installations: &[SteamInstallation]) -> Vec<CompatTool> {
+    let mut tool_folders: Vec<PathBuf> = installations
+        .iter()
+        .map(SteamInstallation::compatibility_tools_folder)
+        .collect();
+    tool_folders.extend([
+        PathBuf::from("/usr/share/steam/compatibilitytools.d"),
+        PathBuf::from("/usr/local/share/steam/compatibilitytools.d"),
+    ]);
+    let common_folders: Vec<PathBuf> = installations
+        .iter()
+        .flat_map(SteamInstallation::library_folders)
+        .map(|steamapps| steamapps.join("common"))
+        .collect();
+    find_compat_tools(&tool_folders, &common_folders)
+}
+
+fn find_compat_tools(tool_folders: &[PathBuf], common_folders: &[PathBuf]) -> Vec<CompatTool> {
+    let mut tools = vec![CompatTool {
+        name: DEFAULT_COMPAT_TOOL.to_string(),
+        display_name: "Proton - Experimental".to_string(),
+    }];
+    let custom_tools = sub_folders(tool_folders).filter_map(|folder| {
+        let content = std::fs::read_to_string(folder.join("compatibilitytool.vdf")).ok()?;
+        Some(parse_compatibility_tool_vdf(content))
+    });
+    for tool in custom_tools.flatten() {
+        add_tool(&mut tools, tool);
+    }
+    let valve_tools = sub_folders(common_folders)
+        .filter(|folder| folder.join("proton").exists())
+        .filter_map(|folder| {
+            let display_name = folder.file_name()?.to_str()?.to_string();
+            let name = valve_proton_name(&display_name)?;
+            Some(CompatTool { name, display_name })
+        });
+    for tool in valve_tools {
+        add_tool(&mut tools, tool);
+    }
+    tools.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    tools
+}
+
+fn add_tool(tools: &mut Vec<CompatTool>, tool: CompatTool) {
+    if !tools.iter().any(|t| t.name == tool.name) {
+        tools.push(tool);
+    }
+}
+
+fn sub_folders(folders: &[PathBuf]) -> impl Iterator<Item = PathBuf> + '_ {
+    folders
+        .iter()
+        .filter_map(|folder| folder.read_dir().ok())
+        .flat_map(|entries| entries.flatten())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+}
+
+/// The tools a custom tool like GE-Proton declares in its compatibilitytool.vdf
+fn parse_compatibility_tool_vdf<S: AsRef<str>>(content: S) -> Vec<CompatTool> {
+    let Ok(vdf) = parse_vdf(content) else {
+        return vec![];
+    };
+    let Some(compat_tools) = vdf.path(&["compatibilitytools", "compat_tools"]) else {
+        return vec![];
+    };
+    compat_tools
+        .entries()
+        .filter_map(|entry| {
+            let display_name = entry
+                .as_section()?
+                .get_str("display_name")
+                .unwrap_or(entry.key());
+            Some(CompatTool {
+                name: entry.key().to_string(),
+                display_name: display_name.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The names Steam uses for the Proton versions Valve ships, fx "Proton 6.3" is "proton_63"
+fn valve_proton_name(folder_name: &str) -> Option<String> {
+    let version = folder_name.strip_prefix("Proton")?.trim();
+    let version = version.trim_end_matches("(Beta)").trim();
+    let version = version.trim_start_matches('-').trim();
+    if version.eq_ignore_ascii_case("experimental") || version.eq_ignore_ascii_case("hotfix") {
+        return Some(format!("proton_{}", version.to_lowercase()));
+    }
+    //The runtimes for anti cheat are also in the common folder, but they are not tools
+    let (major, minor) = version.split_once('.')?;
+    if major.is_empty()
+        || !major
+            .chars()
+            .chain(minor.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    if minor.trim_start_matches('0').is_empty() {
+        Some(format!("proton_{major}"))
+    } else {
+        Some(format!("proton_{major}{minor}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn valve_folders_are_named_like_steam_does() {
+        let name = |folder: &str| valve_proton_name(folder);
+        assert_eq!(
+            Some("proton_experimental"),
+            name("Proton - Experimental").as_deref()
+        );
+        assert_eq!(Some("proton_hotfix"), name("Proton Hotfix").as_deref());
+        assert_eq!(Some("proton_9"), name("Proton 9.0 (Beta)").as_deref());
+        assert_eq!(Some("proton_8"), name("Proton 8.0").as_deref());
+        assert_eq!(Some("proton_63"), name("Proton 6.3").as_deref());
+        assert_eq!(Some("proton_513"), name("Proton 5.13").as_deref());
+        assert_eq!(None, name("Proton EasyAntiCheat Runtime"));
+        assert_eq!(None, name("SteamLinuxRuntime_sniper"));
+    }
+
+    #[test]
+    fn finds_custom_and_valve_tools() {
+        let folder =
+            std::env::temp_dir().join(format!("boilr-compat-tools-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        let ge_proton = folder.join("compatibilitytools.d").join("GE-Proton9-5");
+        std::fs::create_dir_all(&ge_proton).unwrap();
+        std::fs::write(
+            ge_proton.join("compatibilitytool.vdf"),
+            include_str!("../testdata/vdf/compatibilitytool.vdf"),
+        )
+        .unwrap();
+        let common = folder.join("steamapps").join("common");
+        for tool in ["Proton 8.0", "Proton BattlEye Runtime"] {
+            std::fs::create_dir_all(common.join(tool)).unwrap();
+            std::fs::write(common.join(tool).join("proton"), "#!/usr/bin/env python3").unwrap();
+        }
+
+        let tools = find_compat_tools(&[folder.join("compatibilitytools.d")], &[common]);
+        let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            vec!["GE-Proton9-5", "proton_experimental", "proton_8"],
+            names
+        );
+        assert_eq!(
+            Some("Proton 8.0"),
+            tools.last().map(|t| t.display_name.as_str())
+        );
+    }
+
+    #[test]
+    fn game_choice_wins_over_platform_choice() {
+        let mut settings = SteamSettings::default();
+        settings
+            .platform_compat_tools
+            .insert("gog".to_string(), "proton_8".to_string());
+        let games = HashMap::from([(42, "GE-Proton9-5".to_string())]);
+        assert_eq!(
+            Some("GE-Proton9-5"),
+            choose_compat_tool(&settings, &games, "gog", 42)
+        );
+        assert_eq!(
+            Some("proton_8"),
+            choose_compat_tool(&settings, &games, "gog", 43)
+        );
+        assert_eq!(None, choose_compat_tool(&settings, &games, "itch", 43));
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{parse_vdf, SteamInstallation, SteamSettings};
+
+/// The Steam Deck controller, Steam keeps a mapping file for each type of controller
+const CONTROLLER_TYPE: &str = "controller_neptune";
+/// The start of the file names of the custom layouts BoilR copies to Steam
+const CUSTOM_PREFIX: &str = "boilr_";
+
+/// A Steam Input layout for the shortcuts of BoilR
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControllerTemplate {
+    Gamepad,
+    GamepadWithMouse,
+    GamepadFps,
+    WebBrowser,
+    /// The path of a layout the user exported from Steam
+    Custom(String),
+}
+
+impl ControllerTemplate {
+    /// The templates that come with Steam
+    pub const BUILT_IN: [ControllerTemplate; 4] = [
+        ControllerTemplate::Gamepad,
+        ControllerTemplate::GamepadWithMouse,
+        ControllerTemplate::GamepadFps,
+        ControllerTemplate::WebBrowser,
+    ];
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            ControllerTemplate::Gamepad => "Gamepad",
+            ControllerTemplate::GamepadWithMouse => "Gamepad with mouse trackpad",
+            ControllerTemplate::GamepadFps => "Gamepad with gyro (FPS)",
+            ControllerTemplate::WebBrowser => "Mouse and keyboard",
+            ControllerTemplate::Custom(_) => "Custom layout",
+        }
+    }
+
+    /// The name of the template file in Steam's templates folder.
+    /// Custom layouts get a hash of their path, so layouts with the same file name do not overwrite each other.
+    pub fn file_name(&self) -> String {
+        match self {
+            ControllerTemplate::Gamepad => format!("{CONTROLLER_TYPE}_gamepad_joystick.vdf"),
+            ControllerTemplate::GamepadWithMouse => format!("{CONTROLLER_TYPE}_gamepad+mouse.vdf"),
+            ControllerTemplate::GamepadFps => format!("{CONTROLLER_TYPE}_gamepad_fps.vdf"),
+            ControllerTemplate::WebBrowser => format!("{CONTROLLER_TYPE}_webbrowser.vdf"),
+            ControllerTemplate::Custom(path) => {
+                let name = Path::new(path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                format!("{CUSTOM_PREFIX}{:016x}_{name}", path_hash(path))
+            }
+        }
+    }
+}
+
+/// A hash that is the same across runs, so a layout keeps its file name
+fn path_hash(path: &str) -> u64 {
+    //FNV-1a
+    path.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// If BoilR could have set the template, templates chosen in Steam from elsewhere are left alone
+fn is_boilr_template(file_name: &str) -> bool {
+    file_name.starts_with(CUSTOM_PREFIX)
+        || ControllerTemplate::BUILT_IN
+            .iter()
+            .any(|template| template.file_name() == file_name)
+}
+
+/// The template for a game, the template chosen for the game wins over the one chosen for its platform
+pub fn choose_controller_template<'a>(
+    settings: &'a SteamSettings,
+    game_templates: &'a HashMap<u32, ControllerTemplate>,
+    platform_code_name: &str,
+    app_id: u32,
+) -> Option<&'a ControllerTemplate> {
+    game_templates.get(&app_id).or_else(|| {
+        settings
+            .platform_controller_templates
+            .get(platform_code_name)
+    })
+}
+
+/// Sets the controller templates of BoilR's shortcuts for a user, the games are pairs of a shortcut name and a template.
+/// Shortcuts without a template lose the one BoilR set before. Custom layouts are copied to Steam's templates folder first.
+pub fn write_controller_templates(
+    installation: &SteamInstallation,
+    user_id: &str,
+    games: &[(String, Option<ControllerTemplate>)],
+) -> eyre::Result<()> {
+    let templates_folder = installation.controller_templates_folder();
+    let mut ready = vec![];
+    for (name, template) in games {
+        if let Some(custom @ ControllerTemplate::Custom(path)) = template {
+            let target = templates_folder.join(custom.file_name());
+            let copied = std::fs::create_dir_all(&templates_folder)
+                .and_then(|_| std::fs::copy(path, &target));
+            if let Err(err) = copied {
+                eprintln!("Could not copy the controller layout {path} for {name}: {err}");
+                continue;
+            }
+        }
+        //Steam finds the layouts of shortcuts by their name in lowercase
+        ready.push((
+            name.to_lowercase(),
+            template.as_ref().map(ControllerTemplate::file_name),
+        ));
+    }
+    let path = installation.controller_configset_path(user_id, CONTROLLER_TYPE);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            if ready.iter().all(|(_, file_name)| file_name.is_none()) {
+                return Ok(());
+            }
+            "\"controller_config\"\n{\n}\n".to_string()
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let new_content = set_controller_templates(&content, &ready)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, new_content)?;
+    Ok(())
+}
+
+fn set_controller_templates(
+    content: &str,
+    games: &[(String, Option<String>)],
+) -> eyre::Result<String> {
+    let mut vdf = parse_vdf(content)?;
+    let controller_config = vdf.insert_section("controller_config")?;
+    for (name, file_name) in games {
+        //Only the template is changed, the rest of what Steam wrote for the game is kept
+        match file_name {
+            Some(file_name) => controller_config
+                .insert_section(name)?
+                .set_str("template", file_name),
+            None => {
+                if let Some(game) = controller_config.get_section_mut(name) {
+                    if game.get_str("template").is_some_and(is_boilr_template) {
+                        game.remove("template");
+                    }
+                }
+            }
+        }
+    }
+    Ok(vdf.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    #[test]
+    fn game_template_wins_over_platform_template() {
+        let mut settings = SteamSettings::default();
+        settings
+            .platform_controller_templates
+            .insert("epic".to_string(), ControllerTemplate::GamepadWithMouse);
+        let games = HashMap::from([(
+            42,
+            ControllerTemplate::Custom("/home/deck/layouts/doom.vdf".to_string()),
+        )]);
+        let template = choose_controller_template(&settings, &games, "epic", 42).unwrap();
+        let file_name = template.file_name();
+        assert!(file_name.starts_with("boilr_") && file_name.ends_with("_doom.vdf"));
+        let other_doom = ControllerTemplate::Custom("/home/deck/old/doom.vdf".to_string());
+        assert_ne!(file_name, other_doom.file_name());
+        assert_eq!(file_name, template.file_name());
+        assert_eq!(
+            Some(&ControllerTemplate::GamepadWithMouse),
+            choose_controller_template(&settings, &games, "epic", 43)
+        );
+        assert_eq!(
+            None,
+            choose_controller_template(&settings, &games, "gog", 43)
+        );
+    }
+
+    #[test]
+    fn templates_are_set_and_the_rest_is_kept() {
+        let content = include_str!("../testdata/vdf/configset_controller_neptune.vdf");
+        let games = vec![
+            (
+                "stardew valley".to_string(),
+                Some(ControllerTemplate::WebBrowser.file_name()),
+            ),
+            (
+                "quake".to_string(),
+                Some(ControllerTemplate::GamepadFps.file_name()),
+            ),
+            ("doom".to_string(), None),
+            ("hollow knight".to_string(), None),
+        ];
+        let new_content = set_controller_templates(content, &games).unwrap();
+        let vdf = parse_vdf(&new_content).unwrap();
+        let config = vdf.get_section("controller_config").unwrap();
+        let template_of = |name: &str| config.get_section(name).unwrap().get_str("template");
+
+        let stardew = config.get_section("stardew valley").unwrap();
+        assert_eq!(Some("413150"), stardew.get_str("srcid"));
+        assert_eq!(
+            Some("controller_neptune_webbrowser.vdf"),
+            template_of("stardew valley")
+        );
+        assert_eq!(
+            Some("controller_neptune_gamepad_fps.vdf"),
+            template_of("quake")
+        );
+        //The template BoilR set is taken away, the one chosen in Steam is kept
+        assert_eq!(None, template_of("doom"));
+        assert_eq!(Some("my_own_layout.vdf"), template_of("hollow knight"));
+        assert_eq!(
+            Some("2808143916"),
+            config.get_section("1091500").unwrap().get_str("workshop")
+        );
+        assert_eq!(content, set_controller_templates(content, &[]).unwrap());
+    }
+}
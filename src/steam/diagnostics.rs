@@ -0,0 +1,50 @@
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+
+use super::{diagnose_collections_db, sync_targets, SteamInstallation, SteamSettings};
+
+/// Checks each Steam installation a sync writes to
+pub fn diagnose_steam(settings: &SteamSettings) -> Vec<DiagnosticCheck> {
+    match sync_targets(settings) {
+        Ok(installations) => installations
+            .iter()
+            .flat_map(|installation| diagnose_installation(settings, installation))
+            .collect(),
+        Err(err) => vec![DiagnosticCheck::message(
+            CheckStatus::Error,
+            "Steam folder",
+            format!("Could not find the Steam folder: {err}"),
+        )],
+    }
+}
+
+fn diagnose_installation(
+    settings: &SteamSettings,
+    installation: &SteamInstallation,
+) -> Vec<DiagnosticCheck> {
+    let mut checks = vec![
+        DiagnosticCheck::path("Steam folder", installation.root(), true),
+        DiagnosticCheck::path("Steam user data", installation.userdata_folder(), true),
+    ];
+    if let Ok(users) = installation.users() {
+        let check = if users.is_empty() {
+            DiagnosticCheck::message(
+                CheckStatus::Error,
+                "Steam users",
+                "No users found, log in to Steam once",
+            )
+        } else {
+            let ids: Vec<&str> = users.iter().map(|user| user.user_id.as_str()).collect();
+            DiagnosticCheck::message(CheckStatus::Ok, "Steam users", ids.join(", "))
+        };
+        checks.push(check);
+    }
+    let mut collections_check = diagnose_collections_db(installation);
+    if !settings.create_collections && collections_check.status == CheckStatus::Error {
+        //Only a problem if BoilR has to write collections
+        collections_check.status = CheckStatus::Info;
+    }
+    checks.push(collections_check);
+    #[cfg(target_family = "unix")]
+    checks.extend(super::diagnose_config_vdf(installation));
+    checks
+}
@@ -0,0 +1,557 @@
+use std::path::{Path, PathBuf};
+
+use super::{load_user_profiles, parse_vdf, SteamSettings, SteamUsersInfo};
+use crate::platforms::HostCommand;
+
+/// How BoilR found a Steam installation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SteamInstallationKind {
+    /// The location from the settings
+    Configured,
+    Native,
+    Flatpak,
+}
+
+/// A Steam installation, the Steam files BoilR reads and writes are all found from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SteamInstallation {
+    root: PathBuf,
+    kind: SteamInstallationKind,
+}
+
+#[cfg(target_family = "unix")]
+const FLATPAK_ID: &str = "com.valvesoftware.Steam";
+
+impl SteamInstallation {
+    pub fn new<P: Into<PathBuf>>(root: P, kind: SteamInstallationKind) -> Self {
+        Self {
+            root: root.into(),
+            kind,
+        }
+    }
+
+    /// The installation from the Steam location in the settings, or the first one found if no location is set
+    pub fn resolve(settings: &SteamSettings) -> eyre::Result<Self> {
+        configured_installation(settings)
+            .or_else(|| detect_steam_installations().into_iter().next())
+            .ok_or_else(|| {
+                eyre::format_err!(
+                    "Could not find Steam, looked in: {:?}. Please specify its location in the settings",
+                    default_locations()
+                        .iter()
+                        .map(|(root, _)| root)
+                        .collect::<Vec<_>>()
+                )
+            })
+    }
+
+    /// The Steam folder, the one with userdata, steamapps and config in it
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// If this is the flatpak of Steam, also when its location is set in the settings
+    #[cfg(target_family = "unix")]
+    pub fn is_flatpak(&self) -> bool {
+        self.kind == SteamInstallationKind::Flatpak
+            || self.root.to_string_lossy().contains(FLATPAK_ID)
+    }
+
+    /// The Steam folder as it is written in the settings
+    pub fn location(&self) -> String {
+        self.root.to_string_lossy().to_string()
+    }
+
+    /// A name for the installation that can be used as a folder name, fx for its backups
+    pub fn id(&self) -> String {
+        let id: String = self
+            .location()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        id.trim_matches('_').to_string()
+    }
+
+    /// A short description for the settings, fx "Flatpak: ~/.var/app/com.valvesoftware.Steam/data/Steam"
+    pub fn display_name(&self) -> String {
+        let kind = match self.kind {
+            SteamInstallationKind::Configured => "Steam location",
+            SteamInstallationKind::Native => "Steam",
+            SteamInstallationKind::Flatpak => "Flatpak",
+        };
+        format!("{kind}: {}", self.location())
+    }
+
+    pub fn userdata_folder(&self) -> PathBuf {
+        self.root.join("userdata")
+    }
+
+    pub fn user_config_folder(&self, user_id: &str) -> PathBuf {
+        self.userdata_folder().join(user_id).join("config")
+    }
+
+    /// The settings of a user, this is where Steam keeps the user-collections
+    pub fn localconfig_path(&self, user_id: &str) -> PathBuf {
+        self.user_config_folder(user_id).join("localconfig.vdf")
+    }
+
+    /// The settings of the installation, this is where Steam keeps the CompatToolMapping
+    pub fn config_vdf_path(&self) -> PathBuf {
+        self.root.join("config").join("config.vdf")
+    }
+
+    /// The controller layouts Steam can pick from
+    pub fn controller_templates_folder(&self) -> PathBuf {
+        self.root.join("controller_base").join("templates")
+    }
+
+    /// The controller layouts a user chose for each game, Steam keeps a file for each type of controller
+    pub fn controller_configset_path(&self, user_id: &str, controller_type: &str) -> PathBuf {
+        self.root
+            .join("steamapps")
+            .join("common")
+            .join("Steam Controller Configs")
+            .join(user_id)
+            .join("config")
+            .join(format!("configset_{controller_type}.vdf"))
+    }
+
+    pub fn compatibility_tools_folder(&self) -> PathBuf {
+        self.root.join("compatibilitytools.d")
+    }
+
+    /// The database that Steam keeps the collections in
+    #[cfg(target_family = "unix")]
+    pub fn level_db_folder(&self) -> PathBuf {
+        self.root
+            .join("config")
+            .join("htmlcache")
+            .join("Local Storage")
+            .join("leveldb")
+    }
+
+    /// The database that Steam keeps the collections in, on Windows it is not in the Steam folder
+    #[cfg(target_os = "windows")]
+    pub fn level_db_folder(&self) -> PathBuf {
+        let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
+        Path::new(&local_app_data)
+            .join("Steam")
+            .join("htmlcache")
+            .join("Local Storage")
+            .join("leveldb")
+    }
+
+    /// The steamapps folder of each library, the first is the one in the Steam folder
+    pub fn library_folders(&self) -> Vec<PathBuf> {
+        let steamapps = self.root.join("steamapps");
+        let libraries = std::fs::read_to_string(steamapps.join("libraryfolders.vdf"))
+            .map(parse_library_folders)
+            .unwrap_or_default();
+        let mut result = vec![steamapps];
+        for library in libraries {
+            let library = library.join("steamapps");
+            if !result.iter().any(|known| same_folder(known, &library)) {
+                result.push(library);
+            }
+        }
+        result
+    }
+
+    /// The folders with the wine prefixes of the games Steam runs with Proton
+    pub fn compatdata_folders(&self) -> Vec<PathBuf> {
+        self.library_folders()
+            .iter()
+            .map(|library| library.join("compatdata"))
+            .filter(|compatdata| compatdata.exists())
+            .collect()
+    }
+
+    /// The users that have logged in to this installation
+    pub fn users(&self) -> eyre::Result<Vec<SteamUsersInfo>> {
+        if !self.root.exists() {
+            return Err(eyre::format_err!(
+                "Steam folder not found at: {:?}",
+                self.root
+            ));
+        }
+        let user_data_path = self.userdata_folder();
+        if !user_data_path.exists() {
+            return Err(eyre::format_err!(
+                "Steam user data folder not found at: {:?}",
+                user_data_path
+            ));
+        }
+        let user_folders = std::fs::read_dir(&user_data_path)?;
+        let mut users: Vec<SteamUsersInfo> = user_folders
+            .flatten()
+            .filter(|folder| folder.metadata().is_ok_and(|meta| meta.is_dir()))
+            .map(|folder| folder.file_name().to_string_lossy().to_string())
+            .filter(|user_id| user_id != "0")
+            .map(|user_id| {
+                let shortcuts_path = self.user_config_folder(&user_id).join("shortcuts.vdf");
+                SteamUsersInfo {
+                    steam_user_data_folder: self
+                        .userdata_folder()
+                        .join(&user_id)
+                        .to_string_lossy()
+                        .to_string(),
+                    shortcut_path: shortcuts_path
+                        .exists()
+                        .then(|| shortcuts_path.to_string_lossy().to_string()),
+                    user_id,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        users.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        Ok(users)
+    }
+
+    /// The users with their names and avatars, only needed where the users are shown
+    pub fn users_with_profiles(&self) -> eyre::Result<Vec<SteamUsersInfo>> {
+        let mut users = self.users()?;
+        load_user_profiles(self, &mut users);
+        Ok(users)
+    }
+
+    /// The users a sync writes to, that is all users except the ones skipped in the settings
+    pub fn users_to_sync(&self, settings: &SteamSettings) -> eyre::Result<Vec<SteamUsersInfo>> {
+        let mut users = self.users()?;
+        if let Some(skipped) = settings.skipped_users.get(&self.location()) {
+            users.retain(|user| !skipped.contains(&user.user_id));
+        }
+        Ok(users)
+    }
+
+    /// The command that starts this installation of Steam
+    #[cfg(target_family = "unix")]
+    pub fn start_command(&self) -> HostCommand {
+        if self.is_flatpak() {
+            HostCommand::new("flatpak").args(["run", FLATPAK_ID])
+        } else {
+            HostCommand::new("steam")
+        }
+    }
+
+    /// The command that starts this installation of Steam
+    #[cfg(target_os = "windows")]
+    pub fn start_command(&self) -> HostCommand {
+        HostCommand::new(self.root.join("steam.exe").to_string_lossy())
+    }
+}
+
+fn configured_installation(settings: &SteamSettings) -> Option<SteamInstallation> {
+    let location = settings.location.as_deref().map(str::trim)?;
+    (!location.is_empty())
+        .then(|| SteamInstallation::new(location, SteamInstallationKind::Configured))
+}
+
+/// The Steam location from the settings and all the Steam installations that were found
+pub fn steam_installations(settings: &SteamSettings) -> Vec<SteamInstallation> {
+    let mut result: Vec<SteamInstallation> =
+        configured_installation(settings).into_iter().collect();
+    for installation in detect_steam_installations() {
+        if !result
+            .iter()
+            .any(|known| same_folder(&known.root, &installation.root))
+        {
+            result.push(installation);
+        }
+    }
+    result
+}
+
+/// The installations a sync writes to, the one from [`SteamInstallation::resolve`] if none are chosen in the settings
+pub fn sync_targets(settings: &SteamSettings) -> eyre::Result<Vec<SteamInstallation>> {
+    if settings.sync_locations.is_empty() {
+        return Ok(vec![SteamInstallation::resolve(settings)?]);
+    }
+    let known = steam_installations(settings);
+    let targets = settings
+        .sync_locations
+        .iter()
+        .map(|location| {
+            let location = Path::new(location);
+            known
+                .iter()
+                .find(|installation| same_folder(&installation.root, location))
+                .cloned()
+                .unwrap_or_else(|| {
+                    SteamInstallation::new(location, SteamInstallationKind::Configured)
+                })
+        })
+        .collect();
+    Ok(targets)
+}
+
+/// The users a sync writes to, in all the installations it writes to
+pub fn get_users_to_sync(settings: &SteamSettings) -> eyre::Result<Vec<SteamUsersInfo>> {
+    users_of_sync_targets(settings, |installation| {
+        installation.users_to_sync(settings)
+    })
+}
+
+/// The users of all the installations a sync writes to.
+/// An installation that can not be read (fx a removed SD card) is skipped, so the other installations still sync,
+/// it is only an error if no users were found at all.
+pub fn users_of_sync_targets(
+    settings: &SteamSettings,
+    users_of: impl Fn(&SteamInstallation) -> eyre::Result<Vec<SteamUsersInfo>>,
+) -> eyre::Result<Vec<SteamUsersInfo>> {
+    let mut users = vec![];
+    let mut last_error = None;
+    for installation in sync_targets(settings)? {
+        match users_of(&installation) {
+            Ok(installation_users) => users.extend(installation_users),
+            Err(err) => {
+                eprintln!(
+                    "Skipping the Steam installation at {}: {err}",
+                    installation.location()
+                );
+                last_error = Some(err);
+            }
+        }
+    }
+    match last_error {
+        Some(err) if users.is_empty() => Err(err),
+        _ => Ok(users),
+    }
+}
+
+/// The Steam installations in the places Steam is installed to by default
+pub fn detect_steam_installations() -> Vec<SteamInstallation> {
+    let mut result: Vec<SteamInstallation> = vec![];
+    for (root, kind) in default_locations() {
+        if !root.join("userdata").exists() && !root.join("steamapps").exists() {
+            continue;
+        }
+        //~/.steam/steam is a link to ~/.local/share/Steam, that is the same installation
+        if !result.iter().any(|known| same_folder(&known.root, &root)) {
+            result.push(SteamInstallation::new(root, kind));
+        }
+    }
+    result
+}
+
+#[cfg(target_os = "linux")]
+fn default_locations() -> Vec<(PathBuf, SteamInstallationKind)> {
+    let Ok(home) = std::env::var("HOME") else {
+        return vec![];
+    };
+    let home = Path::new(&home);
+    let flatpak = home.join(".var").join("app").join(FLATPAK_ID);
+    vec![
+        (
+            home.join(".steam").join("steam"),
+            SteamInstallationKind::Native,
+        ),
+        (
+            home.join(".local").join("share").join("Steam"),
+            SteamInstallationKind::Native,
+        ),
+        (
+            flatpak.join(".steam").join("steam"),
+            SteamInstallationKind::Flatpak,
+        ),
+        (
+            flatpak.join("data").join("Steam"),
+            SteamInstallationKind::Flatpak,
+        ),
+    ]
+}
+
+#[cfg(target_os = "macos")]
+fn default_locations() -> Vec<(PathBuf, SteamInstallationKind)> {
+    let Ok(home) = std::env::var("HOME") else {
+        return vec![];
+    };
+    vec![(
+        Path::new(&home)
+            .join("Library")
+            .join("Application Support")
+            .join("Steam"),
+        SteamInstallationKind::Native,
+    )]
+}
+
+#[cfg(target_os = "windows")]
+fn default_locations() -> Vec<(PathBuf, SteamInstallationKind)> {
+    let Ok(program_files) = std::env::var("PROGRAMFILES(X86)") else {
+        return vec![];
+    };
+    vec![(
+        Path::new(&program_files).join("Steam"),
+        SteamInstallationKind::Native,
+    )]
+}
+
+fn same_folder(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// The compatdata folders of all the Steam installations that were found, for platforms that look for wine prefixes
+pub fn detected_compatdata_folders() -> Vec<PathBuf> {
+    detect_steam_installations()
+        .iter()
+        .flat_map(SteamInstallation::compatdata_folders)
+        .collect()
+}
+
+/// The libraries in libraryfolders.vdf, older versions of Steam have the path as the value instead of a section
+fn parse_library_folders<S: AsRef<str>>(vdf_content: S) -> Vec<PathBuf> {
+    let Ok(vdf) = parse_vdf(vdf_content) else {
+        return vec![];
+    };
+    let Some(libraries) = vdf.get_section("libraryfolders") else {
+        return vec![];
+    };
+    libraries
+        .entries()
+        .filter(|library| library.key().parse::<u32>().is_ok())
+        .filter_map(|library| {
+            library
+                .as_section()
+                .and_then(|section| section.get_str("path"))
+                .or_else(|| library.as_str())
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn parse_library_folders_test() {
+        let new_format = include_str!("../testdata/vdf/libraryfolders.vdf");
+        assert_eq!(
+            vec![
+                PathBuf::from("/home/deck/.local/share/Steam"),
+                PathBuf::from("/run/media/mmcblk0p1")
+            ],
+            parse_library_folders(new_format)
+        );
+        let old_format = "\"LibraryFolders\"\n{\n\t\"TimeNextStatsReport\"\t\t\"1651783287\"\n\t\"ContentStatsID\"\t\t\"-4589\"\n\t\"1\"\t\t\"D:\\\\SteamLibrary\"\n}\n";
+        assert_eq!(
+            vec![PathBuf::from("D:\\SteamLibrary")],
+            parse_library_folders(old_format)
+        );
+    }
+
+    #[test]
+    fn paths_come_from_the_configured_location() {
+        let root = std::env::temp_dir().join(format!("boilr-steam-root-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("userdata").join("0")).unwrap();
+        std::fs::create_dir_all(root.join("userdata").join("42").join("config")).unwrap();
+        std::fs::write(
+            root.join("userdata/42/config/shortcuts.vdf"),
+            "\0shortcuts\0\x08\x08",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("userdata").join("7")).unwrap();
+
+        let settings = SteamSettings {
+            location: Some(root.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let installation = SteamInstallation::resolve(&settings).unwrap();
+        assert_eq!(SteamInstallationKind::Configured, installation.kind);
+        assert_eq!(
+            root.join("config/config.vdf"),
+            installation.config_vdf_path()
+        );
+        assert_eq!(
+            root.join("userdata/42/config/localconfig.vdf"),
+            installation.localconfig_path("42")
+        );
+        assert_eq!(
+            root.join("steamapps/common/Steam Controller Configs/42/config/configset_controller_neptune.vdf"),
+            installation.controller_configset_path("42", "controller_neptune")
+        );
+        assert_eq!(vec![root.join("steamapps")], installation.library_folders());
+
+        let users = installation.users().unwrap();
+        let ids: Vec<&str> = users.iter().map(|u| u.user_id.as_str()).collect();
+        assert_eq!(vec!["42", "7"], ids);
+        assert!(users.first().unwrap().shortcut_path.is_some());
+        assert!(users.last().unwrap().shortcut_path.is_none());
+    }
+
+    #[test]
+    fn syncs_to_the_chosen_installations_and_users() {
+        let folder = std::env::temp_dir().join(format!("boilr-steam-roots-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        let native = folder.join("native");
+        let flatpak = folder.join("flatpak");
+        for user in ["1", "2"] {
+            std::fs::create_dir_all(native.join("userdata").join(user)).unwrap();
+        }
+        std::fs::create_dir_all(flatpak.join("userdata").join("3")).unwrap();
+
+        let native_location = native.to_string_lossy().to_string();
+        let flatpak_location = flatpak.to_string_lossy().to_string();
+        let settings = SteamSettings {
+            location: Some(native_location.clone()),
+            sync_locations: vec![native_location.clone(), flatpak_location.clone()],
+            skipped_users: HashMap::from([(native_location, vec!["1".to_string()])]),
+            ..Default::default()
+        };
+        let targets = sync_targets(&settings).unwrap();
+        let locations: Vec<String> = targets.iter().map(SteamInstallation::location).collect();
+        assert_eq!(
+            vec![native.to_string_lossy(), flatpak.to_string_lossy()],
+            locations
+        );
+        let users = get_users_to_sync(&settings).unwrap();
+        let ids: Vec<&str> = users.iter().map(|u| u.user_id.as_str()).collect();
+        assert_eq!(vec!["2", "3"], ids);
+
+        let with_missing = SteamSettings {
+            sync_locations: vec![
+                folder.join("removed").to_string_lossy().to_string(),
+                flatpak_location.clone(),
+            ],
+            ..settings.clone()
+        };
+        let users = get_users_to_sync(&with_missing).unwrap();
+        let ids: Vec<&str> = users.iter().map(|u| u.user_id.as_str()).collect();
+        assert_eq!(vec!["3"], ids);
+
+        let only_flatpak = SteamSettings {
+            sync_locations: vec![flatpak_location],
+            ..settings
+        };
+        let targets = sync_targets(&only_flatpak).unwrap();
+        assert_eq!(1, targets.len());
+        assert!(targets
+            .iter()
+            .all(|target| target.id().ends_with("flatpak") && !target.id().starts_with('_')));
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn flatpak_is_found_from_the_path() {
+        let flatpak = SteamInstallation::new(
+            "/home/deck/.var/app/com.valvesoftware.Steam/data/Steam",
+            SteamInstallationKind::Configured,
+        );
+        assert!(flatpak.is_flatpak());
+        let native = SteamInstallation::new(
+            "/home/deck/.local/share/Steam",
+            SteamInstallationKind::Native,
+        );
+        assert!(!native.is_flatpak());
+        assert_eq!("steam", native.start_command().to_string());
+        assert_eq!(
+            "flatpak run com.valvesoftware.Steam",
+            flatpak.start_command().to_string()
+        );
+    }
+}
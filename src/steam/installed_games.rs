@@ -1,9 +1,6 @@
-use std::{
-    ffi::OsStr,
-    path::{Path, PathBuf},
-};
+use std::{ffi::OsStr, path::Path};
 
-use super::{get_steam_path, SteamSettings};
+use super::{parse_vdf, SteamInstallation};
 
 #[derive(Debug, Clone)]
 pub struct SteamGameInfo {
@@ -11,8 +8,8 @@ pub struct SteamGameInfo {
     pub name: String,
 }
 
-pub fn get_installed_games(settings: &SteamSettings) -> Vec<SteamGameInfo> {
-    let install_folders = get_install_folders(settings);
+pub fn get_installed_games(installation: &SteamInstallation) -> Vec<SteamGameInfo> {
+    let install_folders = installation.library_folders();
     let mut games = vec![];
     for apps_path in install_folders {
         if let Ok(files) = std::fs::read_dir(apps_path) {
@@ -27,30 +24,6 @@ pub fn get_installed_games(settings: &SteamSettings) -> Vec<SteamGameInfo> {
     games
 }
 
-fn get_install_folders(settings: &SteamSettings) -> Vec<PathBuf> {
-    let mut result = vec![];
-    if let Ok(path) = get_steam_path(settings) {
-        let path = Path::new(&path);
-
-        let vdf_path = path.join("steamapps").join("libraryfolders.vdf");
-        if !vdf_path.exists() {
-            result.push(path.join("steamapps"));
-            return result;
-        }
-        if let Ok(vdf_file) = std::fs::read_to_string(vdf_path) {
-            for line in vdf_file.lines() {
-                if line.contains("\"path\"") {
-                    if let Some(path_string) = line.get(11..line.len() - 1) {
-                        result.push(Path::new(&path_string).join("steamapps").to_path_buf());
-                    }
-                }
-            }
-        }
-    }
-
-    result
-}
-
 fn parse_manifest_file(path: &Path) -> Option<SteamGameInfo> {
     let extension = path.extension().and_then(OsStr::to_str);
     if let Some("acf") = extension {
@@ -63,21 +36,14 @@ fn parse_manifest_file(path: &Path) -> Option<SteamGameInfo> {
 }
 
 fn parse_manifest_string<S: AsRef<str>>(string: S) -> Option<SteamGameInfo> {
-    let mut lines = string.as_ref().lines();
-    let appid: Option<u32> = lines
-        .find(|l| l.contains("\"appid\""))
-        .and_then(|line| line.get(11..line.len() - 1))
-        .and_then(|app_id_str| app_id_str.parse().ok());
-    let name_line = lines
-        .find(|l| l.contains("\"name\""))
-        .and_then(|line| line.get(10..line.len() - 1));
-    match (appid, name_line) {
-        (Some(appid), Some(name)) => Some(SteamGameInfo {
-            name: name.to_string(),
-            appid,
-        }),
-        _ => None,
-    }
+    let vdf = parse_vdf(string).ok()?;
+    let app_state = vdf.get_section("AppState")?;
+    let appid = app_state.get_str("appid")?.parse().ok()?;
+    let name = app_state.get_str("name")?;
+    Some(SteamGameInfo {
+        name: name.to_string(),
+        appid,
+    })
 }
 
 #[cfg(test)]
@@ -1,15 +1,33 @@
 mod collections;
+mod controller_templates;
+#[cfg(target_family = "unix")]
+mod compat_tools;
+mod diagnostics;
+mod installation;
 mod installed_games;
+mod pending_collections;
 #[cfg(target_family = "unix")]
 mod proton_vdf_util;
 mod restarter;
 mod settings;
+mod shortcut_flags;
+mod user_profiles;
 mod utils;
+mod vdf;
 
 pub use collections::*;
+pub use controller_templates::*;
+#[cfg(target_family = "unix")]
+pub use compat_tools::*;
+pub use diagnostics::*;
+pub use installation::*;
 pub use installed_games::*;
+pub use pending_collections::*;
 #[cfg(target_family = "unix")]
 pub use proton_vdf_util::*;
 pub use restarter::*;
-pub use settings::SteamSettings;
+pub use settings::{CollectionRule, SteamSettings, PLATFORM_PLACEHOLDER};
+pub use shortcut_flags::*;
+pub use user_profiles::*;
 pub use utils::*;
+pub use vdf::*;
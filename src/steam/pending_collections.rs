@@ -0,0 +1,224 @@
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::config::get_pending_collections_file;
+
+use super::{
+    is_collections_db_locked, steam_installations, write_collections, Collection,
+    SteamInstallation, SteamInstallationKind, SteamSettings,
+};
+
+/// The file is read, changed and written again by the import and by the watcher, one at a time
+static PENDING_FILE: Mutex<()> = Mutex::new(());
+/// Tells the watcher that collections were kept for later
+static PENDING_ADDED: Notify = Notify::const_new();
+
+fn lock_pending_file() -> MutexGuard<'static, ()> {
+    PENDING_FILE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Collections that could not be written because Steam held the lock of its database
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PendingCollections {
+    pub users: Vec<PendingUserCollections>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingUserCollections {
+    /// The location of the Steam installation the user is in
+    pub location: String,
+    pub steam_user_id: String,
+    pub collections: Vec<Collection>,
+}
+
+impl PendingCollections {
+    /// Only the newest collections of a user are kept, an import writes all of BoilR's collections at once
+    fn add(&mut self, pending: PendingUserCollections) {
+        self.remove(&pending.location, &pending.steam_user_id);
+        self.users.push(pending);
+    }
+
+    fn remove(&mut self, location: &str, steam_user_id: &str) {
+        self.users
+            .retain(|p| p.location != location || p.steam_user_id != steam_user_id);
+    }
+}
+
+/// Writes the collections of a user, they are kept for later if Steam holds the lock of its database.
+/// The pending collections stay locked until they are changed to match, so the watcher can not write older ones over these.
+pub fn write_or_defer_collections(
+    installation: &SteamInstallation,
+    steam_user_id: &str,
+    collections: &[Collection],
+) {
+    let guard = lock_pending_file();
+    match write_collections(installation, steam_user_id, collections) {
+        Ok(()) => {
+            if let Err(err) = forget_pending_collections(&guard, installation, steam_user_id) {
+                eprintln!("Could not forget the pending collections: {err}");
+            }
+        }
+        Err(_e) if is_collections_db_locked(installation) => {
+            println!(
+                "Steam holds the collections database, the collections are written when it lets go"
+            );
+            if let Err(err) = defer_collections(&guard, installation, steam_user_id, collections) {
+                eprintln!("Could not keep the collections for later: {err}");
+            }
+        }
+        Err(e) => eprintln!("Could not write collections: {e}"),
+    }
+}
+
+/// Keeps the collections of a user, so they can be written when Steam lets go of its database
+fn defer_collections(
+    _guard: &MutexGuard<'static, ()>,
+    installation: &SteamInstallation,
+    steam_user_id: &str,
+    collections: &[Collection],
+) -> eyre::Result<()> {
+    let path = get_pending_collections_file();
+    let mut pending = load_from(&path);
+    pending.add(PendingUserCollections {
+        location: installation.location(),
+        steam_user_id: steam_user_id.to_string(),
+        collections: collections.to_vec(),
+    });
+    save_to(&path, &pending)?;
+    PENDING_ADDED.notify_one();
+    Ok(())
+}
+
+/// Waits until collections are kept for later, returns right away if that happened since the last wait
+pub async fn wait_for_pending_collections() {
+    PENDING_ADDED.notified().await;
+}
+
+/// Forgets the collections that waited for a user, after newer ones were written
+fn forget_pending_collections(
+    _guard: &MutexGuard<'static, ()>,
+    installation: &SteamInstallation,
+    steam_user_id: &str,
+) -> eyre::Result<()> {
+    let path = get_pending_collections_file();
+    let mut pending = load_from(&path);
+    if pending.users.is_empty() {
+        return Ok(());
+    }
+    pending.remove(&installation.location(), steam_user_id);
+    save_to(&path, &pending)
+}
+
+/// Writes the collections that are waiting, for the Steam installations that are not running.
+/// Gives the number of users whose collections are still waiting.
+/// Nothing is written if BoilR no longer makes collections, the waiting ones are forgotten instead.
+pub fn apply_pending_collections(settings: &SteamSettings) -> usize {
+    let _guard = lock_pending_file();
+    let path = get_pending_collections_file();
+    if !settings.create_collections {
+        if let Err(err) = save_to(&path, &PendingCollections::default()) {
+            eprintln!("Could not forget the pending collections: {err}");
+        }
+        return 0;
+    }
+    let mut pending = load_from(&path);
+    if pending.users.is_empty() {
+        return 0;
+    }
+    let installations = steam_installations(settings);
+    let mut still_pending = PendingCollections::default();
+    for user in pending.users.drain(..) {
+        //An installation that is not found now can still be there, fx on a drive that is not mounted yet
+        let installation = installations
+            .iter()
+            .find(|installation| installation.location() == user.location)
+            .cloned()
+            .unwrap_or_else(|| {
+                SteamInstallation::new(&user.location, SteamInstallationKind::Configured)
+            });
+        if is_collections_db_locked(&installation) {
+            still_pending.add(user);
+            continue;
+        }
+        match write_collections(&installation, &user.steam_user_id, &user.collections) {
+            Ok(()) => println!(
+                "Wrote the pending collections for Steam user {}",
+                user.steam_user_id
+            ),
+            Err(err) => {
+                eprintln!("Could not write the pending collections: {err}");
+                still_pending.add(user);
+            }
+        }
+    }
+    if let Err(err) = save_to(&path, &still_pending) {
+        eprintln!("Could not save the pending collections: {err}");
+    }
+    still_pending.users.len()
+}
+
+fn load_from(path: &Path) -> PendingCollections {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_to(path: &Path, pending: &PendingCollections) -> eyre::Result<()> {
+    if pending.users.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    std::fs::write(path, serde_json::to_string(pending)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn pending(steam_user_id: &str, collection_name: &str) -> PendingUserCollections {
+        PendingUserCollections {
+            location: "/home/deck/.steam/steam".to_string(),
+            steam_user_id: steam_user_id.to_string(),
+            collections: vec![Collection {
+                name: collection_name.to_string(),
+                game_ids: vec![42],
+            }],
+        }
+    }
+
+    #[test]
+    fn only_the_newest_collections_of_a_user_are_kept() {
+        let path = std::env::temp_dir().join(format!(
+            "boilr-pending-collections-{}.json",
+            std::process::id()
+        ));
+        let mut collections = PendingCollections::default();
+        collections.add(pending("1", "GOG"));
+        collections.add(pending("2", "GOG"));
+        collections.add(pending("1", "Itch"));
+        save_to(&path, &collections).unwrap();
+
+        let loaded = load_from(&path);
+        let names: Vec<(&str, &str)> = loaded
+            .users
+            .iter()
+            .flat_map(|user| {
+                user.collections
+                    .iter()
+                    .map(|c| (user.steam_user_id.as_str(), c.name.as_str()))
+            })
+            .collect();
+        assert_eq!(vec![("2", "GOG"), ("1", "Itch")], names);
+
+        save_to(&path, &PendingCollections::default()).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -1,100 +1,106 @@
-use std::path::Path;
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
 
-use nom::FindSubstring;
+use super::{parse_vdf, SteamInstallation, VdfSection, DEFAULT_COMPAT_TOOL};
 
-pub fn setup_proton_games<B: AsRef<str>>(games: &[B]) -> eyre::Result<()> {
-    if let Ok(home) = std::env::var("HOME") {
-        let config_file = Path::new(&home).join(".local/share/Steam/config/config.vdf");
-        if config_file.exists() {
-            if let Ok(config_content) = std::fs::read_to_string(&config_file) {
-                let new_string = enable_proton_games(config_content, games);
-                std::fs::write(config_file, new_string)?;
-            }
+/// Sets the compat tool of each game, the games are pairs of an app id and the name of the tool chosen for it.
+/// Games without a chosen tool keep the tool Steam has for them, or get the default tool if Steam has none.
+pub fn setup_proton_games<A: AsRef<str>, T: AsRef<str>>(
+    installation: &SteamInstallation,
+    games: &[(A, Option<T>)],
+) -> eyre::Result<()> {
+    let config_file = installation.config_vdf_path();
+    if config_file.exists() {
+        if let Ok(config_content) = std::fs::read_to_string(&config_file) {
+            let new_string = enable_proton_games(config_content, games);
+            std::fs::write(config_file, new_string)?;
         }
     }
     Ok(())
 }
 
-fn enable_proton_games<S: AsRef<str>, B: AsRef<str>>(vdf_content: S, games: &[B]) -> String {
+fn enable_proton_games<S: AsRef<str>, A: AsRef<str>, T: AsRef<str>>(
+    vdf_content: S,
+    games: &[(A, Option<T>)],
+) -> String {
     let vdf_content = vdf_content.as_ref();
-    if let Some(section_info) = find_indexes(vdf_content) {
-        let (base_indent_string, field_indent_string) = {
-            let mut a = String::new();
-            let mut b = String::new();
-            for _i in 0..=section_info.base_indentation {
-                a.push('\t');
-                b.push('\t');
-            }
-            b.push('\t');
-            (a, b)
-        };
-
-        let proton_replace_string = include_str!("proton_string.txt");
-        let section_str = vdf_content.get(section_info.start..section_info.append_end);
-        if let Some(section_str) = section_str {
-            let games_strings_to_add = games
-                .iter()
-                .filter(|g| {
-                    let game_section_start = format!("\"{}\"\n", g.as_ref());
-                    !section_str.contains(&game_section_start)
-                })
-                .map(|game_id| {
-                    let res = proton_replace_string.to_string();
-                    let res = res.replace("\"X\"", &format!("\"{}\"", game_id.as_ref()));
-                    let res = res.replace('=', &base_indent_string);
-                    res.replace('+', &field_indent_string)
-                });
-            let mut new_section = section_str.to_string();
-            for game_string in games_strings_to_add {
-                new_section.push_str(&game_string);
-            }
-            new_section.push_str(&section_info.end_key);
-
-            if let Some(before_section) = vdf_content.get(..section_info.start) {
-                if let Some(after_section) = vdf_content.get(section_info.end..) {
-                    return format!("{before_section}{new_section}{after_section}");
-                }
-            }
+    let mut vdf = match parse_vdf(vdf_content) {
+        Ok(vdf) => vdf,
+        Err(err) => {
+            println!("Could not read the steam config.vdf: {err}");
+            return vdf_content.to_string();
         }
-    } else {
+    };
+    let Some(mapping) = compat_tool_mapping_mut(&mut vdf) else {
         //TODO make this an error instead?
         println!("Could not find proton section in steam, try to manually set proton on at least one game and then rerun");
+        return vdf_content.to_string();
+    };
+    for (game, tool) in games {
+        let is_new = mapping.get_section(game.as_ref()).is_none();
+        let tool = match tool {
+            Some(tool) => tool.as_ref(),
+            //Without a choice, the tool the user picked in Steam is kept
+            None if !is_new => continue,
+            None => DEFAULT_COMPAT_TOOL,
+        };
+        let section = match mapping.insert_section(game.as_ref()) {
+            Ok(section) => section,
+            Err(err) => {
+                println!("Could not set proton for {}: {err}", game.as_ref());
+                continue;
+            }
+        };
+        //Existing mappings only get the new tool, so the rest of what Steam wrote is kept
+        section.set_str("name", tool);
+        if is_new {
+            section.set_str("config", "");
+            section.set_str("Priority", "250");
+        }
     }
-    vdf_content.to_string()
+    vdf.to_string()
 }
 
-struct SectionInfo {
-    start: usize,
-    end: usize,
-    append_end: usize,
-    base_indentation: usize,
-    end_key: String,
+/// Proton can only be turned on for games if config.vdf has a CompatToolMapping section
+pub fn diagnose_config_vdf(installation: &SteamInstallation) -> Vec<DiagnosticCheck> {
+    let config_file = installation.config_vdf_path();
+    let path_check = DiagnosticCheck::path("Steam config.vdf", &config_file, true);
+    if path_check.status != CheckStatus::Ok {
+        return vec![path_check];
+    }
+    let mapping_check = match std::fs::read_to_string(&config_file) {
+        Ok(content) if has_compat_tool_mapping(&content) => DiagnosticCheck::message(
+            CheckStatus::Ok,
+            "Proton settings",
+            "Found the CompatToolMapping section",
+        ),
+        Ok(_) => DiagnosticCheck::message(
+            CheckStatus::Warning,
+            "Proton settings",
+            "No CompatToolMapping section, set proton manually for one game in Steam and try again",
+        ),
+        Err(err) => DiagnosticCheck::message(
+            CheckStatus::Error,
+            "Proton settings",
+            format!("Could not read config.vdf: {err}"),
+        ),
+    };
+    vec![path_check, mapping_check]
 }
 
-fn find_indexes<S: AsRef<str>>(vdf_content: S) -> Option<SectionInfo> {
-    let compat_key = "\"CompatToolMapping\"\n";
-    let vdf_content = vdf_content.as_ref();
-    if let Some(compat_index) = vdf_content.find_substring(compat_key) {
-        let compat_index = compat_index + compat_key.len();
-        let after_key = vdf_content.get(compat_index..);
-        if let Some(base_indentation) = after_key.and_then(|k| k.find('{')) {
-            let mut end_key = "\n".to_string();
-            for _i in 0..base_indentation {
-                end_key.push('\t');
-            }
-            end_key.push('}');
-            if let Some(end_index) = after_key.and_then(|a| a.find_substring(&end_key)) {
-                return Some(SectionInfo {
-                    start: compat_index,
-                    end: compat_index + end_index + end_key.len(),
-                    append_end: compat_index + end_index,
-                    base_indentation,
-                    end_key: end_key.to_string(),
-                });
-            }
-        }
-    }
-    None
+const COMPAT_TOOL_MAPPING_PATH: [&str; 5] = [
+    "InstallConfigStore",
+    "Software",
+    "Valve",
+    "Steam",
+    "CompatToolMapping",
+];
+
+fn has_compat_tool_mapping(vdf_content: &str) -> bool {
+    parse_vdf(vdf_content).is_ok_and(|vdf| vdf.path(&COMPAT_TOOL_MAPPING_PATH).is_some())
+}
+
+fn compat_tool_mapping_mut(vdf: &mut VdfSection) -> Option<&mut VdfSection> {
+    vdf.path_mut(&COMPAT_TOOL_MAPPING_PATH)
 }
 
 #[cfg(test)]
@@ -104,29 +110,25 @@ mod tests {
     //Okay to unwrap in tests
     #![allow(clippy::unwrap_in_result)]
     #![allow(clippy::unwrap_used)]
-    #![allow(clippy::indexing_slicing)]
     use super::*;
 
     #[test]
-    pub fn can_find_index_test() {
+    pub fn can_find_compat_section_test() {
         let input = include_str!("../testdata/vdf/testconfig.vdf");
-        let SectionInfo {
-            start,
-            end,
-            base_indentation,
-            ..
-        } = find_indexes(input).unwrap();
+        let mut vdf = parse_vdf(input).unwrap();
+        let section = compat_tool_mapping_mut(&mut vdf).unwrap();
 
-        let actual = input[start..end].to_string();
+        let actual = format!("\t\t\t\t{{{section}}}");
         let expected = include_str!("../testdata/vdf/compatmappingsection.vdf");
         assert_eq!(expected, actual);
-        assert_eq!(4, base_indentation);
+        assert!(has_compat_tool_mapping(input));
     }
 
     #[test]
     pub fn enable_proton_test() {
         let input = include_str!("../testdata/vdf/testconfig.vdf");
-        let output = enable_proton_games(input, &["42", "43", "44"]);
+        let games = ["42", "43", "44"].map(|id| (id, None::<&str>));
+        let output = enable_proton_games(input, &games);
         let expected = include_str!("../testdata/vdf/testconfig_expected.vdf");
         assert_eq!(expected, output);
     }
@@ -134,8 +136,23 @@ mod tests {
     #[test]
     pub fn enable_proton_test_empty() {
         let input = include_str!("../testdata/vdf/testconfig.vdf");
-        let output = enable_proton_games(input, &["2719403116"]);
+        let output = enable_proton_games(input, &[("2719403116", None::<&str>)]);
         let expected = include_str!("../testdata/vdf/testconfig.vdf");
         assert_eq!(expected, output);
     }
+
+    #[test]
+    pub fn existing_mapping_is_updated_test() {
+        let input = include_str!("../testdata/vdf/testconfig.vdf");
+        let output = enable_proton_games(input, &[("337340", None::<&str>)]);
+        assert_eq!(input, output);
+        let output = enable_proton_games(input, &[("337340", Some("GE-Proton9-5"))]);
+        let expected = input.replacen(
+            "\"337340\"\n\t\t\t\t\t{\n\t\t\t\t\t\t\"name\"\t\t\"proton_7\"",
+            "\"337340\"\n\t\t\t\t\t{\n\t\t\t\t\t\t\"name\"\t\t\"GE-Proton9-5\"",
+            1,
+        );
+        assert_ne!(input, expected);
+        assert_eq!(expected, output);
+    }
 }
@@ -1,76 +1,206 @@
-use std::{ffi::OsStr, process::Command, thread::sleep, time::Duration};
+use std::{
+    ffi::OsStr,
+    process::Child,
+    thread::sleep,
+    time::{Duration, Instant},
+};
 
-use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use sysinfo::{Pid, ProcessesToUpdate, Signal, System};
 
-pub fn ensure_steam_stopped() {
-    #[cfg(target_os = "windows")]
-    let steam_name = "steam.exe";
-    #[cfg(target_family = "unix")]
-    let steam_name = "steam";
+use super::{is_collections_db_locked, SteamInstallation};
 
-    let os_steam_name = OsStr::new(steam_name);
+#[cfg(target_os = "windows")]
+const STEAM_PROCESS_NAME: &str = "steam.exe";
+#[cfg(target_family = "unix")]
+const STEAM_PROCESS_NAME: &str = "steam";
 
-    let s = System::new_all();
-    let processes = s.processes_by_name(os_steam_name);
-    for process in processes {
-        let mut s = System::new();
+/// The arguments that start Steam in another mode than the desktop client
+const MODE_ARGUMENTS: [&str; 6] = [
+    "-gamepadui",
+    "-bigpicture",
+    "-tenfoot",
+    "-steamdeck",
+    "-steamos3",
+    "-silent",
+];
+
+/// How long Steam gets to stop after it is sent a signal, before it is killed
+const SIGNAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How Steam was running before BoilR stopped it, so it can be started the same way again
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SteamRunState {
+    /// The installation that was running, None if Steam was not running
+    pub installation: Option<SteamInstallation>,
+    /// Fx "-gamepadui" if Steam was in Big Picture mode
+    pub mode_arguments: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SteamStopProgress {
+    /// Steam is asked to exit with its own shutdown command
+    AskingToExit,
+    Waiting {
+        seconds_left: u64,
+    },
+    /// Steam did not exit in time and is stopped with signals
+    Forcing,
+    Stopped,
+}
 
-        let quit_res = process.kill_with(sysinfo::Signal::Quit);
-        let kill_res = process.kill_with(sysinfo::Signal::Kill);
+/// Asks Steam to exit and waits for all the installations to let go of their files,
+/// Steam is only stopped with signals if it takes longer than the timeout.
+/// Only one Steam can run at a time, it is asked to exit with the command of the installation it runs from.
+pub fn ensure_steam_stopped<F>(
+    installations: &[SteamInstallation],
+    timeout: Duration,
+    mut report: F,
+) -> SteamRunState
+where
+    F: FnMut(SteamStopProgress),
+{
+    let mut system = System::new_all();
+    let pids: Vec<Pid> = steam_processes(&system)
+        .map(|process| process.pid())
+        .collect();
+    let running = installations
+        .iter()
+        .find(|installation| steam_processes(&system).any(|p| runs_from(p, installation)))
+        .or_else(|| installations.first());
+    let Some(running) = running.filter(|_| !pids.is_empty()) else {
+        return SteamRunState::default();
+    };
+    let state = SteamRunState {
+        installation: Some(running.clone()),
+        mode_arguments: mode_arguments(&system),
+    };
 
-        if quit_res == Some(false) && kill_res == Some(false) {
-            // Couldn't kill the process, this could be because it was already killed or we don't have permissions
-            // For instance, the process "steamos-manager" in the Steam Deck is owned by root
-            continue;
+    report(SteamStopProgress::AskingToExit);
+    let shutdown = running.start_command().arg("-shutdown");
+    let shutdown = match shutdown.spawn() {
+        Ok(shutdown) => Some(shutdown),
+        Err(err) => {
+            eprintln!("Could not ask Steam to shut down: {err:?}");
+            None
         }
-        
-        let pid = process.pid();
-        let process_name = process.name();
-        let pid_arr = [pid];
-        let process_to_update = ProcessesToUpdate::Some(&pid_arr);
-
-        while s.refresh_processes_specifics(process_to_update, true,ProcessRefreshKind::everything()) > 0 
-                // The process is still alive
-                && s.process(pid).is_some()
-                 {
-            println!("Waiting for steam to stop. PID: {pid:?} Name: {process_name:?}");
-            sleep(Duration::from_millis(500));
-            process.kill_with(sysinfo::Signal::Quit);
-            process.kill_with(sysinfo::Signal::Kill);
+    };
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if is_steam_stopped(&mut system, &pids, installations) {
+            reap(shutdown);
+            report(SteamStopProgress::Stopped);
+            println!("Steam is stopped");
+            return state;
         }
+        let seconds_left = timeout.saturating_sub(start.elapsed()).as_secs();
+        report(SteamStopProgress::Waiting { seconds_left });
+        sleep(POLL_INTERVAL);
     }
+
+    println!("Steam did not stop within {timeout:?}, stopping it with signals");
+    reap(shutdown);
+    report(SteamStopProgress::Forcing);
+    for signal in [Signal::Term, Signal::Kill] {
+        send_signal(&mut system, &pids, signal);
+        let start = Instant::now();
+        while start.elapsed() < SIGNAL_TIMEOUT
+            && !is_steam_stopped(&mut system, &pids, installations)
+        {
+            sleep(POLL_INTERVAL);
+        }
+    }
+    report(SteamStopProgress::Stopped);
     println!("Steam is stopped");
+    state
 }
-#[cfg(target_os = "windows")]
-pub fn ensure_steam_started(settings: &super::SteamSettings) {
-    let steam_name = "steam.exe";
-    let os_steam_name = OsStr::new(steam_name);
-    let s = System::new_all();
-    let mut processes = s.processes_by_name(os_steam_name);
-    if processes.next().is_none() {
-        //no steam, we need to start it
-        println!("Starting steam");
-        let folder = super::get_steam_path(settings);
-        if let Ok(folder) = folder {
-            let path = std::path::Path::new(&folder).join(steam_name);
-            let mut command = Command::new(path);
-            if let Err(e) = command.spawn() {
-                println!("Failed to start steam: {:?}", e);
-            };
+
+/// Waits for the shutdown command, so it does not linger as a zombie while BoilR runs.
+/// If it still runs once Steam is gone or the timeout ran out it is stopped, it has nothing left to do.
+fn reap(shutdown: Option<Child>) {
+    let Some(mut shutdown) = shutdown else {
+        return;
+    };
+    if !matches!(shutdown.try_wait(), Ok(Some(_))) {
+        let _ = shutdown.kill();
+        let _ = shutdown.wait();
+    }
+}
+
+/// If any Steam is running, Steam writes some of its files again when it exits
+pub fn is_steam_running() -> bool {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    let running = steam_processes(&system).next().is_some();
+    running
+}
+
+fn steam_processes(system: &System) -> impl Iterator<Item = &sysinfo::Process> {
+    //processes_by_name also finds fx "steamwebhelper" and "steamos-manager", which stop with Steam or are not Steam at all
+    system
+        .processes()
+        .values()
+        .filter(|process| process.name() == OsStr::new(STEAM_PROCESS_NAME))
+}
+
+/// If the process is a program inside the installation's folder, fx ubuntu12_32/steam
+fn runs_from(process: &sysinfo::Process, installation: &SteamInstallation) -> bool {
+    let root = installation.root();
+    let canonical_root = root.canonicalize().ok();
+    process.exe().is_some_and(|exe| {
+        exe.starts_with(root) || canonical_root.as_ref().is_some_and(|r| exe.starts_with(r))
+    })
+}
+
+fn mode_arguments(system: &System) -> Vec<String> {
+    let mut result = vec![];
+    for argument in steam_processes(system).flat_map(|process| process.cmd()) {
+        let argument = argument.to_string_lossy();
+        if MODE_ARGUMENTS.contains(&argument.as_ref()) && !result.iter().any(|a| a == &argument) {
+            result.push(argument.to_string());
         }
     }
+    result
 }
 
-#[cfg(target_family = "unix")]
-pub fn ensure_steam_started(_settings: &super::SteamSettings) {
-    let steam_name = "steam";
-    let os_steam_name = OsStr::new(steam_name);
+/// Steam is stopped when its processes are gone and no installation holds the lock of its collections database
+fn is_steam_stopped(
+    system: &mut System,
+    pids: &[Pid],
+    installations: &[SteamInstallation],
+) -> bool {
+    system.refresh_processes(ProcessesToUpdate::Some(pids), true);
+    let running = pids.iter().any(|pid| system.process(*pid).is_some());
+    !running && !installations.iter().any(is_collections_db_locked)
+}
+
+fn send_signal(system: &mut System, pids: &[Pid], signal: Signal) {
+    system.refresh_processes(ProcessesToUpdate::Some(pids), true);
+    for pid in pids {
+        if let Some(process) = system.process(*pid) {
+            //Windows has no signals, there the process can only be killed
+            if process.kill_with(signal).is_none() {
+                process.kill();
+            }
+        }
+    }
+}
+
+/// Starts Steam if it is not already running, only one Steam can run at a time.
+/// If BoilR stopped Steam, the installation that was running is started in the mode it was in, fx Big Picture.
+/// Otherwise the first installation BoilR synced to is started.
+pub fn ensure_steam_started(installations: &[SteamInstallation], run_state: &SteamRunState) {
+    let Some(installation) = run_state.installation.as_ref().or(installations.first()) else {
+        return;
+    };
     let s = System::new_all();
-    let mut processes = s.processes_by_name(os_steam_name);
-    if processes.next().is_none() {
+    if steam_processes(&s).next().is_none() {
         //no steam, we need to start it
         println!("Starting steam");
-        let mut command = Command::new(steam_name);
+        let command = installation
+            .start_command()
+            .args(run_state.mode_arguments.iter().cloned());
         if let Err(e) = command.spawn() {
             println!("Failed to start steam: {e:?}");
         };
@@ -1,10 +1,99 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+use super::{ControllerTemplate, ShortcutFlags};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SteamSettings {
     pub location: Option<String>,
     pub create_collections: bool,
+    /// The collections that are created if create_collections is set, by default one for each platform
+    #[serde(default = "default_collection_rules")]
+    pub collection_rules: Vec<CollectionRule>,
     pub optimize_for_big_picture: bool,
     pub stop_steam: bool,
     pub start_steam: bool,
+    /// How long Steam gets to exit by itself when it is stopped, before it is stopped with signals
+    #[serde(default = "default_stop_timeout_seconds")]
+    pub stop_timeout_seconds: u64,
+    /// The Steam folders a sync writes to, if none are chosen it is the location above or the first Steam that is found
+    #[serde(default)]
+    pub sync_locations: Vec<String>,
+    /// The compat tool for the games of a platform that need Proton, by the code name of the platform
+    #[serde(default)]
+    pub platform_compat_tools: HashMap<String, String>,
+    /// The flags for the shortcuts of a platform, by the code name of the platform
+    #[serde(default)]
+    pub platform_shortcut_flags: HashMap<String, ShortcutFlags>,
+    /// The Steam Input template for the shortcuts of a platform, by the code name of the platform
+    #[serde(default)]
+    pub platform_controller_templates: HashMap<String, ControllerTemplate>,
+    /// Left out games are hidden in Steam instead of being removed
+    #[serde(default)]
+    pub hide_blacklisted_games: bool,
+    /// The ids of the users a sync leaves alone, by the Steam folder they are in
+    #[serde(default)]
+    pub skipped_users: HashMap<String, Vec<String>>,
+}
+
+/// Is replaced with the name of the platform of the games, in the name of a collection rule
+pub const PLATFORM_PLACEHOLDER: &str = "{platform}";
+
+/// A collection of the games that match all the filters that are set
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CollectionRule {
+    /// The name of the collection, "{platform}" makes a collection for each platform
+    pub name: String,
+    /// The names of the platforms the games are from
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    /// The game must have one of these tags or genres
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// A regular expression the name of the game must match
+    #[serde(default)]
+    pub name_regex: Option<String>,
+    #[serde(default)]
+    pub needs_proton: Option<bool>,
+    #[serde(default)]
+    pub app_ids: Vec<u32>,
+}
+
+impl CollectionRule {
+    /// The built-in rule that makes a collection for each platform, named after the platform
+    pub fn platforms() -> Self {
+        Self {
+            name: PLATFORM_PLACEHOLDER.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+fn default_collection_rules() -> Vec<CollectionRule> {
+    vec![CollectionRule::platforms()]
+}
+
+fn default_stop_timeout_seconds() -> u64 {
+    30
+}
+
+impl Default for SteamSettings {
+    fn default() -> Self {
+        Self {
+            location: None,
+            create_collections: false,
+            collection_rules: default_collection_rules(),
+            optimize_for_big_picture: false,
+            stop_steam: false,
+            start_steam: false,
+            stop_timeout_seconds: default_stop_timeout_seconds(),
+            sync_locations: Default::default(),
+            platform_compat_tools: Default::default(),
+            platform_shortcut_flags: Default::default(),
+            platform_controller_templates: Default::default(),
+            hide_blacklisted_games: false,
+            skipped_users: Default::default(),
+        }
+    }
 }
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use steam_shortcuts_util::shortcut::ShortcutOwned;
+
+use super::SteamSettings;
+
+/// The flags of a Steam shortcut that BoilR can set, the ones that are not set are left as Steam has them
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutFlags {
+    pub is_hidden: Option<bool>,
+    pub allow_desktop_config: Option<bool>,
+    pub allow_overlay: Option<bool>,
+    pub open_vr: Option<bool>,
+    /// The app id Steam uses for the shortcut on a devkit
+    pub devkit_override_app_id: Option<u32>,
+}
+
+impl ShortcutFlags {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// The flags that are set here, and the other flags where none are set here
+    pub fn or(&self, other: &ShortcutFlags) -> ShortcutFlags {
+        ShortcutFlags {
+            is_hidden: self.is_hidden.or(other.is_hidden),
+            allow_desktop_config: self.allow_desktop_config.or(other.allow_desktop_config),
+            allow_overlay: self.allow_overlay.or(other.allow_overlay),
+            open_vr: self.open_vr.or(other.open_vr),
+            devkit_override_app_id: self.devkit_override_app_id.or(other.devkit_override_app_id),
+        }
+    }
+
+    pub fn apply(&self, shortcut: &mut ShortcutOwned) {
+        if let Some(is_hidden) = self.is_hidden {
+            shortcut.is_hidden = is_hidden;
+        }
+        if let Some(allow_desktop_config) = self.allow_desktop_config {
+            shortcut.allow_desktop_config = allow_desktop_config;
+        }
+        if let Some(allow_overlay) = self.allow_overlay {
+            shortcut.allow_overlay = allow_overlay;
+        }
+        if let Some(open_vr) = self.open_vr {
+            shortcut.open_vr = u32::from(open_vr);
+        }
+        if let Some(app_id) = self.devkit_override_app_id {
+            shortcut.dev_kit_overrite_app_id = app_id;
+        }
+    }
+}
+
+/// The flags for a game, the flags chosen for the game win over the ones chosen for its platform
+pub fn choose_shortcut_flags(
+    settings: &SteamSettings,
+    game_flags: &HashMap<u32, ShortcutFlags>,
+    platform_code_name: &str,
+    app_id: u32,
+) -> ShortcutFlags {
+    let game = game_flags.get(&app_id).copied().unwrap_or_default();
+    let platform = settings
+        .platform_shortcut_flags
+        .get(platform_code_name)
+        .copied()
+        .unwrap_or_default();
+    game.or(&platform)
+}
+
+/// Keeps the flags a shortcut had in Steam, so changes made in Steam survive the next import.
+/// Hidden is not kept, it follows the choices in BoilR.
+pub fn keep_shortcut_flags(old: &ShortcutOwned, new: &mut ShortcutOwned) {
+    new.allow_desktop_config = old.allow_desktop_config;
+    new.allow_overlay = old.allow_overlay;
+    new.open_vr = old.open_vr;
+    new.dev_kit_overrite_app_id = old.dev_kit_overrite_app_id;
+}
+
+#[cfg(test)]
+mod tests {
+    use steam_shortcuts_util::Shortcut;
+
+    use super::*;
+
+    #[test]
+    fn game_flags_win_over_platform_flags() {
+        let mut settings = SteamSettings::default();
+        settings.platform_shortcut_flags.insert(
+            "epic".to_string(),
+            ShortcutFlags {
+                allow_overlay: Some(false),
+                allow_desktop_config: Some(false),
+                ..Default::default()
+            },
+        );
+        let games = HashMap::from([(
+            42,
+            ShortcutFlags {
+                allow_desktop_config: Some(true),
+                open_vr: Some(true),
+                ..Default::default()
+            },
+        )]);
+        let flags = choose_shortcut_flags(&settings, &games, "epic", 42);
+        assert_eq!(Some(false), flags.allow_overlay);
+        assert_eq!(Some(true), flags.allow_desktop_config);
+
+        let mut shortcut = Shortcut::new("0", "Fortnite", "/bin/game", "", "", "", "").to_owned();
+        shortcut.is_hidden = true;
+        flags.apply(&mut shortcut);
+        assert!(!shortcut.allow_overlay);
+        assert!(shortcut.allow_desktop_config);
+        assert_eq!(1, shortcut.open_vr);
+        assert!(shortcut.is_hidden);
+
+        assert!(choose_shortcut_flags(&settings, &games, "gog", 43).is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use super::{parse_vdf, SteamInstallation, SteamUsersInfo};
+
+/// Steam ids in loginusers.vdf and the avatar cache are this plus the id of the userdata folder
+const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct LoginUser {
+    account_name: Option<String>,
+    persona_name: Option<String>,
+}
+
+/// Fills in the names and avatars of the users from what Steam remembers about them
+pub fn load_user_profiles(installation: &SteamInstallation, users: &mut [SteamUsersInfo]) {
+    let config_folder = installation.root().join("config");
+    let login_users = std::fs::read_to_string(config_folder.join("loginusers.vdf"))
+        .map(parse_login_users)
+        .unwrap_or_default();
+    for user in users {
+        let Some(steam_id) = steam_id64(&user.user_id) else {
+            continue;
+        };
+        if let Some(login_user) = login_users.get(&steam_id) {
+            user.account_name.clone_from(&login_user.account_name);
+            user.persona_name.clone_from(&login_user.persona_name);
+        }
+        if user.persona_name.is_none() {
+            //Users that are not remembered on the login screen still have their name in their own settings
+            user.persona_name =
+                std::fs::read_to_string(installation.localconfig_path(&user.user_id))
+                    .ok()
+                    .and_then(parse_localconfig_persona_name);
+        }
+        let avatar = config_folder
+            .join("avatarcache")
+            .join(format!("{steam_id}.png"));
+        user.avatar_path = avatar
+            .exists()
+            .then(|| avatar.to_string_lossy().to_string());
+    }
+}
+
+fn steam_id64(user_id: &str) -> Option<u64> {
+    user_id
+        .parse::<u64>()
+        .ok()
+        .and_then(|account_id| account_id.checked_add(STEAM_ID64_BASE))
+}
+
+/// The users in loginusers.vdf, by their 64 bit Steam id
+fn parse_login_users<S: AsRef<str>>(content: S) -> HashMap<u64, LoginUser> {
+    let Ok(vdf) = parse_vdf(content) else {
+        return HashMap::new();
+    };
+    let Some(users) = vdf.get_section("users") else {
+        return HashMap::new();
+    };
+    users
+        .entries()
+        .filter_map(|entry| {
+            let steam_id = entry.key().parse::<u64>().ok()?;
+            let user = entry.as_section()?;
+            let non_empty = |key| {
+                user.get_str(key)
+                    .filter(|value| !value.trim().is_empty())
+                    .map(str::to_string)
+            };
+            let login_user = LoginUser {
+                account_name: non_empty("AccountName"),
+                persona_name: non_empty("PersonaName"),
+            };
+            Some((steam_id, login_user))
+        })
+        .collect()
+}
+
+fn parse_localconfig_persona_name<S: AsRef<str>>(content: S) -> Option<String> {
+    let vdf = parse_vdf(content).ok()?;
+    vdf.path(&["UserLocalConfigStore", "friends"])?
+        .get_str("PersonaName")
+        .filter(|name| !name.trim().is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::steam::SteamInstallationKind;
+
+    #[test]
+    fn parse_login_users_test() {
+        let users = parse_login_users(include_str!("../testdata/vdf/loginusers.vdf"));
+        assert_eq!(2, users.len());
+        let gaben = LoginUser {
+            account_name: Some("gaben".to_string()),
+            persona_name: Some("Rabscuttle".to_string()),
+        };
+        assert_eq!(Some(&gaben), users.get(&76561197960287930));
+        let tester = steam_id64("39734314").and_then(|id| users.get(&id));
+        assert_eq!(
+            Some("Test \"Deck\" account"),
+            tester.and_then(|user| user.persona_name.as_deref())
+        );
+    }
+
+    #[test]
+    fn profiles_are_loaded_for_the_users_folders() {
+        let root =
+            std::env::temp_dir().join(format!("boilr-steam-profiles-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let config = root.join("config");
+        std::fs::create_dir_all(config.join("avatarcache")).unwrap();
+        std::fs::write(
+            config.join("loginusers.vdf"),
+            include_str!("../testdata/vdf/loginusers.vdf"),
+        )
+        .unwrap();
+        std::fs::copy(
+            "src/testdata/smallpng.png",
+            config.join("avatarcache").join("76561197960287930.png"),
+        )
+        .unwrap();
+        for user in ["22202", "7"] {
+            std::fs::create_dir_all(root.join("userdata").join(user).join("config")).unwrap();
+        }
+        std::fs::write(
+            root.join("userdata/7/config/localconfig.vdf"),
+            "\"UserLocalConfigStore\"\n{\n\t\"friends\"\n\t{\n\t\t\"PersonaName\"\t\t\"Offline friend\"\n\t}\n}\n",
+        )
+        .unwrap();
+
+        let installation = SteamInstallation::new(&root, SteamInstallationKind::Configured);
+        let users = installation.users_with_profiles().unwrap();
+        let names: Vec<String> = users.iter().map(SteamUsersInfo::display_name).collect();
+        assert_eq!(vec!["Rabscuttle (gaben)", "Offline friend"], names);
+        assert!(users.first().unwrap().avatar_path.is_some());
+        assert!(users.last().unwrap().avatar_path.is_none());
+    }
+}
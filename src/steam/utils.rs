@@ -1,12 +1,10 @@
-#[cfg(target_os = "windows")]
-use std::env::{self};
 use std::error::Error;
 use std::path::PathBuf;
 use std::{fmt, path::Path};
 
 use steam_shortcuts_util::{parse_shortcuts, shortcut::ShortcutOwned};
 
-use super::SteamSettings;
+use super::{users_of_sync_targets, SteamSettings};
 
 pub fn get_shortcuts_for_user(user: &SteamUsersInfo) -> eyre::Result<ShortcutInfo> {
     let mut shortcuts = vec![];
@@ -48,116 +46,37 @@ pub struct SteamUsersInfo {
     pub steam_user_data_folder: String,
     pub shortcut_path: Option<String>,
     pub user_id: String,
+    /// The name other Steam users see
+    pub persona_name: Option<String>,
+    /// The name the user logs in to Steam with
+    pub account_name: Option<String>,
+    /// The avatar Steam keeps in its cache for the user
+    pub avatar_path: Option<String>,
 }
 
-/// Get the paths to the steam users shortcuts (one for each user)
-pub fn get_shortcuts_paths(settings: &SteamSettings) -> eyre::Result<Vec<SteamUsersInfo>> {
-    let steam_path_str = get_steam_path(settings)?;
-    let steam_path = Path::new(&steam_path_str);
-    if !steam_path.exists() {
-        return Err(eyre::format_err!(
-            "Steam folder not found at: {:?}",
-            steam_path
-        ));
-    }
-
-    let user_data_path = steam_path.join("userdata");
-    if !user_data_path.exists() {
-        return Err(eyre::format_err!(
-            "Steam user data folder not found at: {:?}",
-            user_data_path
-        ));
-    }
-
-    let user_folders = std::fs::read_dir(&user_data_path)?;
-    let users_info = user_folders
-        .filter_map(|f| f.ok())
-        .filter(|folder| match folder.metadata() {
-            Ok(meta) => meta.is_dir(),
-            _ => false,
-        })
-        .filter(|folder| {
-            let user_id = folder.file_name().to_string_lossy().to_string();
-            user_id != "0"
-        })
-        .map(|folder| {
-            let user_id = folder.file_name().to_string_lossy().to_string();
-            let folder_path = folder.path();
-            let folder_str = folder_path
-                .to_str()
-                .unwrap_or_default();
-            let path = format!("{folder_str}//config//shortcuts.vdf");
-            let shortcuts_path = Path::new(path.as_str());
-            let folder_string = folder_str.to_string();
-            if shortcuts_path.exists() {
-                SteamUsersInfo {
-                    steam_user_data_folder: folder_string,
-                    shortcut_path: Some(shortcuts_path.to_string_lossy().to_string()),
-                    user_id,
-                }
-            } else {
-                SteamUsersInfo {
-                    steam_user_data_folder: folder_string,
-                    shortcut_path: None,
-                    user_id,
-                }
+impl SteamUsersInfo {
+    /// The name to show for the user, fx "Rabscuttle (gaben)", the user id if Steam does not know the name
+    pub fn display_name(&self) -> String {
+        match (&self.persona_name, &self.account_name) {
+            (Some(persona), Some(account)) if persona != account => {
+                format!("{persona} ({account})")
             }
-        })
-        .collect();
-    Ok(users_info)
+            (Some(name), _) | (None, Some(name)) => name.clone(),
+            (None, None) => format!("Steam user id: {}", self.user_id),
+        }
+    }
 }
 
-pub fn get_steam_path(settings: &SteamSettings) -> eyre::Result<String> {
-    let user_location = settings.location.clone();
-    let steam_path_str = match user_location {
-        Some(location) => location,
-        None => get_default_location()?,
-    };
-    Ok(steam_path_str)
+/// Get the paths to the steam users shortcuts (one for each user), in all the Steam installations a sync writes to
+pub fn get_shortcuts_paths(settings: &SteamSettings) -> eyre::Result<Vec<SteamUsersInfo>> {
+    users_of_sync_targets(settings, |installation| installation.users())
 }
 
-pub fn get_default_location() -> eyre::Result<String> {
-    #[cfg(target_os = "windows")]
-    let path_string = {
-        let key = "PROGRAMFILES(X86)";
-        let program_files = env::var(key)?;
-        String::from(
-            Path::new(&program_files)
-                .join("Steam")
-                .to_str()
-                .unwrap_or(""),
-        )
-    };
-    #[cfg(target_os = "linux")]
-    let path_string = {
-        let home = std::env::var("HOME")?;
-        let default_path = Path::new(&home).join(".steam").join("steam");
-        if default_path.exists() {
-            default_path.to_string_lossy().to_string()
-        } else {
-            Path::new(&home)
-                .join(".var")
-                .join("app")
-                .join("com.valvesoftware.Steam")
-                .join(".steam")
-                .join("steam")
-                .to_string_lossy()
-                .to_string()
-        }
-    };
-    #[cfg(target_os = "macos")]
-    let path_string = {
-        let home = std::env::var("HOME")?;
-        String::from(
-            Path::new(&home)
-                .join("Library")
-                .join("Application Support")
-                .join("Steam")
-                .to_str()
-                .unwrap_or(""),
-        )
-    };
-    Ok(path_string)
+/// Like [`get_shortcuts_paths`], with the names and avatars of the users for showing them
+pub fn get_shortcuts_paths_with_profiles(
+    settings: &SteamSettings,
+) -> eyre::Result<Vec<SteamUsersInfo>> {
+    users_of_sync_targets(settings, |installation| installation.users_with_profiles())
 }
 
 #[derive(Debug)]
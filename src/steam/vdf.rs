@@ -0,0 +1,602 @@
+//! Reads and edits Valve's text KeyValues files (config.vdf, localconfig.vdf, libraryfolders.vdf, appmanifest acf files).
+//! Everything that is not changed is written back exactly as it was read,
+//! including whitespace, comments and how the strings were escaped.
+
+/// A section of key values, the whole file is the root section
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VdfSection {
+    entries: Vec<VdfEntry>,
+    /// Whitespace and comments before the closing brace, or before the end of the file
+    end: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VdfEntry {
+    /// Whitespace and comments before the key
+    before: String,
+    key: VdfText,
+    condition: Option<VdfCondition>,
+    value: VdfValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VdfValue {
+    Text { before: String, text: VdfText },
+    Section { before: String, section: VdfSection },
+}
+
+/// A platform condition like [$WIN32] or [!$POSIX].
+/// It comes after the value of a string, and between the key and the brace of a section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VdfCondition {
+    before: String,
+    text: String,
+}
+
+/// A key or a string value, the raw text is kept so it is written back the same way it was read
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VdfText {
+    value: String,
+    raw: Option<String>,
+}
+
+impl VdfText {
+    fn new<S: Into<String>>(value: S) -> Self {
+        Self {
+            value: value.into(),
+            raw: None,
+        }
+    }
+}
+
+impl std::fmt::Display for VdfText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.raw {
+            Some(raw) => write!(f, "{raw}"),
+            None => write!(f, "\"{}\"", escape(&self.value)),
+        }
+    }
+}
+
+pub fn parse_vdf<S: AsRef<str>>(input: S) -> eyre::Result<VdfSection> {
+    let mut parser = Parser {
+        input: input.as_ref(),
+        pos: 0,
+    };
+    parser.section(false)
+}
+
+impl VdfSection {
+    /// All the entries, also those whose condition does not hold on this platform
+    pub fn entries(&self) -> impl Iterator<Item = &VdfEntry> {
+        self.entries.iter()
+    }
+
+    /// The first entry with the key (keys are not case sensitive) whose condition holds on this platform
+    pub fn get(&self, key: &str) -> Option<&VdfEntry> {
+        self.entries.iter().find(|e| e.matches(key))
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut VdfEntry> {
+        self.entries.iter_mut().find(|e| e.matches(key))
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(VdfEntry::as_str)
+    }
+
+    pub fn get_section(&self, key: &str) -> Option<&VdfSection> {
+        self.get(key).and_then(VdfEntry::as_section)
+    }
+
+    pub fn get_section_mut(&mut self, key: &str) -> Option<&mut VdfSection> {
+        self.get_mut(key).and_then(VdfEntry::as_section_mut)
+    }
+
+    /// The section found by following the keys from this section
+    pub fn path(&self, keys: &[&str]) -> Option<&VdfSection> {
+        keys.iter()
+            .try_fold(self, |section, key| section.get_section(key))
+    }
+
+    pub fn path_mut(&mut self, keys: &[&str]) -> Option<&mut VdfSection> {
+        keys.iter()
+            .try_fold(self, |section, key| section.get_section_mut(key))
+    }
+
+    /// The first entry with the key in this section or any section below it
+    pub fn find(&self, key: &str) -> Option<&VdfEntry> {
+        self.get(key).or_else(|| {
+            self.entries
+                .iter()
+                .filter_map(VdfEntry::as_section)
+                .find_map(|section| section.find(key))
+        })
+    }
+
+    pub fn find_mut(&mut self, key: &str) -> Option<&mut VdfEntry> {
+        if self.get(key).is_some() {
+            return self.get_mut(key);
+        }
+        self.entries
+            .iter_mut()
+            .filter_map(VdfEntry::as_section_mut)
+            .find_map(|section| section.find_mut(key))
+    }
+
+    /// Sets the string value of the key, the entry is added at the end of the section if it is not there
+    pub fn set_str<K: AsRef<str>, V: AsRef<str>>(&mut self, key: K, value: V) {
+        let (key, value) = (key.as_ref(), value.as_ref());
+        if let Some(entry) = self.get_mut(key).filter(|e| e.as_str().is_some()) {
+            entry.set_str(value);
+            return;
+        }
+        let entry = VdfEntry {
+            before: self.child_indentation(),
+            key: VdfText::new(key),
+            condition: None,
+            value: VdfValue::Text {
+                before: self.separator(),
+                text: VdfText::new(value),
+            },
+        };
+        self.entries.push(entry);
+    }
+
+    /// The section with the key, an empty section is added at the end if the key is not there.
+    /// It is an error if the key has a string value.
+    pub fn insert_section<K: AsRef<str>>(&mut self, key: K) -> eyre::Result<&mut VdfSection> {
+        let key = key.as_ref();
+        if self.get(key).is_none() {
+            let indentation = self.child_indentation();
+            self.entries.push(VdfEntry {
+                before: indentation.clone(),
+                key: VdfText::new(key),
+                condition: None,
+                value: VdfValue::Section {
+                    before: indentation.clone(),
+                    section: VdfSection {
+                        entries: vec![],
+                        end: indentation,
+                    },
+                },
+            });
+        }
+        self.get_section_mut(key)
+            .ok_or_else(|| eyre::format_err!("\"{key}\" is a string and not a section"))
+    }
+
+    /// Takes out the entries with the key, true if there were any
+    pub fn remove(&mut self, key: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| !e.matches(key));
+        self.entries.len() != before
+    }
+
+    /// The whitespace before a new entry, copied from the last entry or from the closing brace
+    fn child_indentation(&self) -> String {
+        let indentation = match self.entries.last() {
+            Some(last) => last_line(&last.before).to_string(),
+            None if self.end.contains('\n') => format!("{}\t", last_line(&self.end)),
+            None => " ".to_string(),
+        };
+        if indentation.is_empty() {
+            "\n".to_string()
+        } else {
+            indentation
+        }
+    }
+
+    /// The whitespace between a key and its string value, copied from the other strings of the section
+    fn separator(&self) -> String {
+        self.entries
+            .iter()
+            .find_map(|e| match &e.value {
+                VdfValue::Text { before, .. } => Some(before.clone()),
+                VdfValue::Section { .. } => None,
+            })
+            .unwrap_or_else(|| "\t\t".to_string())
+    }
+}
+
+/// The whitespace from the last line break, or all of it if there is no line break
+fn last_line(whitespace: &str) -> &str {
+    whitespace
+        .rfind('\n')
+        .and_then(|index| whitespace.get(index..))
+        .unwrap_or(whitespace)
+}
+
+impl std::fmt::Display for VdfSection {
+    /// The entries of the section without its braces, for the root section that is the whole file
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.entries {
+            write!(f, "{}{}", entry.before, entry.key)?;
+            match &entry.value {
+                VdfValue::Text { before, text } => {
+                    write!(f, "{before}{text}")?;
+                    if let Some(condition) = &entry.condition {
+                        write!(f, "{}[{}]", condition.before, condition.text)?;
+                    }
+                }
+                VdfValue::Section { before, section } => {
+                    if let Some(condition) = &entry.condition {
+                        write!(f, "{}[{}]", condition.before, condition.text)?;
+                    }
+                    write!(f, "{before}{{{section}}}")?;
+                }
+            }
+        }
+        write!(f, "{}", self.end)
+    }
+}
+
+impl VdfEntry {
+    pub fn key(&self) -> &str {
+        &self.key.value
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            VdfValue::Text { text, .. } => Some(&text.value),
+            VdfValue::Section { .. } => None,
+        }
+    }
+
+    pub fn as_section(&self) -> Option<&VdfSection> {
+        match &self.value {
+            VdfValue::Section { section, .. } => Some(section),
+            VdfValue::Text { .. } => None,
+        }
+    }
+
+    pub fn as_section_mut(&mut self) -> Option<&mut VdfSection> {
+        match &mut self.value {
+            VdfValue::Section { section, .. } => Some(section),
+            VdfValue::Text { .. } => None,
+        }
+    }
+
+    /// Sets the value if it is a string, false if the entry is a section
+    pub fn set_str<V: AsRef<str>>(&mut self, value: V) -> bool {
+        let value = value.as_ref();
+        match &mut self.value {
+            VdfValue::Text { text, .. } => {
+                //Keep the raw text when nothing changed, so it is written back the same way
+                if text.value != value {
+                    *text = VdfText::new(value);
+                }
+                true
+            }
+            VdfValue::Section { .. } => false,
+        }
+    }
+
+    /// The condition without its brackets, fx "$WIN32"
+    pub fn condition(&self) -> Option<&str> {
+        self.condition.as_ref().map(|c| c.text.as_str())
+    }
+
+    /// If the entry has no condition, or its condition holds on this platform
+    pub fn applies(&self) -> bool {
+        self.condition().map(condition_holds).unwrap_or(true)
+    }
+
+    fn matches(&self, key: &str) -> bool {
+        self.key.value.eq_ignore_ascii_case(key) && self.applies()
+    }
+}
+
+/// Evaluates conditions like "$WIN32", "!$POSIX" and "$OSX || $LINUX"
+fn condition_holds(condition: &str) -> bool {
+    condition.split("||").any(|any| {
+        any.split("&&").all(|term| {
+            let term = term.trim();
+            match term.strip_prefix('!') {
+                Some(negated) => !platform_flag(negated.trim()),
+                None => platform_flag(term),
+            }
+        })
+    })
+}
+
+fn platform_flag(name: &str) -> bool {
+    match name.to_ascii_uppercase().as_str() {
+        "$WIN32" | "$WINDOWS" => cfg!(target_os = "windows"),
+        "$WIN64" => cfg!(all(target_os = "windows", target_pointer_width = "64")),
+        "$POSIX" => cfg!(target_family = "unix"),
+        "$LINUX" => cfg!(target_os = "linux"),
+        "$OSX" => cfg!(target_os = "macos"),
+        _ => false,
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        self.input.get(self.pos..).unwrap_or_default()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn line(&self) -> usize {
+        self.input
+            .get(..self.pos)
+            .map(|before| before.matches('\n').count() + 1)
+            .unwrap_or(1)
+    }
+
+    fn error<S: AsRef<str>>(&self, message: S) -> eyre::Report {
+        eyre::format_err!("{} on line {}", message.as_ref(), self.line())
+    }
+
+    /// Whitespace and // comments
+    fn trivia(&mut self) -> String {
+        let start = self.pos;
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+                self.pos += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+        self.input
+            .get(start..self.pos)
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn section(&mut self, nested: bool) -> eyre::Result<VdfSection> {
+        let mut entries = vec![];
+        loop {
+            let before = self.trivia();
+            match self.peek() {
+                None if nested => return Err(self.error("A section is not closed")),
+                None => {
+                    return Ok(VdfSection {
+                        entries,
+                        end: before,
+                    })
+                }
+                Some('}') if nested => {
+                    self.pos += 1;
+                    return Ok(VdfSection {
+                        entries,
+                        end: before,
+                    });
+                }
+                Some('}') => return Err(self.error("Unexpected closing brace")),
+                Some('{') => return Err(self.error("A section has no key")),
+                Some(_) => {
+                    let key = self.text()?;
+                    entries.push(self.entry(before, key)?);
+                }
+            }
+        }
+    }
+
+    fn entry(&mut self, before: String, key: VdfText) -> eyre::Result<VdfEntry> {
+        let mut separator = self.trivia();
+        let mut condition = None;
+        if self.peek() == Some('[') {
+            condition = Some(VdfCondition {
+                before: separator,
+                text: self.condition()?,
+            });
+            separator = self.trivia();
+        }
+        let value = match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                VdfValue::Section {
+                    before: separator,
+                    section: self.section(true)?,
+                }
+            }
+            None | Some('}') => return Err(self.error(format!("\"{}\" has no value", key.value))),
+            Some(_) if condition.is_some() => {
+                return Err(self.error("A condition has to come after a string value"))
+            }
+            Some(_) => {
+                let text = self.text()?;
+                //A condition can follow the value on the same line
+                let checkpoint = self.pos;
+                let condition_before = self.trivia();
+                if self.peek() == Some('[') {
+                    condition = Some(VdfCondition {
+                        before: condition_before,
+                        text: self.condition()?,
+                    });
+                } else {
+                    self.pos = checkpoint;
+                }
+                VdfValue::Text {
+                    before: separator,
+                    text,
+                }
+            }
+        };
+        Ok(VdfEntry {
+            before,
+            key,
+            condition,
+            value,
+        })
+    }
+
+    fn condition(&mut self) -> eyre::Result<String> {
+        let rest = self.rest();
+        let end = rest
+            .find(']')
+            .ok_or_else(|| self.error("A condition is not closed"))?;
+        let text = rest.get(1..end).unwrap_or_default().to_string();
+        self.pos += end + 1;
+        Ok(text)
+    }
+
+    /// A quoted or unquoted string
+    fn text(&mut self) -> eyre::Result<VdfText> {
+        let start = self.pos;
+        let value = if self.peek() == Some('"') {
+            self.pos += 1;
+            let mut value = String::new();
+            let mut chars = self.rest().char_indices();
+            loop {
+                match chars.next() {
+                    Some((i, '"')) => {
+                        self.pos += i + 1;
+                        break;
+                    }
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, 'n')) => value.push('\n'),
+                        Some((_, 't')) => value.push('\t'),
+                        Some((_, '\\')) => value.push('\\'),
+                        Some((_, '"')) => value.push('"'),
+                        Some((_, c)) => {
+                            value.push('\\');
+                            value.push(c);
+                        }
+                        None => return Err(self.error("A string is not closed")),
+                    },
+                    Some((_, c)) => value.push(c),
+                    None => return Err(self.error("A string is not closed")),
+                }
+            }
+            value
+        } else {
+            let rest = self.rest();
+            let end = rest
+                .find(|c: char| c.is_whitespace() || matches!(c, '"' | '{' | '}'))
+                .unwrap_or(rest.len());
+            self.pos += end;
+            rest.get(..end).unwrap_or_default().to_string()
+        };
+        let raw = self.input.get(start..self.pos).map(str::to_string);
+        Ok(VdfText { value, raw })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn assert_round_trip(input: &str) {
+        assert_eq!(input, parse_vdf(input).unwrap().to_string());
+    }
+
+    #[test]
+    fn fixtures_round_trip() {
+        assert_round_trip(include_str!("../testdata/vdf/testconfig.vdf"));
+        assert_round_trip(include_str!("../testdata/vdf/testconfig_expected.vdf"));
+        assert_round_trip(include_str!("../testdata/vdf/libraryfolders.vdf"));
+        assert_round_trip(include_str!("../testdata/vdf/compatibilitytool.vdf"));
+        assert_round_trip(include_str!("../testdata/acf/appmanifest_763890.acf"));
+    }
+
+    #[test]
+    fn reads_nested_sections() {
+        let vdf = parse_vdf(include_str!("../testdata/acf/appmanifest_763890.acf")).unwrap();
+        let app = vdf.get_section("AppState").unwrap();
+        assert_eq!(Some("763890"), app.get_str("appid"));
+        assert_eq!(Some("Wildermyth"), app.get_str("NAME"));
+        let depot = app.path(&["InstalledDepots", "763893"]).unwrap();
+        assert_eq!(Some("2035740746"), depot.get_str("size"));
+        assert_eq!(
+            Some("english"),
+            vdf.find("language").and_then(VdfEntry::as_str)
+        );
+    }
+
+    #[test]
+    fn escapes_are_read_and_written() {
+        let input = "\"root\"\n{\n\t\"path\"\t\t\"C:\\\\Games\\\\Steam\"\n\t\"json\"\t\t\"{\\\"a\\\":1}\"\n}\n";
+        let mut vdf = parse_vdf(input).unwrap();
+        let root = vdf.get_section_mut("root").unwrap();
+        assert_eq!(Some("C:\\Games\\Steam"), root.get_str("path"));
+        assert_eq!(Some("{\"a\":1}"), root.get_str("json"));
+        root.set_str("json", "{\"a\":2}");
+        root.set_str("tab", "a\tb");
+        assert_eq!(
+            "\"root\"\n{\n\t\"path\"\t\t\"C:\\\\Games\\\\Steam\"\n\t\"json\"\t\t\"{\\\"a\\\":2}\"\n\t\"tab\"\t\t\"a\\tb\"\n}\n",
+            vdf.to_string()
+        );
+    }
+
+    #[test]
+    fn conditions_and_comments_are_kept() {
+        let input = "// settings\n\"root\" [$WIN32||$OSX]\n{\n\tkey \"windows\" [$WIN32] // only there\n\tkey \"other\" [!$WIN32]\n}\n";
+        assert_round_trip(input);
+        let vdf = parse_vdf(input).unwrap();
+        let entry = vdf.entries().next().unwrap();
+        assert_eq!(Some("$WIN32||$OSX"), entry.condition());
+        let section = entry.as_section().unwrap();
+        if cfg!(target_os = "windows") {
+            assert_eq!(Some("windows"), section.get_str("key"));
+        } else {
+            assert_eq!(Some("other"), section.get_str("key"));
+        }
+    }
+
+    #[test]
+    fn new_entries_follow_the_indentation() {
+        let input = "\"a\"\n{\n\t\"b\"\n\t{\n\t}\n}\n";
+        let mut vdf = parse_vdf(input).unwrap();
+        let b = vdf.path_mut(&["a", "b"]).unwrap();
+        b.insert_section("c").unwrap().set_str("d", "e");
+        assert_eq!(
+            "\"a\"\n{\n\t\"b\"\n\t{\n\t\t\"c\"\n\t\t{\n\t\t\t\"d\"\t\t\"e\"\n\t\t}\n\t}\n}\n",
+            vdf.to_string()
+        );
+        assert!(vdf.path(&["a", "b", "C"]).is_some());
+    }
+
+    #[test]
+    fn strings_are_not_made_sections() {
+        let mut vdf = parse_vdf("\"a\"\n{\n\t\"b\"\t\t\"c\"\n}\n").unwrap();
+        let a = vdf.get_section_mut("a").unwrap();
+        assert!(a.insert_section("B").is_err());
+        assert!(a.insert_section("d").is_ok());
+        assert_eq!(Some("c"), a.get_str("b"));
+    }
+
+    #[test]
+    fn removed_entries_leave_no_lines() {
+        let mut vdf = parse_vdf("\"a\"\n{\n\t\"b\"\t\t\"c\"\n\t\"d\"\t\t\"e\"\n}\n").unwrap();
+        let a = vdf.get_section_mut("a").unwrap();
+        assert!(a.remove("B"));
+        assert!(!a.remove("b"));
+        assert_eq!("\"a\"\n{\n\t\"d\"\t\t\"e\"\n}\n", vdf.to_string());
+    }
+
+    #[test]
+    fn broken_files_are_errors() {
+        assert!(parse_vdf("\"a\"\n{\n\t\"b\"\t\"c\"\n").is_err());
+        assert!(parse_vdf("\"a\"\t\"b\n").is_err());
+        assert!(parse_vdf("\"a\"\n}\n").is_err());
+        assert!(parse_vdf("\"a\"\n").is_err());
+    }
+}
@@ -2,6 +2,7 @@ use dashmap::DashMap;
 use std::{fs::File, io::Write};
 
 use crate::config::get_cache_file;
+use crate::platforms::GameMetadata;
 
 type SearchMap = DashMap<u32, (String, usize)>;
 
@@ -37,6 +38,19 @@ impl<'a> CachedSearch<'a> {
         app_id: u32,
         query: S,
     ) -> Result<Option<usize>, Box<dyn std::error::Error>>
+    where
+        S: AsRef<str> + Into<String>,
+    {
+        self.search_with_metadata(app_id, query, None).await
+    }
+
+    /// Searches for the game, what its platform knows about it is used to pick the right one of the results
+    pub async fn search_with_metadata<S>(
+        &self,
+        app_id: u32,
+        query: S,
+        metadata: Option<&GameMetadata>,
+    ) -> Result<Option<usize>, Box<dyn std::error::Error>>
     where
         S: AsRef<str> + Into<String>,
     {
@@ -46,7 +60,15 @@ impl<'a> CachedSearch<'a> {
         }
         println!("Searching for {}", query.as_ref());
         let search = self.client.search(query.as_ref()).await?;
-        let first_id = search.first().map(|f| f.id);
+        let store = metadata.and_then(|metadata| metadata.store.as_deref());
+        let mut best: Option<(u8, usize)> = None;
+        for result in &search {
+            let score = match_score(query.as_ref(), store, &result.name, &result.types);
+            if best.is_none_or(|(best_score, _)| score > best_score) {
+                best = Some((score, result.id));
+            }
+        }
+        let first_id = best.map(|(_, id)| id);
         match first_id {
             Some(assumed_id) => {
                 self.search_map.insert(app_id, (query.into(), assumed_id));
@@ -57,6 +79,21 @@ impl<'a> CachedSearch<'a> {
     }
 }
 
+/// How well a search result fits the game, a result with the same name wins over one from the same store.
+/// Results that score the same keep the order SteamGridDB gave them.
+fn match_score(query: &str, store: Option<&str>, name: &str, types: &[String]) -> u8 {
+    let same_name = normalize_name(query) == normalize_name(name);
+    let same_store = store.is_some_and(|store| types.iter().any(|t| t == store));
+    u8::from(same_name) * 2 + u8::from(same_store)
+}
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
 fn get_search_map() -> SearchMap {
     let path = get_cache_file();
     if path.exists() {
@@ -76,3 +113,25 @@ fn save_search_map(search_map: &SearchMap) -> eyre::Result<()> {
     file.write_all(string.as_bytes())?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_are_matched_on_name_and_store() {
+        let steam = vec!["steam".to_string()];
+        let epic = vec!["steam".to_string(), "egs".to_string()];
+        assert_eq!(
+            0,
+            match_score("Control", Some("egs"), "Controller Tester", &steam)
+        );
+        assert_eq!(
+            1,
+            match_score("Control", Some("egs"), "Control: Ultimate", &epic)
+        );
+        assert_eq!(2, match_score("Control", Some("egs"), "CONTROL", &steam));
+        assert_eq!(3, match_score("Control", Some("egs"), "Control", &epic));
+        assert_eq!(2, match_score("Control", None, "Control", &epic));
+    }
+}
@@ -15,6 +15,7 @@ use steam_shortcuts_util::shortcut::ShortcutOwned;
 use steamgriddb_api::Client;
 
 use super::CachedSearch;
+use crate::platforms::GameMetadata;
 use crate::settings::Settings;
 use crate::steam::{get_shortcuts_for_user, get_users_images, SteamUsersInfo};
 use crate::steamgriddb::ImageType;
@@ -45,9 +46,12 @@ impl SearchSettings for Settings {
     }
 }
 
+/// Downloads the missing images of the users' shortcuts,
+/// the metadata of the games is by their app id and helps finding the right game on SteamGridDB
 pub async fn download_images_for_users(
     settings: &Settings,
     users: &[SteamUsersInfo],
+    games_metadata: &HashMap<u32, GameMetadata>,
     sender: &mut Option<Sender<SyncProgress>>,
 ) {
     let auth_key = &settings.steamgrid_db.auth_key;
@@ -78,6 +82,7 @@ pub async fn download_images_for_users(
                     known_images,
                     data_folder.as_str(),
                     &shortcut_info.shortcuts,
+                    games_metadata,
                     search,
                     client,
                     settings,
@@ -167,6 +172,7 @@ async fn search_for_images_to_download<T: SearchSettings>(
     known_images: Vec<String>,
     user_data_folder: &str,
     shortcuts: &[ShortcutOwned],
+    games_metadata: &HashMap<u32, GameMetadata>,
     search: &CachedSearch<'_>,
     client: &Client,
     search_settins: &T,
@@ -203,7 +209,10 @@ async fn search_for_images_to_download<T: SearchSettings>(
     let mut search_results = HashMap::new();
     let search_results_a = stream::iter(shortcuts_to_search_for)
         .map(|s| async move {
-            let search_result = search.search(s.app_id, &s.app_name).await;
+            let metadata = games_metadata.get(&s.app_id);
+            let search_result = search
+                .search_with_metadata(s.app_id, &s.app_name, metadata)
+                .await;
             match search_result {
                 Ok(Some(search_result)) => Some((s.app_id, search_result)),
                 _ => None,
@@ -0,0 +1,210 @@
+use regex::Regex;
+use steam_shortcuts_util::shortcut::ShortcutOwned;
+
+use crate::{
+    platforms::ShortcutToImport,
+    steam::{Collection, CollectionRule, PLATFORM_PLACEHOLDER},
+};
+
+/// The collections the rules make from the games of the platforms, collections without games are left out.
+/// `in_steam` gives the app id and name a game has in Steam, they are not the platform's if the game is renamed.
+pub fn get_rule_collections(
+    rules: &[CollectionRule],
+    platform_results: &[(String, Vec<ShortcutToImport>)],
+    in_steam: impl Fn(&ShortcutOwned) -> (u32, String),
+) -> Vec<Collection> {
+    let mut collections: Vec<Collection> = vec![];
+    for rule in rules.iter().filter(|rule| !rule.name.trim().is_empty()) {
+        let name_regex = match compile_name_regex(rule) {
+            Ok(name_regex) => name_regex,
+            Err(err) => {
+                eprintln!(
+                    "Skipping the collection {}, its name filter is not valid: {err}",
+                    rule.name
+                );
+                continue;
+            }
+        };
+        for (platform, shortcuts) in platform_results {
+            if !matches_platform(rule, platform) {
+                continue;
+            }
+            let name = rule.name.replace(PLATFORM_PLACEHOLDER, platform);
+            let game_ids = shortcuts.iter().filter_map(|to_import| {
+                let (app_id, app_name) = in_steam(&to_import.shortcut);
+                matches_game(rule, name_regex.as_ref(), to_import, app_id, &app_name)
+                    .then_some(app_id as usize)
+            });
+            add_games(&mut collections, name, game_ids);
+        }
+    }
+    collections.retain(|collection| !collection.game_ids.is_empty());
+    collections
+}
+
+/// The name filter of a rule, if it has one
+pub fn compile_name_regex(rule: &CollectionRule) -> Result<Option<Regex>, regex::Error> {
+    rule.name_regex
+        .as_deref()
+        .map(str::trim)
+        .filter(|name_regex| !name_regex.is_empty())
+        .map(Regex::new)
+        .transpose()
+}
+
+fn matches_platform(rule: &CollectionRule, platform: &str) -> bool {
+    rule.platforms.is_empty()
+        || rule
+            .platforms
+            .iter()
+            .any(|p| p.trim().eq_ignore_ascii_case(platform))
+}
+
+/// The app ids of a rule can be the ones from before or after the game was renamed
+fn matches_game(
+    rule: &CollectionRule,
+    name_regex: Option<&Regex>,
+    to_import: &ShortcutToImport,
+    app_id: u32,
+    app_name: &str,
+) -> bool {
+    let shortcut = &to_import.shortcut;
+    let genres = to_import.metadata.iter().flat_map(|m| &m.genres);
+    let matches_tags = rule.tags.is_empty()
+        || shortcut.tags.iter().chain(genres).any(|tag| {
+            rule.tags
+                .iter()
+                .any(|wanted| wanted.trim().eq_ignore_ascii_case(tag.trim()))
+        });
+    matches_tags
+        && name_regex.is_none_or(|regex| regex.is_match(app_name))
+        && rule
+            .needs_proton
+            .is_none_or(|needs_proton| needs_proton == to_import.needs_proton)
+        && (rule.app_ids.is_empty()
+            || rule.app_ids.contains(&shortcut.app_id)
+            || rule.app_ids.contains(&app_id))
+}
+
+/// Rules can end up with the same name, fx "Emulation" for several platforms, then their games share the collection
+fn add_games(
+    collections: &mut Vec<Collection>,
+    name: String,
+    game_ids: impl Iterator<Item = usize>,
+) {
+    let Some(collection) = collections.iter_mut().find(|c| c.name == name) else {
+        collections.push(Collection {
+            name,
+            game_ids: game_ids.collect(),
+        });
+        return;
+    };
+    for game_id in game_ids {
+        if !collection.game_ids.contains(&game_id) {
+            collection.game_ids.push(game_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use steam_shortcuts_util::Shortcut;
+
+    use crate::platforms::GameMetadata;
+
+    use super::*;
+
+    fn to_import(name: &str, tags: &[&str], needs_proton: bool) -> ShortcutToImport {
+        let mut shortcut = Shortcut::new("0", name, "/bin/game", "", "", "", "").to_owned();
+        shortcut.tags = tags.iter().map(|t| t.to_string()).collect();
+        ShortcutToImport {
+            shortcut,
+            needs_proton,
+            needs_symlinks: false,
+            metadata: None,
+        }
+    }
+
+    fn games() -> Vec<(String, Vec<ShortcutToImport>)> {
+        let mut portal = to_import("Portal 2", &[], true);
+        portal.metadata = Some(GameMetadata {
+            genres: vec!["Co-op".to_string()],
+            ..Default::default()
+        });
+        vec![
+            (
+                "Lutris".to_string(),
+                vec![
+                    to_import("Super Metroid", &["SNES"], false),
+                    to_import("Doom", &[], false),
+                ],
+            ),
+            ("GOG".to_string(), vec![portal]),
+        ]
+    }
+
+    fn not_renamed(shortcut: &ShortcutOwned) -> (u32, String) {
+        (shortcut.app_id, shortcut.app_name.clone())
+    }
+
+    fn names(collections: &[Collection]) -> Vec<(&str, usize)> {
+        collections
+            .iter()
+            .map(|c| (c.name.as_str(), c.game_ids.len()))
+            .collect()
+    }
+
+    #[test]
+    fn the_built_in_rule_makes_a_collection_for_each_platform() {
+        let collections =
+            get_rule_collections(&[CollectionRule::platforms()], &games(), not_renamed);
+        assert_eq!(vec![("Lutris", 2), ("GOG", 1)], names(&collections));
+    }
+
+    #[test]
+    fn games_are_collected_by_the_filters_of_the_rules() {
+        let rules = vec![
+            CollectionRule {
+                name: "Co-op".to_string(),
+                tags: vec!["co-op".to_string()],
+                ..Default::default()
+            },
+            CollectionRule {
+                name: "Emulation - {platform}".to_string(),
+                platforms: vec!["lutris".to_string()],
+                tags: vec!["SNES".to_string()],
+                ..Default::default()
+            },
+            CollectionRule {
+                name: "Needs Proton".to_string(),
+                needs_proton: Some(true),
+                ..Default::default()
+            },
+            CollectionRule {
+                name: "D games".to_string(),
+                name_regex: Some("^D".to_string()),
+                ..Default::default()
+            },
+            CollectionRule {
+                name: "Nothing".to_string(),
+                app_ids: vec![42],
+                ..Default::default()
+            },
+            CollectionRule {
+                name: "Broken".to_string(),
+                name_regex: Some("(".to_string()),
+                ..Default::default()
+            },
+        ];
+        let collections = get_rule_collections(&rules, &games(), not_renamed);
+        assert_eq!(
+            vec![
+                ("Co-op", 1),
+                ("Emulation - Lutris", 1),
+                ("Needs Proton", 1),
+                ("D games", 1)
+            ],
+            names(&collections)
+        );
+    }
+}
@@ -1,7 +1,10 @@
 #[cfg(target_family = "unix")]
 pub mod symlinks;
+mod collection_rules;
 mod synchronization;
 
+pub use collection_rules::*;
+
 pub use synchronization::download_images;
 
 pub use synchronization::IsBoilRShortcut;
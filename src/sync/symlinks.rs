@@ -1,22 +1,25 @@
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
 
 use steam_shortcuts_util::{shortcut::ShortcutOwned, Shortcut};
 
 use crate::config::get_boilr_links_path;
+use crate::platforms::ShortcutToImport;
+use crate::steam::{get_shortcuts_for_user, SteamUsersInfo};
+use crate::sync::IsBoilRShortcut;
 
 pub fn create_sym_links(shortcut: &ShortcutOwned) -> ShortcutOwned {
     let links_folder = get_boilr_links_path();
 
-    let target_link = links_folder.join(format!("t{}", shortcut.app_id));
-    let workdir_link = links_folder.join(format!("w{}", shortcut.app_id));
+    let target_link = links_folder.join(LinkKind::Target.file_name(shortcut.app_id));
+    let workdir_link = links_folder.join(LinkKind::WorkDir.file_name(shortcut.app_id));
 
-    let target_original = Path::new(&shortcut.exe);
-    let workdir_original = Path::new(&shortcut.start_dir);
+    let target_original = LinkKind::Target.original(shortcut);
+    let workdir_original = LinkKind::WorkDir.original(shortcut);
 
-    use std::os::unix::fs::symlink;
-    // If the links exsists, then they must point towards what is needed, otherwise they would have a different app id
-    let target_ok = target_link.exists() || symlink(target_original, &target_link).is_ok();
-    let workdir_ok = workdir_link.exists() || symlink(workdir_original, &workdir_link).is_ok();
+    let target_ok = ensure_link(&target_link, target_original).is_ok();
+    let workdir_ok = ensure_link(&workdir_link, workdir_original).is_ok();
     match (target_ok, workdir_ok) {
         (true, true) => {
             let exe = target_link.to_string_lossy().to_string();
@@ -59,3 +62,226 @@ pub fn ensure_links_folder_created(name: &str) {
         }
     }
 }
+
+/// The two links made for a shortcut, "t<app_id>" for the exe and "w<app_id>" for the working directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LinkKind {
+    Target,
+    WorkDir,
+}
+
+impl LinkKind {
+    fn file_name(&self, app_id: u32) -> String {
+        match self {
+            LinkKind::Target => format!("t{app_id}"),
+            LinkKind::WorkDir => format!("w{app_id}"),
+        }
+    }
+
+    fn original<'a>(&self, shortcut: &'a ShortcutOwned) -> &'a Path {
+        match self {
+            LinkKind::Target => Path::new(&shortcut.exe),
+            LinkKind::WorkDir => Path::new(&shortcut.start_dir),
+        }
+    }
+
+    /// The kind and app id of a link, from its file name
+    fn parse(file_name: &str) -> Option<(LinkKind, u32)> {
+        let kind = match file_name.get(..1)? {
+            "t" => LinkKind::Target,
+            "w" => LinkKind::WorkDir,
+            _ => return None,
+        };
+        let app_id = file_name.get(1..)?.parse().ok()?;
+        Some((kind, app_id))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum LinkChange {
+    Unchanged,
+    Created,
+    Repaired,
+}
+
+/// Makes sure the link points to the original, a link that points somewhere else is replaced
+fn ensure_link(link: &Path, original: &Path) -> std::io::Result<LinkChange> {
+    //symlink_metadata does not follow the link, so it also finds links whose target is gone
+    if link.symlink_metadata().is_err() {
+        symlink(original, link)?;
+        return Ok(LinkChange::Created);
+    }
+    if std::fs::read_link(link).is_ok_and(|current| current == original) {
+        return Ok(LinkChange::Unchanged);
+    }
+    std::fs::remove_file(link)?;
+    symlink(original, link)?;
+    Ok(LinkChange::Repaired)
+}
+
+/// What the maintenance of the links folder did
+#[derive(Debug, Default)]
+pub struct LinksReport {
+    pub removed: Vec<PathBuf>,
+    pub repaired: Vec<PathBuf>,
+    pub errors: Vec<String>,
+}
+
+impl LinksReport {
+    /// True if nothing was done and nothing went wrong
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.repaired.is_empty() && self.errors.is_empty()
+    }
+}
+
+impl std::fmt::Display for LinksReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Removed {} and repaired {} links",
+            self.removed.len(),
+            self.repaired.len()
+        )?;
+        for error in &self.errors {
+            write!(f, "\n  {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Cleans up the links folder after a sync.
+/// Links of games that are no longer BoilR shortcuts in Steam, or whose target is gone, are removed,
+/// and links of the imported games that point to the wrong place are made again.
+pub fn maintain_links_folder(
+    shortcuts: &[(String, Vec<ShortcutToImport>)],
+    users: &[SteamUsersInfo],
+) -> LinksReport {
+    let mut wanted = HashMap::new();
+    let mut keep = HashSet::new();
+    for to_import in shortcuts.iter().flat_map(|(_, s)| s) {
+        keep.insert(to_import.shortcut.app_id);
+        if to_import.needs_symlinks {
+            wanted.insert(to_import.shortcut.app_id, &to_import.shortcut);
+        }
+    }
+    for user in users {
+        if let Ok(shortcut_info) = get_shortcuts_for_user(user) {
+            keep.extend(
+                shortcut_info
+                    .shortcuts
+                    .iter()
+                    .filter(|s| s.is_boilr_shortcut())
+                    .map(|s| s.app_id),
+            );
+        }
+    }
+    maintain_links(&get_boilr_links_path(), &wanted, &keep)
+}
+
+fn maintain_links(
+    links_folder: &Path,
+    wanted: &HashMap<u32, &ShortcutOwned>,
+    keep: &HashSet<u32>,
+) -> LinksReport {
+    let mut report = LinksReport::default();
+    let Ok(entries) = links_folder.read_dir() else {
+        return report;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_link = entry.file_type().is_ok_and(|t| t.is_symlink());
+        let parsed = entry.file_name().to_str().and_then(LinkKind::parse);
+        let Some((kind, app_id)) = parsed.filter(|_| is_link) else {
+            //Only touch the links BoilR made
+            continue;
+        };
+        if let Some(shortcut) = wanted.get(&app_id) {
+            match ensure_link(&path, kind.original(shortcut)) {
+                Ok(LinkChange::Repaired) => report.repaired.push(path),
+                Ok(_) => {}
+                Err(err) => report
+                    .errors
+                    .push(format!("Could not repair {path:?}: {err}")),
+            }
+        } else if !keep.contains(&app_id) || !path.exists() {
+            match std::fs::remove_file(&path) {
+                Ok(()) => report.removed.push(path),
+                Err(err) => report
+                    .errors
+                    .push(format!("Could not remove {path:?}: {err}")),
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn shortcut(name: &str, exe: &Path, start_dir: &Path) -> ShortcutOwned {
+        Shortcut::new(
+            "0",
+            name,
+            &exe.to_string_lossy(),
+            &start_dir.to_string_lossy(),
+            "",
+            "",
+            "",
+        )
+        .to_owned()
+    }
+
+    #[test]
+    fn parses_link_names() {
+        assert_eq!(Some((LinkKind::Target, 42)), LinkKind::parse("t42"));
+        assert_eq!(Some((LinkKind::WorkDir, 7)), LinkKind::parse("w7"));
+        assert_eq!(None, LinkKind::parse("tunic"));
+        assert_eq!(None, LinkKind::parse(""));
+    }
+
+    #[test]
+    fn dead_and_moved_links_are_cleaned_up() {
+        let folder = std::env::temp_dir().join(format!("boilr-links-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        let links = folder.join("links");
+        let game = folder.join("game");
+        std::fs::create_dir_all(&links).unwrap();
+        std::fs::create_dir_all(&game).unwrap();
+        let exe = game.join("game.exe");
+        std::fs::write(&exe, "MZ").unwrap();
+
+        let moved = shortcut("Moved", &exe, &game);
+        //Points to where the game used to be
+        symlink(
+            folder.join("old/game.exe"),
+            links.join(format!("t{}", moved.app_id)),
+        )
+        .unwrap();
+        symlink(&game, links.join(format!("w{}", moved.app_id))).unwrap();
+        //A game that was uninstalled and is no longer in Steam
+        symlink(folder.join("gone.exe"), links.join("t1")).unwrap();
+        //A BoilR shortcut from a platform that did not need links this time
+        symlink(&exe, links.join("t2")).unwrap();
+        std::fs::write(links.join("t3"), "not a link").unwrap();
+
+        let mut wanted = HashMap::new();
+        wanted.insert(moved.app_id, &moved);
+        let keep = HashSet::from([moved.app_id, 2]);
+        let report = maintain_links(&links, &wanted, &keep);
+
+        assert!(report.errors.is_empty());
+        assert_eq!(vec![links.join("t1")], report.removed);
+        assert_eq!(
+            vec![links.join(format!("t{}", moved.app_id))],
+            report.repaired
+        );
+        assert_eq!(
+            exe,
+            std::fs::read_link(links.join(format!("t{}", moved.app_id))).unwrap()
+        );
+        assert!(links.join("t2").exists());
+        assert!(links.join("t3").exists());
+    }
+}
@@ -4,17 +4,20 @@ use steam_shortcuts_util::{
 };
 use tokio::sync::watch::Sender;
 
+use super::get_rule_collections;
 use crate::{
-    platforms::{GamesPlatform, ShortcutToImport},
+    platforms::{get_shortcuts_cached, GameMetadata, GamesPlatform, ShortcutToImport},
     settings::Settings,
     steam::{
-        get_shortcuts_for_user, get_shortcuts_paths, write_collections, Collection, ShortcutInfo,
-        SteamUsersInfo,
+        get_shortcuts_for_user, get_shortcuts_paths, get_users_images, get_users_to_sync,
+        is_steam_running, keep_shortcut_flags, write_controller_templates,
+        write_or_defer_collections, Collection, CollectionRule, ControllerTemplate, ShortcutFlags,
+        ShortcutInfo, SteamInstallation, SteamStopProgress, SteamUsersInfo,
     },
     steamgriddb::{download_images_for_users, ImageType},
 };
 
-use std::{collections::HashMap, error::Error};
+use std::collections::HashMap;
 
 use std::{fs::File, io::Write, path::Path};
 
@@ -22,11 +25,15 @@ pub const BOILR_TAG: &str = "boilr";
 
 pub enum SyncProgress {
     NotStarted,
+    StoppingSteam(SteamStopProgress),
     Starting,
     FoundGames { games_found: usize },
     FindingImages,
     DownloadingImages { to_download: usize },
     Done,
+    /// Done, and links in the links folder were removed or repaired, the report tells what happened
+    #[cfg(target_family = "unix")]
+    DoneWithLinksReport { report: String },
     /// Error occurred during sync - contains user-friendly error message
     Error { message: String },
 }
@@ -53,43 +60,35 @@ pub fn disconnect_shortcut(settings: &Settings, app_id: u32) -> Result<(), Strin
     Ok(())
 }
 
+/// The platforms are found in platform_code_names by the name of their collection
+#[allow(clippy::too_many_arguments)]
 pub fn sync_shortcuts(
     settings: &Settings,
-    platform_shortcuts: &[(String, Vec<ShortcutOwned>)],
+    installation: &SteamInstallation,
+    platform_shortcuts: &[(String, Vec<ShortcutToImport>)],
     sender: &mut Option<Sender<SyncProgress>>,
-    renames: &HashMap<u32, String>,
+    renames: &Renames,
+    platform_code_names: &HashMap<String, String>,
+    shortcut_flags: &HashMap<u32, ShortcutFlags>,
+    controller_templates: &HashMap<u32, ControllerTemplate>,
 ) -> eyre::Result<Vec<SteamUsersInfo>> {
-    let mut userinfo_shortcuts = get_shortcuts_paths(&settings.steam)?;
-    let mut all_shortcuts: Vec<ShortcutOwned> = platform_shortcuts
-        .iter()
-        .flat_map(|s| s.1.clone())
-        .filter(|s| !settings.blacklisted_games.contains(&s.app_id))
-        .collect();
-    for shortcut in &mut all_shortcuts {
-        shortcut.dev_kit_game_id = BOILR_TAG.to_string();
-    }
+    let mut userinfo_shortcuts = installation.users_to_sync(&settings.steam)?;
     if let Some(sender) = &sender {
-        let _ = sender.send(SyncProgress::FoundGames {
-            games_found: all_shortcuts.len(),
-        });
-    }
-    for shortcut in &mut all_shortcuts {
-        if let Some(rename) = renames.get(&shortcut.app_id) {
-            shortcut.app_name = rename.clone();
-            let new_shortcut = Shortcut::new(
-                "0",
-                shortcut.app_name.as_str(),
-                &shortcut.exe,
-                "",
-                "",
-                "",
-                "",
-            );
-            shortcut.app_id = calculate_app_id_for_shortcut(&new_shortcut);
-        }
-        println!("Appid: {} name: {}", shortcut.app_id, shortcut.app_name);
+        let games_found = platform_shortcuts
+            .iter()
+            .flat_map(|(_, shortcuts)| shortcuts)
+            .filter(|s| !settings.blacklisted_games.contains(&s.shortcut.app_id))
+            .count();
+        let _ = sender.send(SyncProgress::FoundGames { games_found });
     }
     println!("Found {} user(s)", userinfo_shortcuts.len());
+    //Steam writes the controller configs of the users again when it exits, so templates set now would be lost
+    let steam_running = is_steam_running();
+    if steam_running {
+        println!(
+            "Steam is running, the controller templates are set by an import with Steam stopped"
+        );
+    }
     let ok_shorcuts = userinfo_shortcuts.iter_mut().filter_map(|user|{
         let shortcut_info = get_shortcuts_for_user(user).ok();
         shortcut_info.map(|shortcut_info| {
@@ -104,10 +103,29 @@ pub fn sync_shortcuts(
             user.user_id
         );
 
+        let mut user_platforms = platforms_for_user(
+            settings,
+            &user.user_id,
+            platform_shortcuts,
+            platform_code_names,
+        );
+        hide_flagged_games(&mut user_platforms, shortcut_flags);
+        let mut user_shortcuts = shortcuts_for_user(
+            &user.user_id,
+            &user_platforms,
+            renames,
+            &shortcut_info.shortcuts,
+            shortcut_flags,
+        );
+
         remove_old_shortcuts(&mut shortcut_info);
-        remove_shortcuts_with_same_appid(&mut shortcut_info, &all_shortcuts);
+        //A hidden game must not replace a shortcut the user made for it themselves, fx one that was disconnected
+        user_shortcuts.retain(|s| {
+            !s.is_hidden || !shortcut_info.shortcuts.iter().any(|o| o.app_id == s.app_id)
+        });
+        remove_shortcuts_with_same_appid(&mut shortcut_info, &user_shortcuts);
 
-        shortcut_info.shortcuts.extend(all_shortcuts.clone());
+        shortcut_info.shortcuts.extend(user_shortcuts);
 
         if let Err(e) = save_shortcuts(&shortcut_info.shortcuts, Path::new(&shortcut_info.path)) {
             eprintln!("Failed to save shortcuts for user {}: {}", user.user_id, e);
@@ -117,30 +135,261 @@ pub fn sync_shortcuts(
             // Continue with other users even if one fails
         }
 
-        if settings.steam.create_collections {
-            match write_shortcut_collections(&user.user_id, platform_shortcuts) {
-                Ok(_) => (),
-                Err(_e) => eprintln!("Could not write collections, make sure steam is shut down"),
+        if !steam_running {
+            let user_templates = controller_templates_for_user(
+                &user.user_id,
+                &user_platforms,
+                renames,
+                controller_templates,
+            );
+            if let Err(err) =
+                write_controller_templates(installation, &user.user_id, &user_templates)
+            {
+                eprintln!(
+                    "Could not set the controller templates for user {}: {err}",
+                    user.user_id
+                );
             }
         }
 
+        if settings.steam.create_collections {
+            let collections = get_shortcut_collections(
+                &user.user_id,
+                &user_platforms,
+                &settings.steam.collection_rules,
+                renames,
+            );
+            write_shortcut_collections(installation, &user.user_id, &collections);
+        }
+
         let duration = start_time.elapsed();
         println!("Finished synchronizing games in: {duration:?}");
     }
     Ok(userinfo_shortcuts)
 }
 
+/// The names chosen for games, for everyone and for single Steam users
+#[derive(Debug, Default, Clone)]
+pub struct Renames {
+    /// By the app id the game has before it is renamed
+    pub all_users: HashMap<u32, String>,
+    /// By the user id, these win over the names for everyone
+    pub users: HashMap<String, HashMap<u32, String>>,
+}
+
+impl Renames {
+    pub fn get(&self, user_id: &str, app_id: u32) -> Option<&String> {
+        self.users
+            .get(user_id)
+            .and_then(|renames| renames.get(&app_id))
+            .or_else(|| self.all_users.get(&app_id))
+    }
+}
+
+/// The platforms and games that are synced for the user, left out games are hidden if the settings say so
+fn platforms_for_user(
+    settings: &Settings,
+    user_id: &str,
+    platform_shortcuts: &[(String, Vec<ShortcutToImport>)],
+    platform_code_names: &HashMap<String, String>,
+) -> Vec<(String, Vec<ShortcutToImport>)> {
+    platform_shortcuts
+        .iter()
+        .filter(|(name, _)| {
+            platform_code_names
+                .get(name)
+                .is_none_or(|code_name| settings.is_platform_enabled_for(user_id, code_name))
+        })
+        .map(|(name, shortcuts)| {
+            let shortcuts = shortcuts
+                .iter()
+                .filter_map(|s| {
+                    if !settings.is_blacklisted_for(user_id, s.shortcut.app_id) {
+                        Some(s.clone())
+                    } else if settings.steam.hide_blacklisted_games {
+                        let mut hidden = s.clone();
+                        hidden.shortcut.is_hidden = true;
+                        Some(hidden)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            (name.clone(), shortcuts)
+        })
+        .collect()
+}
+
+/// Hides the games that their flags hide, so they are also left out of the collections
+fn hide_flagged_games(
+    user_platforms: &mut [(String, Vec<ShortcutToImport>)],
+    shortcut_flags: &HashMap<u32, ShortcutFlags>,
+) {
+    for to_import in user_platforms.iter_mut().flat_map(|(_, s)| s) {
+        let flags = shortcut_flags.get(&to_import.shortcut.app_id);
+        if flags.is_some_and(|flags| flags.is_hidden == Some(true)) {
+            to_import.shortcut.is_hidden = true;
+        }
+    }
+}
+
+/// The shortcuts as they are written for the user, with the names the user has for them.
+/// The flags that are not chosen in BoilR are kept as the shortcuts had them in Steam.
+fn shortcuts_for_user(
+    user_id: &str,
+    user_platforms: &[(String, Vec<ShortcutToImport>)],
+    renames: &Renames,
+    old_shortcuts: &[ShortcutOwned],
+    shortcut_flags: &HashMap<u32, ShortcutFlags>,
+) -> Vec<ShortcutOwned> {
+    let mut shortcuts: Vec<ShortcutOwned> = user_platforms
+        .iter()
+        .flat_map(|(_, shortcuts)| shortcuts.iter().map(|s| s.shortcut.clone()))
+        .collect();
+    for shortcut in &mut shortcuts {
+        //Games are hidden here when they are left out for the user, that wins over their flags
+        let hidden = shortcut.is_hidden;
+        shortcut.dev_kit_game_id = BOILR_TAG.to_string();
+        //The flags are chosen by the app id the game has before it is renamed
+        let flags = shortcut_flags.get(&shortcut.app_id);
+        if let Some(rename) = renames.get(user_id, shortcut.app_id) {
+            shortcut.app_id = renamed_app_id(shortcut, rename);
+            shortcut.app_name = rename.clone();
+        }
+        let old = old_shortcuts
+            .iter()
+            .find(|old| old.app_id == shortcut.app_id && old.is_boilr_shortcut());
+        if let Some(old) = old {
+            keep_shortcut_flags(old, shortcut);
+        }
+        if let Some(flags) = flags {
+            flags.apply(shortcut);
+        }
+        shortcut.is_hidden |= hidden;
+        println!("Appid: {} name: {}", shortcut.app_id, shortcut.app_name);
+    }
+    shortcuts
+}
+
+/// The controller templates for the games of a user, by the name the game has in Steam.
+/// Games without a chosen template are there too, so the template BoilR set before can be taken away.
+fn controller_templates_for_user(
+    user_id: &str,
+    user_platforms: &[(String, Vec<ShortcutToImport>)],
+    renames: &Renames,
+    controller_templates: &HashMap<u32, ControllerTemplate>,
+) -> Vec<(String, Option<ControllerTemplate>)> {
+    user_platforms
+        .iter()
+        .flat_map(|(_, shortcuts)| shortcuts)
+        .map(|to_import| {
+            let shortcut = &to_import.shortcut;
+            //The templates are chosen by the app id the game has before it is renamed
+            let template = controller_templates.get(&shortcut.app_id).cloned();
+            let name = renames
+                .get(user_id, shortcut.app_id)
+                .unwrap_or(&shortcut.app_name);
+            (name.clone(), template)
+        })
+        .collect()
+}
+
+/// What the platforms know about the games, by the app id the games have in Steam.
+/// Renamed games are found by both their old and new app ids.
+pub fn games_metadata(
+    platform_shortcuts: &[(String, Vec<ShortcutToImport>)],
+    renames: &Renames,
+) -> HashMap<u32, GameMetadata> {
+    let mut games_metadata = HashMap::new();
+    for to_import in platform_shortcuts.iter().flat_map(|(_, shortcuts)| shortcuts) {
+        let Some(metadata) = &to_import.metadata else {
+            continue;
+        };
+        let shortcut = &to_import.shortcut;
+        let new_names = renames.all_users.get(&shortcut.app_id).into_iter().chain(
+            renames
+                .users
+                .values()
+                .filter_map(|user_renames| user_renames.get(&shortcut.app_id)),
+        );
+        for new_name in new_names {
+            games_metadata.insert(renamed_app_id(shortcut, new_name), metadata.clone());
+        }
+        games_metadata.insert(shortcut.app_id, metadata.clone());
+    }
+    games_metadata
+}
+
+/// The app id a shortcut gets when it is renamed
+fn renamed_app_id(shortcut: &ShortcutOwned, new_name: &str) -> u32 {
+    let new_shortcut = Shortcut::new("0", new_name, &shortcut.exe, "", "", "", "");
+    calculate_app_id_for_shortcut(&new_shortcut)
+}
+
+/// Gives the games that did not get a grid image (fx from SteamGridDB) the cover art their platform has
+pub fn copy_platform_covers(
+    settings: &Settings,
+    users: &[SteamUsersInfo],
+    platform_shortcuts: &[(String, Vec<ShortcutToImport>)],
+    platform_code_names: &HashMap<String, String>,
+    renames: &Renames,
+) {
+    let image_type = ImageType::Grid;
+    for user in users {
+        let user_platforms = platforms_for_user(
+            settings,
+            &user.user_id,
+            platform_shortcuts,
+            platform_code_names,
+        );
+        let known_images = get_users_images(&user.steam_user_data_folder).unwrap_or_default();
+        let grid_folder = Path::new(&user.steam_user_data_folder)
+            .join("config")
+            .join("grid");
+        for to_import in user_platforms.iter().flat_map(|(_, s)| s) {
+            let shortcut = &to_import.shortcut;
+            let cover = to_import
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.cover_path.as_ref())
+                .map(Path::new);
+            let Some(cover) = cover.filter(|cover| cover.exists()) else {
+                continue;
+            };
+            let app_id = match renames.get(&user.user_id, shortcut.app_id) {
+                Some(rename) => renamed_app_id(shortcut, rename),
+                None => shortcut.app_id,
+            };
+            if known_images.contains(&image_type.file_name_no_extension(app_id)) {
+                continue;
+            }
+            let extension = cover
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or("png");
+            let target = grid_folder.join(image_type.file_name(app_id, extension));
+            if let Err(err) = std::fs::copy(cover, &target) {
+                eprintln!(
+                    "Could not copy the cover of {} to {target:?}: {err}",
+                    shortcut.app_name
+                );
+            }
+        }
+    }
+}
+
 pub async fn download_images(
     settings: &Settings,
     userinfo_shortcuts: &[SteamUsersInfo],
+    games_metadata: &HashMap<u32, GameMetadata>,
     sender: &mut Option<Sender<SyncProgress>>,
 ) {
     if settings.steamgrid_db.enabled {
-        download_images_for_users(settings, userinfo_shortcuts,  sender).await;
+        download_images_for_users(settings, userinfo_shortcuts, games_metadata, sender).await;
         if settings.steamgrid_db.prefer_animated{
             let mut set = settings.clone();
             set.steamgrid_db.prefer_animated = false;
-            download_images_for_users(&set, userinfo_shortcuts,  sender).await;
+            download_images_for_users(&set, userinfo_shortcuts, games_metadata, sender).await;
         }
     }
 }
@@ -173,7 +422,7 @@ fn remove_old_shortcuts(shortcut_info: &mut ShortcutInfo) {
 }
 
 pub fn fix_all_shortcut_icons(settings: &Settings) -> eyre::Result<()> {
-    let mut userinfo_shortcuts = get_shortcuts_paths(&settings.steam)
+    let mut userinfo_shortcuts = get_users_to_sync(&settings.steam)
         .map_err(|e| eyre::format_err!("Could not find steam shortcuts; {e}"))?;
     for user in userinfo_shortcuts.iter_mut() {
         let shortcut_info = get_shortcuts_for_user(user);
@@ -224,29 +473,49 @@ fn fix_shortcut_icons(
     has_changes
 }
 
-fn write_shortcut_collections<S: AsRef<str>>(
-    steam_id: S,
-    platform_results: &[(String, Vec<ShortcutOwned>)],
-) -> Result<(), Box<dyn Error>> {
-    let mut collections = vec![];
-
-    for (name, shortcuts) in platform_results {
-        let game_ids = shortcuts.iter().map(|s| s.app_id as usize).collect();
-        collections.push(Collection {
-            name: name.clone(),
-            game_ids,
-        });
-    }
+/// The collections of a user, with the app ids and names the user's renamed games have in Steam
+fn get_shortcut_collections(
+    user_id: &str,
+    platform_results: &[(String, Vec<ShortcutToImport>)],
+    rules: &[CollectionRule],
+    renames: &Renames,
+) -> Vec<Collection> {
+    //Games that are hidden in Steam are left out of the collections
+    let visible_results: Vec<(String, Vec<ShortcutToImport>)> = platform_results
+        .iter()
+        .map(|(name, shortcuts)| {
+            let visible = shortcuts
+                .iter()
+                .filter(|s| !s.shortcut.is_hidden)
+                .cloned()
+                .collect();
+            (name.clone(), visible)
+        })
+        .collect();
+    get_rule_collections(rules, &visible_results, |shortcut| {
+        match renames.get(user_id, shortcut.app_id) {
+            Some(rename) => (renamed_app_id(shortcut, rename), rename.clone()),
+            None => (shortcut.app_id, shortcut.app_name.clone()),
+        }
+    })
+}
+
+/// Writes the collections, if Steam holds the lock of its database they are kept until it lets go
+fn write_shortcut_collections(
+    installation: &SteamInstallation,
+    steam_id: &str,
+    collections: &[Collection],
+) {
     println!("Writing {} collections ", collections.len());
-    write_collections(steam_id.as_ref(), &collections)?;
-    Ok(())
+    write_or_defer_collections(installation, steam_id, collections);
 }
 
 pub fn get_platform_shortcuts(
     platform: Box<dyn GamesPlatform>,
+    use_cache: bool,
 ) -> eyre::Result<Vec<ShortcutToImport>> {
     if platform.enabled() {
-        platform.get_shortcut_info()
+        get_shortcuts_cached(platform.as_ref(), use_cache)
     } else {
         Ok(vec![])
     }
@@ -281,3 +550,193 @@ fn save_shortcuts(shortcuts: &[ShortcutOwned], path: &Path) -> Result<(), String
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use crate::platforms::GameMetadata;
+
+    use super::*;
+
+    fn to_import(name: &str, metadata: GameMetadata) -> ShortcutToImport {
+        ShortcutToImport {
+            shortcut: Shortcut::new("0", name, "/bin/game", "", "", "", "").to_owned(),
+            needs_proton: false,
+            needs_symlinks: false,
+            metadata: Some(metadata),
+        }
+    }
+
+    #[test]
+    fn users_get_their_own_platforms_blacklist_and_names() {
+        let doom = to_import("Doom", GameMetadata::default());
+        let quake = to_import("Quake", GameMetadata::default());
+        let witcher = to_import("The Witcher", GameMetadata::default());
+        let games = vec![
+            ("Lutris".to_string(), vec![doom.clone(), quake.clone()]),
+            ("GOG".to_string(), vec![witcher]),
+        ];
+        let mut settings = Settings::default();
+        settings.steam_users.insert(
+            "2".to_string(),
+            crate::settings::SteamUserSettings {
+                disabled_platforms: vec!["gog".to_string()],
+                blacklisted_games: vec![quake.shortcut.app_id],
+            },
+        );
+        let mut renames = Renames::default();
+        renames
+            .all_users
+            .insert(doom.shortcut.app_id, "Doom (1993)".to_string());
+        renames.users.insert(
+            "2".to_string(),
+            HashMap::from([(doom.shortcut.app_id, "Ultimate Doom".to_string())]),
+        );
+
+        let code_names = HashMap::from([
+            ("Lutris".to_string(), "lutris".to_string()),
+            ("GOG".to_string(), "gog".to_string()),
+        ]);
+        let names_for = |user_id: &str| -> Vec<String> {
+            let platforms = platforms_for_user(&settings, user_id, &games, &code_names);
+            shortcuts_for_user(user_id, &platforms, &renames, &[], &HashMap::new())
+                .into_iter()
+                .map(|s| s.app_name)
+                .collect()
+        };
+        assert_eq!(vec!["Doom (1993)", "Quake", "The Witcher"], names_for("1"));
+        assert_eq!(vec!["Ultimate Doom"], names_for("2"));
+    }
+
+    #[test]
+    fn left_out_games_stay_hidden_and_hidden_games_get_no_collections() {
+        let doom = to_import("Doom", GameMetadata::default());
+        let quake = to_import("Quake", GameMetadata::default());
+        let witcher = to_import("The Witcher", GameMetadata::default());
+        let games = vec![(
+            "Lutris".to_string(),
+            vec![doom.clone(), quake.clone(), witcher.clone()],
+        )];
+        let mut settings = Settings {
+            blacklisted_games: vec![doom.shortcut.app_id],
+            ..Default::default()
+        };
+        settings.steam.hide_blacklisted_games = true;
+        let shown = ShortcutFlags {
+            is_hidden: Some(false),
+            ..Default::default()
+        };
+        let hidden = ShortcutFlags {
+            is_hidden: Some(true),
+            ..Default::default()
+        };
+        let flags = HashMap::from([
+            (doom.shortcut.app_id, shown),
+            (quake.shortcut.app_id, hidden),
+        ]);
+
+        let mut platforms = platforms_for_user(&settings, "1", &games, &HashMap::new());
+        hide_flagged_games(&mut platforms, &flags);
+        let shortcuts = shortcuts_for_user("1", &platforms, &Renames::default(), &[], &flags);
+        let hidden_names: Vec<&str> = shortcuts
+            .iter()
+            .filter(|s| s.is_hidden)
+            .map(|s| s.app_name.as_str())
+            .collect();
+        assert_eq!(vec!["Doom", "Quake"], hidden_names);
+
+        let collections = get_shortcut_collections(
+            "1",
+            &platforms,
+            &[CollectionRule::platforms()],
+            &Renames::default(),
+        );
+        let game_ids: Vec<usize> = collections
+            .iter()
+            .flat_map(|c| c.game_ids.iter().copied())
+            .collect();
+        assert_eq!(vec![witcher.shortcut.app_id as usize], game_ids);
+    }
+
+    #[test]
+    fn collections_have_the_names_and_app_ids_of_renamed_games() {
+        let doom = to_import("Doom", GameMetadata::default());
+        let quake = to_import("Quake", GameMetadata::default());
+        let games = vec![("Lutris".to_string(), vec![doom.clone(), quake.clone()])];
+        let mut renames = Renames::default();
+        renames.users.insert(
+            "1".to_string(),
+            HashMap::from([(doom.shortcut.app_id, "Ultimate Doom".to_string())]),
+        );
+        let rules = [
+            CollectionRule::platforms(),
+            CollectionRule {
+                name: "Ultimate".to_string(),
+                name_regex: Some("^Ultimate".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let collections = get_shortcut_collections("1", &games, &rules, &renames);
+        let renamed_id = renamed_app_id(&doom.shortcut, "Ultimate Doom") as usize;
+        let collection_ids: Vec<(&str, Vec<usize>)> = collections
+            .iter()
+            .map(|c| (c.name.as_str(), c.game_ids.clone()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Lutris", vec![renamed_id, quake.shortcut.app_id as usize]),
+                ("Ultimate", vec![renamed_id]),
+            ],
+            collection_ids
+        );
+
+        let not_renamed = get_shortcut_collections("2", &games, &rules, &renames);
+        assert_eq!(1, not_renamed.len());
+    }
+
+    #[test]
+    fn covers_are_only_used_for_missing_grid_images() {
+        let folder = std::env::temp_dir().join(format!("boilr-covers-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        let user = SteamUsersInfo {
+            steam_user_data_folder: folder.to_string_lossy().to_string(),
+            shortcut_path: None,
+            user_id: "1".to_string(),
+            ..Default::default()
+        };
+        let with_cover = GameMetadata {
+            cover_path: Some("src/testdata/smallpng.png".to_string()),
+            ..Default::default()
+        };
+        let game = to_import("Doom", with_cover.clone());
+        let renamed = to_import("Quake", with_cover);
+        let renamed_id = renamed.shortcut.app_id;
+        let mut renames = Renames::default();
+        renames
+            .all_users
+            .insert(renamed_id, "Quake (modded)".to_string());
+        let grid_folder = folder.join("config").join("grid");
+        std::fs::create_dir_all(&grid_folder).unwrap();
+        let existing = grid_folder.join(ImageType::Grid.file_name(game.shortcut.app_id, "jpg"));
+        std::fs::write(&existing, "steamgriddb").unwrap();
+
+        let games = vec![("Lutris".to_string(), vec![game, renamed.clone()])];
+        copy_platform_covers(
+            &Settings::default(),
+            &[user],
+            &games,
+            &HashMap::new(),
+            &renames,
+        );
+
+        assert_eq!("steamgriddb", std::fs::read_to_string(&existing).unwrap());
+        let new_id = renamed_app_id(&renamed.shortcut, "Quake (modded)");
+        assert!(grid_folder
+            .join(ImageType::Grid.file_name(new_id, "png"))
+            .exists());
+        assert!(!grid_folder
+            .join(ImageType::Grid.file_name(renamed_id, "png"))
+            .exists());
+    }
+}
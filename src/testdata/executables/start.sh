@@ -0,0 +1,2 @@
+#!/bin/sh
+exec ./game.x86_64 "$@"
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::steam::CompatTool;
+
+/// Picks the compat tool for the key, removing the key from the choices means the default is used.
+/// Returns true if the choice changed.
+pub fn render_compat_tool_select<K: Hash + Eq + Clone>(
+    choices: &mut HashMap<K, String>,
+    key: K,
+    default_label: &str,
+    tools: &[CompatTool],
+    ui: &mut egui::Ui,
+) -> bool {
+    let before = choices.get(&key).cloned();
+    let mut choice = before.clone();
+    let selected_text = match &choice {
+        Some(name) => tools
+            .iter()
+            .find(|tool| &tool.name == name)
+            .map(|tool| tool.display_name.clone())
+            .unwrap_or_else(|| name.clone()),
+        None => default_label.to_string(),
+    };
+    egui::ComboBox::from_id_salt(egui::Id::new("compat_tool_select").with(&key))
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut choice, None, default_label);
+            for tool in tools {
+                ui.selectable_value(&mut choice, Some(tool.name.clone()), &tool.display_name);
+            }
+        });
+    if choice == before {
+        return false;
+    }
+    match choice {
+        Some(name) => choices.insert(key, name),
+        None => choices.remove(&key),
+    };
+    true
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::steam::ControllerTemplate;
+
+/// Picks the controller template for the key, removing the key from the choices means the default is used.
+/// A custom layout gets a field for the path of the file.
+/// Returns true if the choice changed.
+pub fn render_controller_template_select<K: Hash + Eq + Clone>(
+    choices: &mut HashMap<K, ControllerTemplate>,
+    key: K,
+    default_label: &str,
+    ui: &mut egui::Ui,
+) -> bool {
+    let before = choices.get(&key).cloned();
+    let mut choice = before.clone();
+    let selected_text = match &choice {
+        Some(template) => template.display_name().to_string(),
+        None => default_label.to_string(),
+    };
+    let id = egui::Id::new("controller_template_select").with(&key);
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut choice, None, default_label);
+                for template in ControllerTemplate::BUILT_IN {
+                    let name = template.display_name().to_string();
+                    ui.selectable_value(&mut choice, Some(template), name);
+                }
+                let custom = match &before {
+                    Some(ControllerTemplate::Custom(path)) => path.clone(),
+                    _ => String::new(),
+                };
+                ui.selectable_value(
+                    &mut choice,
+                    Some(ControllerTemplate::Custom(custom)),
+                    "Custom layout",
+                );
+            });
+        if let Some(ControllerTemplate::Custom(path)) = &mut choice {
+            ui.add(egui::TextEdit::singleline(path).hint_text("Path of an exported layout"));
+        }
+    });
+    if choice == before {
+        return false;
+    }
+    match choice {
+        Some(template) => choices.insert(key, template),
+        None => choices.remove(&key),
+    };
+    true
+}
@@ -1,3 +1,11 @@
+#[cfg(target_family = "unix")]
+mod compat_tool_select;
+mod controller_template_select;
+mod shortcut_flags_edit;
 mod steam_user_select;
 
-pub use steam_user_select::render_user_select;
\ No newline at end of file
+#[cfg(target_family = "unix")]
+pub use compat_tool_select::render_compat_tool_select;
+pub use controller_template_select::render_controller_template_select;
+pub use shortcut_flags_edit::render_shortcut_flags;
+pub use steam_user_select::{render_user_avatar, render_user_select, user_id_text};
\ No newline at end of file
@@ -0,0 +1,55 @@
+use crate::steam::ShortcutFlags;
+
+/// Lets the user choose the flags of shortcuts, a flag that is not chosen uses the default.
+/// Returns true if a flag changed.
+pub fn render_shortcut_flags(
+    flags: &mut ShortcutFlags,
+    default_label: &str,
+    ui: &mut egui::Ui,
+) -> bool {
+    let before = *flags;
+    let id = ui.id().with("shortcut_flags");
+    egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+        render_flag(&mut flags.is_hidden, "Hidden", default_label, ui);
+        render_flag(
+            &mut flags.allow_desktop_config,
+            "Desktop configuration",
+            default_label,
+            ui,
+        );
+        render_flag(&mut flags.allow_overlay, "Steam overlay", default_label, ui);
+        render_flag(&mut flags.open_vr, "VR", default_label, ui);
+
+        ui.label("Devkit app id");
+        ui.horizontal(|ui| {
+            let mut override_app_id = flags.devkit_override_app_id.is_some();
+            ui.checkbox(&mut override_app_id, "");
+            match (override_app_id, &mut flags.devkit_override_app_id) {
+                (true, Some(app_id)) => {
+                    ui.add(egui::DragValue::new(app_id));
+                }
+                (true, None) => flags.devkit_override_app_id = Some(0),
+                (false, _) => flags.devkit_override_app_id = None,
+            }
+        });
+        ui.end_row();
+    });
+    before != *flags
+}
+
+fn render_flag(flag: &mut Option<bool>, label: &str, default_label: &str, ui: &mut egui::Ui) {
+    ui.label(label);
+    let selected_text = match flag {
+        Some(true) => "On",
+        Some(false) => "Off",
+        None => default_label,
+    };
+    egui::ComboBox::from_id_salt(ui.id().with(label))
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(flag, None, default_label);
+            ui.selectable_value(flag, Some(true), "On");
+            ui.selectable_value(flag, Some(false), "Off");
+        });
+    ui.end_row();
+}
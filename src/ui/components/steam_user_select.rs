@@ -1,25 +1,33 @@
 use crate::steam::SteamUsersInfo;
 
+const AVATAR_SIZE: f32 = 20.0;
+
 pub fn render_user_select<'a>(
     steam_user: Option<&'a SteamUsersInfo>,
     steam_users: &'a [SteamUsersInfo],
     ui: &mut egui::Ui,
 ) -> Option<&'a SteamUsersInfo> {
     if let Some(mut selected_user) = steam_user {
-        let id_before = selected_user.user_id.clone();
+        //The same user can be in several Steam installations, so they are told apart by their folder
+        let id_before = selected_user.steam_user_data_folder.clone();
         if steam_users.len() <= 1 {
             return None;
         }
         if !steam_users.is_empty() {
+            render_user_avatar(selected_user, ui);
             let combo_box = egui::ComboBox::new("ImageUserSelect", "")
-                .selected_text(format!("Steam user id: {}", &selected_user.user_id));
-            combo_box.show_ui(ui, |ui| {
-                for user in steam_users {
-                    ui.selectable_value(&mut selected_user, user, &user.user_id);
-                }
-            });
+                .selected_text(selected_user.display_name());
+            combo_box
+                .show_ui(ui, |ui| {
+                    for user in steam_users {
+                        ui.selectable_value(&mut selected_user, user, user.display_name())
+                            .on_hover_text(user_id_text(user));
+                    }
+                })
+                .response
+                .on_hover_text(user_id_text(selected_user));
         }
-        let id_now = selected_user.user_id.clone();
+        let id_now = selected_user.steam_user_data_folder.clone();
         if !id_before.eq(&id_now) {
             Some(selected_user)
         } else {
@@ -29,3 +37,18 @@ pub fn render_user_select<'a>(
         steam_users.first()
     }
 }
+
+/// The avatar Steam cached for the user, nothing if there is none
+pub fn render_user_avatar(user: &SteamUsersInfo, ui: &mut egui::Ui) {
+    if let Some(avatar_path) = &user.avatar_path {
+        // Convert Windows backslashes to forward slashes for file:// URL
+        let avatar_path = avatar_path.replace('\\', "/");
+        let image = egui::Image::new(format!("file:///{avatar_path}"))
+            .fit_to_exact_size(egui::Vec2::splat(AVATAR_SIZE));
+        ui.add(image);
+    }
+}
+
+pub fn user_id_text(user: &SteamUsersInfo) -> String {
+    format!("Steam user id: {}", user.user_id)
+}
@@ -15,11 +15,11 @@ use std::path::Path;
 
 use crate::{
     config::get_thumbnails_folder,
-    steam::get_shortcuts_paths,
-    steam::{get_installed_games, SteamUsersInfo},
+    steam::get_shortcuts_paths_with_profiles,
+    steam::{get_installed_games, SteamInstallation, SteamUsersInfo},
     steamgriddb::{get_image_extension, get_query_type, ImageType},
-    sync::{download_images, SyncProgress},
-    ui::{components::render_user_select, FetchStatus, MyEguiApp},
+    sync::{download_images, games_metadata, Renames, SyncProgress},
+    ui::{components::render_user_select, get_all_games, FetchStatus, MyEguiApp},
 };
 use egui::ScrollArea;
 use futures::executor::block_on;
@@ -121,7 +121,7 @@ impl MyEguiApp {
         if self.image_selected_state.settings_error.is_none()
             && self.image_selected_state.steam_users.is_none()
         {
-            let paths = get_shortcuts_paths(&self.settings.steam);
+            let paths = get_shortcuts_paths_with_profiles(&self.settings.steam);
             match paths {
                 Ok(paths) => self.image_selected_state.steam_users = Some(paths),
                 Err(err) => {
@@ -258,8 +258,13 @@ impl MyEguiApp {
             let mut sender_op = Some(sender);
             let settings = self.settings.clone();
             let users = users.clone();
+            let renames = Renames {
+                all_users: self.rename_map.clone(),
+                users: self.user_rename_map.clone(),
+            };
+            let games_metadata = games_metadata(&get_all_games(&self.games_to_sync), &renames);
             self.rt.spawn_blocking(move || {
-                let task = download_images(&settings, &users, &mut sender_op);
+                let task = download_images(&settings, &users, &games_metadata, &mut sender_op);
                 block_on(task);
                 if let Some(sender_op) = sender_op {
                     let _ = sender_op.send(SyncProgress::Done);
@@ -296,7 +301,10 @@ impl MyEguiApp {
 
     fn handle_set_game_mode(&mut self, game_mode: GameMode) {
         self.image_selected_state.game_mode = game_mode;
-        self.image_selected_state.steam_games = Some(get_installed_games(&self.settings.steam));
+        let steam_games = SteamInstallation::resolve(&self.settings.steam)
+            .map(|installation| get_installed_games(&installation))
+            .unwrap_or_default();
+        self.image_selected_state.steam_games = Some(steam_games);
     }
 
     fn handle_user_selected(&mut self, user: SteamUsersInfo) {
@@ -1,5 +1,7 @@
 mod defines;
 mod ui_backup;
+mod ui_collections;
+mod ui_diagnostics;
 mod ui_disconnect;
 mod ui_import_games;
 mod ui_settings;
@@ -9,6 +11,8 @@ mod components;
 
 pub use defines::*;
 pub use ui_backup::*;
+pub use ui_collections::*;
+pub use ui_diagnostics::*;
 pub use ui_disconnect::*;
 pub use ui_import_games::*;
 pub use ui_settings::*;
@@ -5,7 +5,7 @@ use time::format_description;
 
 use crate::{
     config::get_backups_folder,
-    steam::{get_shortcuts_paths, SteamSettings},
+    steam::{steam_installations, sync_targets, SteamInstallation, SteamSettings},
 };
 
 use super::MyEguiApp;
@@ -63,7 +63,8 @@ impl MyEguiApp {
 
 pub fn restore_backup(steam_settings: &SteamSettings, shortcut_path: &Path) -> bool {
     let file_name = shortcut_path.file_name();
-    let paths = get_shortcuts_paths(steam_settings);
+    let paths = backup_installation(steam_settings, shortcut_path)
+        .and_then(|installation| installation.users());
     if let (Ok(paths), Some(file_name)) = (paths, file_name) {
         for user in paths {
             if let Some(user_shortcut_path) = user.shortcut_path {
@@ -86,11 +87,37 @@ pub fn restore_backup(steam_settings: &SteamSettings, shortcut_path: &Path) -> b
     false
 }
 
+/// The installation a backup was made from, backups from before BoilR supported several installations are not in a sub folder
+fn backup_installation(
+    steam_settings: &SteamSettings,
+    backup_path: &Path,
+) -> eyre::Result<SteamInstallation> {
+    let folder = backup_path.parent().and_then(Path::file_name);
+    let in_sub_folder = backup_path.parent() != Some(get_backups_folder().as_path());
+    match folder {
+        Some(folder) if in_sub_folder => steam_installations(steam_settings)
+            .into_iter()
+            .find(|installation| folder.to_string_lossy() == installation.id())
+            .ok_or_else(|| eyre::format_err!("Steam installation for {folder:?} not found")),
+        _ => SteamInstallation::resolve(steam_settings),
+    }
+}
+
 pub fn load_backups() -> Vec<PathBuf> {
     let backup_folder = get_backups_folder();
-    let files = std::fs::read_dir(backup_folder);
+    let installation_folders = std::fs::read_dir(&backup_folder)
+        .into_iter()
+        .flat_map(|files| files.flatten())
+        .map(|file| file.path())
+        .filter(|path| path.is_dir());
+    let folders: Vec<PathBuf> = std::iter::once(backup_folder.clone())
+        .chain(installation_folders)
+        .collect();
     let mut result = vec![];
-    if let Ok(files) = files {
+    for folder in folders {
+        let Ok(files) = std::fs::read_dir(folder) else {
+            continue;
+        };
         for file in files.flatten() {
             if file
                 .path()
@@ -113,14 +140,21 @@ const DATE_FORMAT: &str = "[year]-[month]-[day]-[hour]-[minute]-[second]";
 pub fn backup_shortcuts(steam_settings: &SteamSettings) {
     use time::OffsetDateTime;
 
-    let backup_folder = get_backups_folder();
-    let paths = get_shortcuts_paths(steam_settings);
     let date = OffsetDateTime::now_utc();
-    let format = format_description::parse(DATE_FORMAT);
-    if let Ok(format) = format{
-    let date_string = date.format(&format);
-    if let (Ok(date_string),Ok(user_infos)) = (date_string,paths) {
-        for user_info in user_infos {
+    let Ok(format) = format_description::parse(DATE_FORMAT) else {
+        return;
+    };
+    let Ok(date_string) = date.format(&format) else {
+        return;
+    };
+    for installation in sync_targets(steam_settings).unwrap_or_default() {
+        //Each installation has its own folder, so a backup is restored to the installation it came from
+        let backup_folder = get_backups_folder().join(installation.id());
+        if let Err(err) = std::fs::create_dir_all(&backup_folder) {
+            eprintln!("Failed to create backup folder {backup_folder:?}, error: {err:?}");
+            continue;
+        }
+        for user_info in installation.users().unwrap_or_default() {
             if let Some(shortcut_path) = user_info.shortcut_path {
                 let new_path = backup_folder.join(format!(
                     "{}-{}-shortcuts.vdf",
@@ -131,12 +165,10 @@ pub fn backup_shortcuts(steam_settings: &SteamSettings) {
                         println!("Backed up shortcut at: {new_path:?}");
                     }
                     Err(err) => {
-                        eprintln!(
-                            "Failed to backup shortcut at: {new_path:?}, error: {err:?}"
-                        );
+                        eprintln!("Failed to backup shortcut at: {new_path:?}, error: {err:?}");
                     }
                 }
             }
-        }}
+        }
     }
 }
@@ -0,0 +1,253 @@
+use egui::ScrollArea;
+
+use super::components::{render_user_avatar, user_id_text};
+use super::MyEguiApp;
+use crate::steam::{
+    change_collection, get_shortcuts_for_user, read_collections, CollectionChange,
+    SteamCollectionInfo, SteamInstallation, SteamUsersInfo,
+};
+use crate::sync::IsBoilRShortcut;
+
+#[derive(Default)]
+pub struct CollectionsState {
+    /// The collections of the chosen user, they are read again when this is None
+    pub collections: Option<Result<Vec<SteamCollectionInfo>, String>>,
+    /// The BoilR shortcuts of the chosen user, by app id and name
+    pub shortcuts: Vec<(usize, String)>,
+    /// The index of the chosen user, in the users of all the Steam installations
+    pub selected_user: usize,
+    pub previewing: Option<String>,
+    /// The id of the collection that is being renamed and its new name
+    pub renaming: Option<(String, String)>,
+    pub last_error: Option<String>,
+}
+
+impl MyEguiApp {
+    pub fn render_collections(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Collections");
+        ui.label(
+            "Here you can see the collections Steam has for a user, and rename or delete them",
+        );
+        ui.label("BoilR makes its collections again at every import, change the collection rules in the configuration to change them for good");
+        ui.label("Steam must not be running when collections are changed");
+        ui.add_space(super::SECTION_SPACING);
+
+        let users: Vec<(SteamInstallation, SteamUsersInfo)> = self
+            .steam_installations
+            .iter()
+            .flat_map(|(installation, users)| {
+                users
+                    .iter()
+                    .map(move |user| (installation.clone(), user.clone()))
+            })
+            .collect();
+        if users.is_empty() {
+            ui.label("No Steam users found, set the Steam location in the settings");
+            return;
+        }
+        if self.render_collections_user_select(&users, ui) {
+            self.collections_state.collections = None;
+            self.collections_state.previewing = None;
+            self.collections_state.renaming = None;
+        }
+        let Some((installation, user)) = users.get(self.collections_state.selected_user) else {
+            self.collections_state.selected_user = 0;
+            return;
+        };
+
+        if ui.button("Refresh").clicked() {
+            self.collections_state.collections = None;
+        }
+        if let Some(error) = &self.collections_state.last_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let state = &mut self.collections_state;
+        if state.collections.is_none() {
+            state.shortcuts = get_shortcuts_for_user(user)
+                .map(|shortcut_info| {
+                    shortcut_info
+                        .shortcuts
+                        .iter()
+                        .filter(|s| s.is_boilr_shortcut())
+                        .map(|s| (s.app_id as usize, s.app_name.clone()))
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+        let collections = state.collections.get_or_insert_with(|| {
+            read_collections(installation, &user.user_id).map_err(|e| format!("{e}"))
+        });
+        let collections = match collections {
+            Ok(collections) => collections,
+            Err(message) => {
+                ui.label(&*message);
+                return;
+            }
+        };
+        if collections.is_empty() {
+            ui.label("This user has no collections");
+            return;
+        }
+
+        let mut change = None;
+        ScrollArea::vertical()
+            .stick_to_right(true)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for collection in collections.iter() {
+                    if let Some(c) = render_collection(
+                        collection,
+                        &state.shortcuts,
+                        &mut state.previewing,
+                        &mut state.renaming,
+                        ui,
+                    ) {
+                        change = Some((collection.id.clone(), c));
+                    }
+                }
+            });
+
+        if let Some((collection_id, change)) = change {
+            match change_collection(installation, &user.user_id, &collection_id, &change) {
+                Ok(()) => {
+                    state.last_error = None;
+                    state.collections = None;
+                }
+                Err(err) => state.last_error = Some(format!("{err}")),
+            }
+        }
+    }
+
+    /// True if another user is chosen
+    fn render_collections_user_select(
+        &mut self,
+        users: &[(SteamInstallation, SteamUsersInfo)],
+        ui: &mut egui::Ui,
+    ) -> bool {
+        if users.len() <= 1 {
+            return false;
+        }
+        let selected_before = self.collections_state.selected_user;
+        let user_text = |(installation, user): &(SteamInstallation, SteamUsersInfo)| {
+            if self.steam_installations.len() > 1 {
+                format!("{} - {}", user.display_name(), installation.display_name())
+            } else {
+                user.display_name()
+            }
+        };
+        ui.horizontal(|ui| {
+            ui.label("Collections of: ");
+            if let Some(selected) = users.get(selected_before) {
+                render_user_avatar(&selected.1, ui);
+                egui::ComboBox::new("CollectionsUserSelect", "")
+                    .selected_text(user_text(selected))
+                    .show_ui(ui, |ui| {
+                        for (index, target) in users.iter().enumerate() {
+                            ui.selectable_value(
+                                &mut self.collections_state.selected_user,
+                                index,
+                                user_text(target),
+                            )
+                            .on_hover_text(user_id_text(&target.1));
+                        }
+                    });
+            }
+        });
+        selected_before != self.collections_state.selected_user
+    }
+}
+
+fn render_collection(
+    collection: &SteamCollectionInfo,
+    shortcuts: &[(usize, String)],
+    previewing: &mut Option<String>,
+    renaming: &mut Option<(String, String)>,
+    ui: &mut egui::Ui,
+) -> Option<CollectionChange> {
+    let mut change = None;
+    let is_previewing = previewing.as_ref() == Some(&collection.id);
+    ui.horizontal(|ui| {
+        match renaming {
+            Some((id, new_name)) if id == &collection.id => {
+                ui.text_edit_singleline(new_name);
+                if ui.button("Save").clicked() && !new_name.trim().is_empty() {
+                    change = Some(CollectionChange::Update {
+                        name: new_name.trim().to_string(),
+                        game_ids: collection.game_ids.clone(),
+                    });
+                    *renaming = None;
+                } else if ui.button("Cancel").clicked() {
+                    *renaming = None;
+                }
+            }
+            _ => {
+                ui.label(&collection.name).on_hover_text(&collection.id);
+            }
+        }
+        if collection.is_dynamic {
+            ui.label("Dynamic")
+                .on_hover_text("Steam fills this collection from a filter");
+        } else {
+            ui.label(format!("{} games", collection.game_ids.len()));
+        }
+        if collection.is_boilr_collection() {
+            ui.label("BoilR")
+                .on_hover_text("BoilR made this collection, it is made again at the next import");
+        }
+        let preview_text = if is_previewing { "Hide" } else { "Preview" };
+        if !collection.is_dynamic && ui.button(preview_text).clicked() {
+            *previewing = (!is_previewing).then(|| collection.id.clone());
+        }
+        if !collection.is_built_in() {
+            let rename = ui
+                .add_enabled(!collection.is_local_only, egui::Button::new("Rename"))
+                .on_disabled_hover_text("Steam keeps no name for this collection");
+            if rename.clicked() {
+                *renaming = Some((collection.id.clone(), collection.name.clone()));
+            }
+            if ui.button("Delete").clicked() {
+                change = Some(CollectionChange::Delete);
+            }
+        }
+    });
+    if is_previewing {
+        ui.indent(&collection.id, |ui| {
+            if let Some(game_ids) = render_collection_games(collection, shortcuts, ui) {
+                change = Some(CollectionChange::Update {
+                    name: collection.name.clone(),
+                    game_ids,
+                });
+            }
+        });
+    }
+    change
+}
+
+/// Lists the games of a collection, the BoilR shortcuts can be added and removed
+fn render_collection_games(
+    collection: &SteamCollectionInfo,
+    shortcuts: &[(usize, String)],
+    ui: &mut egui::Ui,
+) -> Option<Vec<usize>> {
+    let mut game_ids = None;
+    for game_id in &collection.game_ids {
+        if !shortcuts.iter().any(|(app_id, _)| app_id == game_id) {
+            ui.label(format!("Steam app {game_id}"));
+        }
+    }
+    for (app_id, name) in shortcuts {
+        let was_in_collection = collection.game_ids.contains(app_id);
+        let mut in_collection = was_in_collection;
+        ui.checkbox(&mut in_collection, name);
+        if in_collection != was_in_collection {
+            let mut new_ids = collection.game_ids.clone();
+            new_ids.retain(|id| id != app_id);
+            if in_collection {
+                new_ids.push(*app_id);
+            }
+            game_ids = Some(new_ids);
+        }
+    }
+    game_ids
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+use tokio::{
+    runtime::Runtime,
+    sync::watch::{self, Receiver},
+};
+
+use crate::diagnostics::{CheckStatus, DiagnosticCheck};
+
+use super::FetchStatus;
+
+/// The diagnostics that have been run, by the code name of the platform they were run for
+#[derive(Default)]
+pub struct DiagnosticsState {
+    results: HashMap<String, Receiver<FetchStatus<Vec<DiagnosticCheck>>>>,
+}
+
+impl DiagnosticsState {
+    /// Renders the diagnostics panel for a platform, returns true if the user wants to run the diagnostics
+    pub(crate) fn render(&mut self, ui: &mut egui::Ui, key: &str) -> bool {
+        let mut run_clicked = false;
+        egui::CollapsingHeader::new("Diagnostics")
+            .id_salt(key)
+            .show(ui, |ui| {
+                let running = self
+                    .results
+                    .get(key)
+                    .is_some_and(|rx| matches!(&*rx.borrow(), FetchStatus::Fetching));
+                run_clicked = ui
+                    .add_enabled(!running, egui::Button::new("Run diagnostics"))
+                    .on_hover_text("Check the paths and commands used to find games")
+                    .clicked();
+                if let Some(rx) = self.results.get(key) {
+                    match &*rx.borrow() {
+                        FetchStatus::NeedsFetched => {}
+                        FetchStatus::Fetching => {
+                            ui.ctx().request_repaint();
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label("Running diagnostics");
+                            });
+                        }
+                        FetchStatus::Fetched(checks) => {
+                            for check in checks {
+                                render_check(ui, check);
+                            }
+                        }
+                    }
+                }
+            });
+        run_clicked
+    }
+
+    pub(crate) fn run<F>(&mut self, rt: &Runtime, key: &str, diagnose: F)
+    where
+        F: FnOnce() -> Vec<DiagnosticCheck>,
+        F: Send + 'static,
+    {
+        let (tx, rx) = watch::channel(FetchStatus::Fetching);
+        self.results.insert(key.to_string(), rx);
+        rt.spawn_blocking(move || {
+            let _ = tx.send(FetchStatus::Fetched(diagnose()));
+        });
+    }
+}
+
+fn render_check(ui: &mut egui::Ui, check: &DiagnosticCheck) {
+    let color = match check.status {
+        CheckStatus::Ok => ui.visuals().text_color(),
+        CheckStatus::Info => ui.visuals().weak_text_color(),
+        CheckStatus::Warning => ui.visuals().warn_fg_color,
+        CheckStatus::Error => ui.visuals().error_fg_color,
+    };
+    ui.colored_label(
+        color,
+        format!("{}: {}", check.description, check.detail_text()),
+    );
+}
@@ -1,16 +1,18 @@
 use egui::ScrollArea;
 
 use super::ui_colors::*;
+use super::components::{render_user_avatar, user_id_text};
 use super::MyEguiApp;
 use crate::steam::get_shortcuts_for_user;
-use crate::steam::get_shortcuts_paths;
+use crate::steam::get_shortcuts_paths_with_profiles;
 use crate::steam::ShortcutInfo;
+use crate::steam::SteamUsersInfo;
 use crate::sync::disconnect_shortcut;
 use crate::sync::IsBoilRShortcut;
 
 #[derive(Default)]
 pub struct DisconnectState {
-    pub connected_shortcuts: Option<Result<Vec<ShortcutInfo>, String>>,
+    pub connected_shortcuts: Option<Result<Vec<(SteamUsersInfo, ShortcutInfo)>, String>>,
 }
 
 impl MyEguiApp {
@@ -20,14 +22,14 @@ impl MyEguiApp {
             .disconnect_state
             .connected_shortcuts
             .get_or_insert_with(|| {
-                let users = get_shortcuts_paths(&steam_settings)
+                let users = get_shortcuts_paths_with_profiles(&steam_settings)
                     .map_err(|e| format!("Getting shortcut paths failed: {e}"));
                 users.map(|users| {
                     let mut user_info = vec![];
                     for user in users {
                         let shortcut_info = get_shortcuts_for_user(&user);
                         if let Ok(shortcut_info) = shortcut_info {
-                            user_info.push(shortcut_info);
+                            user_info.push((user, shortcut_info));
                         }
                     }
                     user_info
@@ -54,11 +56,18 @@ impl MyEguiApp {
                     .show(ui, |ui| {
                         ui.reset_style();
 
-                        for user in users.iter_mut() {
+                        for (user, shortcut_info) in users.iter_mut() {
                             if has_multiple_users {
-                                ui.heading(user.path.to_string_lossy().to_string());
+                                ui.horizontal(|ui| {
+                                    render_user_avatar(user, ui);
+                                    ui.heading(user.display_name()).on_hover_text(format!(
+                                        "{}\n{}",
+                                        user_id_text(user),
+                                        shortcut_info.path.to_string_lossy()
+                                    ));
+                                });
                             }
-                            for shortcut in user.shortcuts.iter() {
+                            for shortcut in shortcut_info.shortcuts.iter() {
                                 if shortcut.is_boilr_shortcut()
                                     && ui.button(&shortcut.app_name).clicked()
                                     && disconnect_shortcut(&self.settings, shortcut.app_id).is_ok()
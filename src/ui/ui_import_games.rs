@@ -1,26 +1,46 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use eframe::egui;
 use egui::ScrollArea;
 use futures::executor::block_on;
+use serde::Serialize;
 
-use steam_shortcuts_util::shortcut::ShortcutOwned;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
 
-use crate::config::get_renames_file;
-use crate::platforms::ShortcutToImport;
+use crate::config::{get_renames_file, get_user_renames_file};
+use crate::diagnostics::{diagnose_platform, diagnose_steam};
+use crate::platforms::{GameMetadata, ShortcutToImport};
 #[cfg(target_family = "unix")]
-use crate::steam::setup_proton_games;
+use crate::steam::{choose_compat_tool, setup_proton_games, SteamInstallation};
+use crate::settings::{Settings, SteamUserSettings};
+use crate::steam::{
+    choose_controller_template, choose_shortcut_flags, sync_targets, ControllerTemplate,
+    ShortcutFlags, SteamRunState, SteamSettings, SteamUsersInfo,
+};
 use crate::sync;
 
-use crate::sync::{download_images, SyncProgress};
+use crate::sync::{download_images, Renames, SyncProgress};
 
-use super::{all_ready, backup_shortcuts, get_all_games};
+use super::components::{
+    render_controller_template_select, render_shortcut_flags, render_user_avatar, user_id_text,
+};
 use super::{
-    ui_colors::{BACKGROUND_COLOR, EXTRA_BACKGROUND_COLOR},
+    all_ready, backup_shortcuts, fetch_platform_games, get_all_games, get_platform_code_names,
+    GamesToSync,
+};
+#[cfg(target_family = "unix")]
+use super::components::render_compat_tool_select;
+use super::{
+    ui_colors::{BACKGROUND_COLOR, EXTRA_BACKGROUND_COLOR, ORANGE},
     MyEguiApp,
 };
 
 const SECTION_SPACING: f32 = 25.0;
+//No platform has this code name, so it can be used for the Steam checks
+const STEAM_DIAGNOSTICS: &str = "steam";
 
 pub enum FetchStatus<T> {
     NeedsFetched,
@@ -41,6 +61,7 @@ impl<T> FetchStatus<T> {
 impl MyEguiApp {
     pub(crate) fn render_import_games(&mut self, ui: &mut egui::Ui) {
         ui.heading("Import Games");
+        self.render_pending_collections(ui);
 
         let scroll_style = ui.style_mut();
         scroll_style.visuals.extreme_bg_color = BACKGROUND_COLOR;
@@ -55,9 +76,30 @@ impl MyEguiApp {
         .show(ui,|ui| {
             ui.reset_style();
             ui.label("Select the games you want to import into steam");
-            for (name,status) in &self.games_to_sync{
-                ui.heading(name);
-                match &*status.borrow(){
+            self.render_import_user_select(ui);
+            let mut to_rescan = None;
+            for platform_games in &self.games_to_sync{
+                ui.horizontal(|ui|{
+                    ui.heading(&platform_games.name);
+                    if let Some(user_id) = &self.import_user {
+                        let code_name = &platform_games.code_name;
+                        let mut enabled = self.settings.is_platform_enabled_for(user_id, code_name);
+                        if ui.checkbox(&mut enabled, "Sync for this user").changed() {
+                            let user = self.settings.steam_users.entry(user_id.clone()).or_default();
+                            if enabled {
+                                user.disabled_platforms.retain(|name| name != code_name);
+                            } else {
+                                user.disabled_platforms.push(code_name.clone());
+                            }
+                            remove_unused_user_settings(&mut self.settings);
+                        }
+                    }
+                    let fetching = !platform_games.status.borrow().is_some();
+                    if ui.add_enabled(!fetching, egui::Button::new("Rescan")).on_hover_text("Search for games again, instead of using the games found last time").clicked(){
+                        to_rescan = Some(platform_games.code_name.clone());
+                    }
+                });
+                match &*platform_games.status.borrow(){
                     FetchStatus::NeedsFetched => {ui.label("Need to find games");},
                     FetchStatus::Fetching => {
                         ui.horizontal(|ui|{
@@ -73,38 +115,75 @@ impl MyEguiApp {
                                 }
                                 for shortcut_to_import in shortcuts {
                                     let shortcut = &shortcut_to_import.shortcut;
-                                    let mut import_game = !self.settings.blacklisted_games.contains(&shortcut.app_id);
+                                    let import_user = self.import_user.as_ref();
+                                    let blacklisted_for_all = self.settings.blacklisted_games.contains(&shortcut.app_id);
+                                    let mut import_game = match import_user {
+                                        Some(user_id) => !self.settings.is_blacklisted_for(user_id, shortcut.app_id),
+                                        None => !blacklisted_for_all,
+                                    };
                                     ui.horizontal(|ui|{
                                         if self.current_edit == Option::Some(shortcut.app_id){
-                                            if let Some(new_name) = self.rename_map.get_mut(&shortcut.app_id){
+                                            let renames = edited_renames(&mut self.rename_map, &mut self.user_rename_map, import_user);
+                                            if let Some(new_name) = renames.get_mut(&shortcut.app_id){
                                                 ui.text_edit_singleline(new_name).request_focus();
                                                 if ui.button("Rename").clicked() {
                                                     if new_name.is_empty(){
                                                         *new_name = shortcut.app_name.to_string();
                                                     }
                                                     self.current_edit = Option::None;
-                                                    let rename_file_path = get_renames_file();
-                                                    let contents = serde_json::to_string(&self.rename_map);
-                                                    if let Ok(contents) = contents{
-                                                        let res = std::fs::write(&rename_file_path, contents);
-                                                        println!("Write rename file at {rename_file_path:?} with result: {res:?}");
-                                                    }
+                                                    save_renames(&self.rename_map, &self.user_rename_map);
                                                 }
                                             }
                                         }  else {
-                                            let name = self.rename_map.get(&shortcut.app_id).unwrap_or(&shortcut.app_name);
-                                            let checkbox = egui::Checkbox::new(&mut import_game,name);
-                                            let response = ui.add(checkbox);
+                                            let name = import_user
+                                                .and_then(|user_id| self.user_rename_map.get(user_id))
+                                                .and_then(|renames| renames.get(&shortcut.app_id))
+                                                .or_else(|| self.rename_map.get(&shortcut.app_id))
+                                                .unwrap_or(&shortcut.app_name)
+                                                .to_owned();
+                                            let checkbox = egui::Checkbox::new(&mut import_game,&name);
+                                            //A game that is left out for everyone can not be added for a single user
+                                            let mut response = ui.add_enabled(import_user.is_none() || !blacklisted_for_all, checkbox);
+                                            if let Some(metadata) = &shortcut_to_import.metadata{
+                                                response = response.on_hover_ui(|ui| render_game_details(ui, metadata));
+                                            }
                                             if response.double_clicked(){
-                                                self.rename_map.entry(shortcut.app_id).or_insert_with(|| shortcut.app_name.to_owned());
+                                                edited_renames(&mut self.rename_map, &mut self.user_rename_map, import_user).entry(shortcut.app_id).or_insert(name);
                                                 self.current_edit = Option::Some(shortcut.app_id);
                                             }
                                             if response.clicked(){
-                                                if !self.settings.blacklisted_games.contains(&shortcut.app_id){
-                                                    self.settings.blacklisted_games.push(shortcut.app_id);
+                                                let blacklist = match import_user {
+                                                    Some(user_id) => &mut self.settings.steam_users.entry(user_id.clone()).or_default().blacklisted_games,
+                                                    None => &mut self.settings.blacklisted_games,
+                                                };
+                                                if !blacklist.contains(&shortcut.app_id){
+                                                    blacklist.push(shortcut.app_id);
                                                 } else {
-                                                    self.settings.blacklisted_games.retain(|id| *id != shortcut.app_id);
+                                                    blacklist.retain(|id| *id != shortcut.app_id);
                                                 }
+                                                remove_unused_user_settings(&mut self.settings);
+                                            }
+                                            #[cfg(target_family = "unix")]
+                                            if shortcut_to_import.needs_proton && import_game
+                                                && render_compat_tool_select(&mut self.compat_tool_map, shortcut.app_id, "Platform default", &self.compat_tools, ui){
+                                                save_map(&self.compat_tool_map, crate::config::get_compat_tools_file());
+                                            }
+                                            if import_game {
+                                                ui.menu_button("Flags", |ui| {
+                                                    let flags = self.shortcut_flags_map.entry(shortcut.app_id).or_default();
+                                                    if render_shortcut_flags(flags, "Platform default", ui) {
+                                                        self.shortcut_flags_map.retain(|_, flags| !flags.is_empty());
+                                                        save_map(&self.shortcut_flags_map, crate::config::get_shortcut_flags_file());
+                                                    }
+                                                }).response.on_hover_text("The flags of the shortcut in Steam, fx if it uses the Steam overlay");
+                                                ui.menu_button("Controller", |ui| {
+                                                    if !self.settings.steam.stop_steam {
+                                                        ui.colored_label(ORANGE, "Templates are only set when Steam is not running, turn on \"Stop Steam before import\" in the settings");
+                                                    }
+                                                    if render_controller_template_select(&mut self.controller_template_map, shortcut.app_id, "Platform default", ui) {
+                                                        save_map(&self.controller_template_map, crate::config::get_controller_templates_file());
+                                                    }
+                                                }).response.on_hover_text("The Steam Input template the game starts with");
                                             }
                                         }
                                     });
@@ -116,14 +195,84 @@ impl MyEguiApp {
                         };
                     },
                 }
+                let code_name = &platform_games.code_name;
+                if self.diagnostics.render(ui, code_name){
+                    if let Some(platform) = self.platforms.iter().find(|p| p.code_name() == code_name){
+                        let platform = platform.clone();
+                        self.diagnostics.run(&self.rt, code_name, move || diagnose_platform(platform.as_ref()));
+                    }
+                }
 
             };
+            if let Some(code_name) = to_rescan{
+                if let Some(platform) = self.platforms.iter().find(|p| p.code_name() == code_name){
+                    let status = fetch_platform_games(&self.rt, platform.clone(), false);
+                    if let Some(platform_games) = self.games_to_sync.iter_mut().find(|g| g.code_name == code_name){
+                        platform_games.status = status;
+                    }
+                }
+            }
             ui.add_space(SECTION_SPACING);
 
             ui.label("Check the settings if BoilR didn't find the game you where looking for");
+            ui.heading("Steam");
+            if self.diagnostics.render(ui, STEAM_DIAGNOSTICS){
+                let settings = self.settings.clone();
+                self.diagnostics.run(&self.rt, STEAM_DIAGNOSTICS, move || diagnose_steam(&settings));
+            }
         });
     }
 
+    /// Chooses the Steam user that games are left out and renamed for, when there is more than one
+    fn render_import_user_select(&mut self, ui: &mut egui::Ui) {
+        let mut users: Vec<&SteamUsersInfo> = vec![];
+        for user in self.steam_installations.iter().flat_map(|(_, users)| users) {
+            //The same user can be in several installations, the choices are the same for all of them
+            if !users.iter().any(|known| known.user_id == user.user_id) {
+                users.push(user);
+            }
+        }
+        if users.len() <= 1 {
+            self.import_user = None;
+            return;
+        }
+        let everyone = "All Steam users".to_string();
+        let selected_user = users
+            .iter()
+            .find(|user| Some(&user.user_id) == self.import_user.as_ref());
+        ui.horizontal(|ui| {
+            ui.label("Choices for: ");
+            if let Some(user) = selected_user {
+                render_user_avatar(user, ui);
+            }
+            let selected_text = selected_user.map_or(everyone.clone(), |user| user.display_name());
+            egui::ComboBox::new("import_user_select", "")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.import_user, None, everyone);
+                    for user in &users {
+                        ui.selectable_value(
+                            &mut self.import_user,
+                            Some(user.user_id.clone()),
+                            user.display_name(),
+                        )
+                        .on_hover_text(user_id_text(user));
+                    }
+                });
+        })
+        .response
+        .on_hover_text("Games and platforms that are left out or renamed for a Steam user are only left out or renamed for that user");
+    }
+
+    fn render_pending_collections(&self, ui: &mut egui::Ui) {
+        let pending = *self.pending_collections.borrow();
+        if pending > 0 {
+            ui.colored_label(ORANGE, "Collections pending").on_hover_text(format!(
+                "Steam held its collections database during the import, the collections for {pending} Steam user(s) are written once Steam exits"
+            ));
+        }
+    }
+
     pub fn run_sync_blocking(&mut self) -> eyre::Result<()> {
         self.run_sync(true)
     }
@@ -134,38 +283,112 @@ impl MyEguiApp {
     fn run_sync(&mut self, wait: bool) -> eyre::Result<()> {
         let (sender, reciever) = watch::channel(SyncProgress::NotStarted);
         let settings = self.settings.clone();
-        if settings.steam.stop_steam {
-            crate::steam::ensure_steam_stopped();
-        }
-
+        let installations = match sync_targets(&settings.steam) {
+            Ok(installations) => installations,
+            Err(err) => {
+                let _ = sender.send(SyncProgress::Error {
+                    message: format!("{err}"),
+                });
+                self.status_reciever = reciever;
+                return Err(err);
+            }
+        };
         self.status_reciever = reciever;
-        let renames = self.rename_map.clone();
+        let renames = Renames {
+            all_users: self.rename_map.clone(),
+            users: self.user_rename_map.clone(),
+        };
         let all_ready = all_ready(&self.games_to_sync);
         let _ = sender.send(SyncProgress::Starting);
         if all_ready {
             let shortcuts_to_import = get_all_games(&self.games_to_sync);
+            let platform_code_names = get_platform_code_names(&self.games_to_sync);
+            #[cfg(target_family = "unix")]
+            let proton_games = get_proton_games(&self.games_to_sync, &settings.steam, &self.compat_tool_map);
+            let shortcut_flags = get_shortcut_flags(&self.games_to_sync, &settings.steam, &self.shortcut_flags_map);
+            let controller_templates = get_controller_templates(&self.games_to_sync, &settings.steam, &self.controller_template_map);
             let handle: JoinHandle<eyre::Result<()>> = self.rt.spawn_blocking(move || {
+                let run_state = if settings.steam.stop_steam {
+                    crate::steam::ensure_steam_stopped(
+                        &installations,
+                        Duration::from_secs(settings.steam.stop_timeout_seconds),
+                        |progress| {
+                            let _ = sender.send(SyncProgress::StoppingSteam(progress));
+                        },
+                    )
+                } else {
+                    SteamRunState::default()
+                };
                 #[cfg(target_family = "unix")]
-                setup_proton(shortcuts_to_import.iter());
-
-                let import_games = to_shortcut_owned(shortcuts_to_import);
+                setup_proton(&installations, shortcuts_to_import.iter(), &proton_games);
 
                 let mut some_sender = Some(sender);
                 backup_shortcuts(&settings.steam);
-                let usersinfo =
-                    sync::sync_shortcuts(&settings, &import_games, &mut some_sender, &renames)?;
-                let task = download_images(&settings, &usersinfo, &mut some_sender);
+                let mut usersinfo = vec![];
+                let mut sync_errors = vec![];
+                for installation in &installations {
+                    //A broken installation must not keep the games from the others
+                    let synced = sync::sync_shortcuts(
+                        &settings,
+                        installation,
+                        &shortcuts_to_import,
+                        &mut some_sender,
+                        &renames,
+                        &platform_code_names,
+                        &shortcut_flags,
+                        &controller_templates,
+                    );
+                    match synced {
+                        Ok(users) => usersinfo.extend(users),
+                        Err(err) => {
+                            let message = format!("Could not sync to {}: {err}", installation.display_name());
+                            eprintln!("{message}");
+                            sync_errors.push(message);
+                        }
+                    }
+                }
+                let games_metadata = sync::games_metadata(&shortcuts_to_import, &renames);
+                let task = download_images(&settings, &usersinfo, &games_metadata, &mut some_sender);
                 block_on(task);
+                sync::copy_platform_covers(
+                    &settings,
+                    &usersinfo,
+                    &shortcuts_to_import,
+                    &platform_code_names,
+                    &renames,
+                );
                 //Run a second time to fix up shortcuts after images are downloaded
                 if let Err(e) = sync::fix_all_shortcut_icons(&settings) {
                     eprintln!("Could not fix shortcuts with error {e}");
                 }
 
+                #[cfg(target_family = "unix")]
+                let done = {
+                    let report =
+                        sync::symlinks::maintain_links_folder(&shortcuts_to_import, &usersinfo);
+                    println!("Links folder: {report}");
+                    if report.is_empty() {
+                        SyncProgress::Done
+                    } else {
+                        SyncProgress::DoneWithLinksReport {
+                            report: report.to_string(),
+                        }
+                    }
+                };
+                #[cfg(target_os = "windows")]
+                let done = SyncProgress::Done;
+                let done = if sync_errors.is_empty() {
+                    done
+                } else {
+                    SyncProgress::Error {
+                        message: sync_errors.join("\n"),
+                    }
+                };
                 if let Some(sender) = some_sender {
-                    let _ = sender.send(SyncProgress::Done);
+                    let _ = sender.send(done);
                 }
                 if settings.steam.start_steam {
-                    crate::steam::ensure_steam_started(&settings.steam);
+                    crate::steam::ensure_steam_started(&installations, &run_state);
                 }
                 Ok(())
             });
@@ -177,42 +400,161 @@ impl MyEguiApp {
     }
 }
 
-fn to_shortcut_owned(
-    shortcuts_to_import: Vec<(String, Vec<ShortcutToImport>)>,
-) -> Vec<(String, Vec<ShortcutOwned>)> {
-    let mut import_games = vec![];
-    for (name, infos) in shortcuts_to_import {
-        let mut shortcuts = vec![];
-        for info in infos {
-            shortcuts.push(info.shortcut);
+/// The names that are edited on the import page, the ones for the chosen Steam user or the ones for everyone
+fn edited_renames<'a>(
+    renames: &'a mut HashMap<u32, String>,
+    user_renames: &'a mut HashMap<String, HashMap<u32, String>>,
+    import_user: Option<&String>,
+) -> &'a mut HashMap<u32, String> {
+    match import_user {
+        Some(user_id) => user_renames.entry(user_id.clone()).or_default(),
+        None => renames,
+    }
+}
+
+fn save_renames(
+    renames: &HashMap<u32, String>,
+    user_renames: &HashMap<String, HashMap<u32, String>>,
+) {
+    let files = [
+        (get_renames_file(), serde_json::to_string(renames)),
+        (get_user_renames_file(), serde_json::to_string(user_renames)),
+    ];
+    for (rename_file_path, contents) in files {
+        if let Ok(contents) = contents {
+            let res = std::fs::write(&rename_file_path, contents);
+            println!("Write rename file at {rename_file_path:?} with result: {res:?}");
         }
-        import_games.push((name, shortcuts));
     }
-    import_games
+}
+
+/// Users that have nothing chosen for them are left out of the settings file
+fn remove_unused_user_settings(settings: &mut Settings) {
+    settings
+        .steam_users
+        .retain(|_, user| *user != SteamUserSettings::default());
+}
+
+fn render_game_details(ui: &mut egui::Ui, metadata: &GameMetadata) {
+    egui::Grid::new("game_details").num_columns(2).show(ui, |ui| {
+        for (label, value) in metadata.details() {
+            ui.label(label);
+            ui.label(value);
+            ui.end_row();
+        }
+    });
 }
 
 #[cfg(target_family = "unix")]
-fn setup_proton<'a, I>(shortcut_infos: I)
+fn setup_proton<'a, I>(
+    installations: &[SteamInstallation],
+    shortcut_infos: I,
+    proton_games: &[(String, Option<String>)],
+)
 where
     I: IntoIterator<Item = &'a (String, Vec<ShortcutToImport>)>,
 {
-    let mut shortcuts_to_proton = vec![];
-
     for (name, shortcuts) in shortcut_infos {
         for shortcut_info in shortcuts {
             if shortcut_info.needs_proton {
                 crate::sync::symlinks::ensure_links_folder_created(name);
             }
-            if shortcut_info.needs_proton {
-                shortcuts_to_proton.push(format!("{}", shortcut_info.shortcut.app_id));
-            }
 
             if shortcut_info.needs_symlinks {
                 crate::sync::symlinks::create_sym_links(&shortcut_info.shortcut);
             }
         }
-        if let Err(err) = setup_proton_games(&shortcuts_to_proton){
+    }
+    for installation in installations {
+        if let Err(err) = setup_proton_games(installation, proton_games) {
             eprintln!("failed to save proton settings: {err:?}");
         }
     }
 }
+
+/// The app ids of the games that need Proton, with the compat tool chosen for each of them if there is a choice
+#[cfg(target_family = "unix")]
+fn get_proton_games(
+    games: &GamesToSync,
+    steam_settings: &SteamSettings,
+    compat_tool_map: &HashMap<u32, String>,
+) -> Vec<(String, Option<String>)> {
+    let mut proton_games = vec![];
+    for platform_games in games {
+        if let FetchStatus::Fetched(Ok(shortcuts)) = &*platform_games.status.borrow() {
+            for shortcut_info in shortcuts.iter().filter(|s| s.needs_proton) {
+                let app_id = shortcut_info.shortcut.app_id;
+                let tool = choose_compat_tool(
+                    steam_settings,
+                    compat_tool_map,
+                    &platform_games.code_name,
+                    app_id,
+                );
+                proton_games.push((app_id.to_string(), tool.map(str::to_string)));
+            }
+        }
+    }
+    proton_games
+}
+
+/// The shortcut flags chosen for each game and its platform, by the app id the game has before it is renamed
+fn get_shortcut_flags(
+    games: &GamesToSync,
+    steam_settings: &SteamSettings,
+    shortcut_flags_map: &HashMap<u32, ShortcutFlags>,
+) -> HashMap<u32, ShortcutFlags> {
+    let mut shortcut_flags = HashMap::new();
+    for platform_games in games {
+        if let FetchStatus::Fetched(Ok(shortcuts)) = &*platform_games.status.borrow() {
+            for shortcut_info in shortcuts {
+                let app_id = shortcut_info.shortcut.app_id;
+                let flags = choose_shortcut_flags(
+                    steam_settings,
+                    shortcut_flags_map,
+                    &platform_games.code_name,
+                    app_id,
+                );
+                if !flags.is_empty() {
+                    shortcut_flags.insert(app_id, flags);
+                }
+            }
+        }
+    }
+    shortcut_flags
+}
+
+/// The controller template chosen for each game and its platform, by the app id the game has before it is renamed
+fn get_controller_templates(
+    games: &GamesToSync,
+    steam_settings: &SteamSettings,
+    controller_template_map: &HashMap<u32, ControllerTemplate>,
+) -> HashMap<u32, ControllerTemplate> {
+    let mut controller_templates = HashMap::new();
+    for platform_games in games {
+        if let FetchStatus::Fetched(Ok(shortcuts)) = &*platform_games.status.borrow() {
+            for shortcut_info in shortcuts {
+                let app_id = shortcut_info.shortcut.app_id;
+                let template = choose_controller_template(
+                    steam_settings,
+                    controller_template_map,
+                    &platform_games.code_name,
+                    app_id,
+                );
+                if let Some(template) = template {
+                    controller_templates.insert(app_id, template.clone());
+                }
+            }
+        }
+    }
+    controller_templates
+}
+
+/// Saves the choices made for the games, by their app id
+fn save_map<T: Serialize>(map: &HashMap<u32, T>, path: PathBuf) {
+    let result = serde_json::to_string(map)
+        .map_err(eyre::Report::from)
+        .and_then(|contents| Ok(std::fs::write(&path, contents)?));
+    if let Err(err) = result {
+        eprintln!("Could not save {path:?}: {err}");
+    }
+}
@@ -2,6 +2,15 @@ use copypasta::ClipboardProvider;
 use eframe::egui;
 use egui::ScrollArea;
 
+use crate::platforms::is_valid_instance_name;
+use crate::steam::SteamInstallation;
+
+#[cfg(target_family = "unix")]
+use super::components::render_compat_tool_select;
+use super::components::{
+    render_controller_template_select, render_shortcut_flags, render_user_avatar, user_id_text,
+};
+
 use super::{
     ui_colors::{BACKGROUND_COLOR, EXTRA_BACKGROUND_COLOR},
     MyEguiApp,
@@ -9,6 +18,12 @@ use super::{
 pub const SECTION_SPACING: f32 = 25.0;
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+#[derive(Default)]
+pub struct NewPlatformInstanceState {
+    pub platform_type: String,
+    pub instance_name: String,
+}
+
 impl MyEguiApp {
     pub(crate) fn render_settings(&mut self, ui: &mut egui::Ui) {
         ui.heading("Settings");
@@ -30,16 +45,128 @@ impl MyEguiApp {
 
                 self.render_steam_settings(ui);
 
-                for platform in &mut self.platforms {
+                let mut to_remove = None;
+                for (index, platform) in self.platforms.iter_mut().enumerate() {
                     platform.render_ui(ui);
+                    #[cfg(target_family = "unix")]
+                    if platform.can_need_proton() {
+                        ui.horizontal(|ui| {
+                            ui.label("Proton version: ");
+                            render_compat_tool_select(
+                                &mut self.settings.steam.platform_compat_tools,
+                                platform.code_name().to_string(),
+                                "Proton Experimental",
+                                &self.compat_tools,
+                                ui,
+                            );
+                        })
+                        .response
+                        .on_hover_text("The compat tool for the games of this platform that need Proton, it can also be chosen for each game on the import page");
+                    }
+                    let code_name = platform.code_name().to_string();
+                    ui.horizontal(|ui| {
+                        ui.label("Controller template: ");
+                        render_controller_template_select(
+                            &mut self.settings.steam.platform_controller_templates,
+                            code_name.clone(),
+                            "Steam default",
+                            ui,
+                        );
+                    })
+                    .response
+                    .on_hover_text("The Steam Input template the games of this platform start with, it can also be chosen for each game on the import page");
+                    ui.collapsing("Shortcut flags", |ui| {
+                        let flags_map = &mut self.settings.steam.platform_shortcut_flags;
+                        let flags = flags_map.entry(code_name.clone()).or_default();
+                        render_shortcut_flags(flags, "Keep", ui);
+                        flags_map.retain(|_, flags| !flags.is_empty());
+                    })
+                    .header_response
+                    .on_hover_text("The flags of the shortcuts of this platform in Steam, they can also be chosen for each game on the import page. Flags that are kept are left as they are in Steam");
+                    if platform.instance_name().is_some()
+                        && ui.button("Remove instance").clicked()
+                    {
+                        to_remove = Some(index);
+                    }
                     ui.add_space(SECTION_SPACING);
                 }
+                if let Some(index) = to_remove {
+                    self.platforms.remove(index);
+                }
+                self.render_add_platform_instance(ui);
                 ui.label(format!("Version: {VERSION}"));
             });
     }
 
+    fn render_add_platform_instance(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Extra platform instances");
+        ui.label("Add another instance of a platform, fx for a second library folder. Each instance has its own settings.");
+        let registry = &self.platform_registry;
+        let state = &mut self.new_platform_instance;
+        if state.platform_type.is_empty() {
+            if let Some(first) = registry.types().first() {
+                state.platform_type = first.code_name.to_string();
+            }
+        }
+        let selected_name = registry
+            .types()
+            .iter()
+            .find(|t| t.code_name == state.platform_type)
+            .map(|t| t.display_name().to_string())
+            .unwrap_or_default();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("new_platform_instance_type")
+                .selected_text(selected_name)
+                .show_ui(ui, |ui| {
+                    for platform_type in registry.types() {
+                        ui.selectable_value(
+                            &mut state.platform_type,
+                            platform_type.code_name.to_string(),
+                            platform_type.display_name(),
+                        );
+                    }
+                });
+            ui.label("Instance name: ");
+            ui.text_edit_singleline(&mut state.instance_name);
+        });
+        let section_name = format!("{}.{}", state.platform_type, state.instance_name);
+        let already_exists = self
+            .platforms
+            .iter()
+            .any(|p| p.code_name() == section_name);
+        let valid = is_valid_instance_name(&state.instance_name) && !already_exists;
+        if !state.instance_name.is_empty() && !valid {
+            ui.label("Instance names must be unique and can only contain letters, numbers, - and _");
+        }
+        if ui
+            .add_enabled(valid, egui::Button::new("Add instance"))
+            .clicked()
+        {
+            match registry.load_instance(&state.platform_type, &state.instance_name, "") {
+                Ok(platform) => {
+                    //Keep instances next to the other instances of the same platform
+                    let type_prefix = format!("{}.", state.platform_type);
+                    let position = self
+                        .platforms
+                        .iter()
+                        .rposition(|p| {
+                            p.code_name() == state.platform_type
+                                || p.code_name().starts_with(&type_prefix)
+                        })
+                        .map(|index| index + 1)
+                        .unwrap_or(self.platforms.len());
+                    self.platforms.insert(position, platform);
+                    state.instance_name.clear();
+                }
+                Err(err) => eprintln!("Could not add platform instance: {err}"),
+            }
+        }
+        ui.add_space(SECTION_SPACING);
+    }
+
     fn render_steam_settings(&mut self, ui: &mut egui::Ui) {
         ui.heading("Steam");
+        let mut location_changed = false;
         ui.horizontal(|ui| {
             let mut empty_string = "".to_string();
             let steam_location = self
@@ -55,8 +182,13 @@ impl MyEguiApp {
                 } else {
                     self.settings.steam.location = Some(steam_location.to_string());
                 }
+                location_changed = true;
             }
         });
+        if location_changed {
+            self.refresh_steam_installations();
+        }
+        self.render_steam_installations(ui);
         ui.checkbox(
             &mut self.settings.steam.create_collections,
             "Create collections",
@@ -67,15 +199,96 @@ impl MyEguiApp {
             &mut self.settings.steam.stop_steam,
             "Stop Steam before import",
         )
-        .on_hover_text("Stops Steam if it is running when import starts");
+        .on_hover_text("Stops Steam if it is running when import starts, controller templates are only set when Steam is not running");
+        if self.settings.steam.stop_steam {
+            ui.horizontal(|ui| {
+                ui.label("Seconds Steam gets to exit: ");
+                ui.add(
+                    egui::DragValue::new(&mut self.settings.steam.stop_timeout_seconds)
+                        .range(1..=600),
+                );
+            })
+            .response
+            .on_hover_text("Steam is asked to exit by itself, it is only stopped with signals if it takes longer than this");
+        }
         ui.checkbox(
             &mut self.settings.steam.start_steam,
             "Start Steam after import",
         )
         .on_hover_text("Starts Steam is it is not running after the import");
+        ui.checkbox(
+            &mut self.settings.steam.hide_blacklisted_games,
+            "Hide left out games",
+        )
+        .on_hover_text("Games that are left out on the import page are hidden in Steam instead of being removed");
         ui.add_space(SECTION_SPACING);
     }
 
+    fn render_steam_installations(&mut self, ui: &mut egui::Ui) {
+        if self.steam_installations.is_empty() {
+            ui.label("No Steam installation found, set the Steam location above");
+            return;
+        }
+        ui.label("Sync to: ")
+            .on_hover_text("The Steam installations and users the import writes shortcuts to");
+        let targets = &self.sync_targets;
+        let mut toggled = None;
+        for (installation, users) in &self.steam_installations {
+            let location = installation.location();
+            let mut synced = targets.iter().any(|target| target.location() == location);
+            //There always has to be something to sync to
+            let only_target = synced && targets.len() == 1;
+            let checkbox = egui::Checkbox::new(&mut synced, installation.display_name());
+            if ui.add_enabled(!only_target, checkbox).changed() {
+                toggled = Some((location.clone(), synced));
+            }
+            if !synced {
+                continue;
+            }
+            ui.indent(installation.id(), |ui| {
+                for user in users {
+                    let skipped_users = &mut self.settings.steam.skipped_users;
+                    let skipped = skipped_users
+                        .get(&location)
+                        .is_some_and(|skipped| skipped.contains(&user.user_id));
+                    let mut sync_user = !skipped;
+                    let changed = ui
+                        .horizontal(|ui| {
+                            render_user_avatar(user, ui);
+                            ui.checkbox(&mut sync_user, user.display_name())
+                                .on_hover_text(user_id_text(user))
+                                .changed()
+                        })
+                        .inner;
+                    if !changed {
+                        continue;
+                    }
+                    let skipped = skipped_users.entry(location.clone()).or_default();
+                    if sync_user {
+                        skipped.retain(|id| id != &user.user_id);
+                    } else {
+                        skipped.push(user.user_id.clone());
+                    }
+                    if skipped.is_empty() {
+                        skipped_users.remove(&location);
+                    }
+                }
+            });
+        }
+        if let Some((location, synced)) = toggled {
+            let locations = &mut self.settings.steam.sync_locations;
+            if locations.is_empty() {
+                *locations = targets.iter().map(SteamInstallation::location).collect();
+            }
+            if synced {
+                locations.push(location);
+            } else {
+                locations.retain(|known| known != &location);
+            }
+            self.refresh_steam_installations();
+        }
+    }
+
     fn render_steamgriddb_settings(&mut self, ui: &mut egui::Ui) {
         ui.heading("SteamGridDB");
         ui.checkbox(&mut self.settings.steamgrid_db.enabled, "Download images");
@@ -1,6 +1,7 @@
-use std::{collections::HashMap, error::Error, time::Duration};
+use std::{collections::HashMap, error::Error, path::PathBuf, sync::Arc, time::Duration};
 
 use eframe::{egui, App, Frame};
+use serde::de::DeserializeOwned;
 use egui::{
    ImageButton, Rounding, Stroke, Vec2
 };
@@ -10,9 +11,20 @@ use tokio::{
 };
 
 use crate::{
-    config::get_renames_file,
-    platforms::{get_platforms, GamesPlatform, Platforms, ShortcutToImport},
+    config::{
+        get_controller_templates_file, get_renames_file, get_shortcut_flags_file,
+        get_user_renames_file,
+    },
+    platforms::{
+        get_platform_registry, get_platforms, CancelToken, GamesPlatform, PlatformRegistry,
+        Platforms, ShortcutToImport,
+    },
     settings::{save_settings, Settings},
+    steam::{
+        apply_pending_collections, steam_installations, wait_for_pending_collections,
+        ControllerTemplate, ShortcutFlags, SteamInstallation, SteamSettings, SteamStopProgress,
+        SteamUsersInfo,
+    },
     sync::{self, SyncProgress},
 };
 
@@ -24,27 +36,34 @@ use super::{
     },
     ui_images::get_logo_icon,
     ui_import_games::FetchStatus,
-    BackupState, DisconnectState,
+    BackupState, CollectionsState, DiagnosticsState, DisconnectState, NewPlatformInstanceState,
 };
 
 const SECTION_SPACING: f32 = 25.0;
+/// How often BoilR checks if Steam let go of its database, while collections wait for it
+const PENDING_COLLECTIONS_INTERVAL: Duration = Duration::from_secs(5);
 
 
-type GamesToSync = Vec<(
-    String,
-    Receiver<FetchStatus<eyre::Result<Vec<ShortcutToImport>>>>,
-)>;
+/// An enabled platform and the games that were found for it
+pub(crate) struct PlatformGames {
+    pub(crate) name: String,
+    pub(crate) code_name: String,
+    pub(crate) collection_name: String,
+    pub(crate) status: Receiver<FetchStatus<eyre::Result<Vec<ShortcutToImport>>>>,
+}
+
+pub(crate) type GamesToSync = Vec<PlatformGames>;
 
 pub(crate) fn all_ready(games: &GamesToSync) -> bool {
-    games.iter().all(|(_name, rx)| rx.borrow().is_some())
+    games.iter().all(|games| games.status.borrow().is_some())
 }
 
 pub(crate) fn get_all_games(games: &GamesToSync) -> Vec<(String, Vec<ShortcutToImport>)> {
     games
         .iter()
-        .filter_map(|(name, rx)| {
-            if let FetchStatus::Fetched(Ok(data)) = &*rx.borrow() {
-                Some((name.to_owned(), data.to_owned()))
+        .filter_map(|games| {
+            if let FetchStatus::Fetched(Ok(data)) = &*games.status.borrow() {
+                Some((games.collection_name.to_owned(), data.to_owned()))
             } else {
                 None
             }
@@ -52,18 +71,52 @@ pub(crate) fn get_all_games(games: &GamesToSync) -> Vec<(String, Vec<ShortcutToI
         .collect()
 }
 
+/// The code names of the platforms, by the name of their collection
+pub(crate) fn get_platform_code_names(games: &GamesToSync) -> HashMap<String, String> {
+    games
+        .iter()
+        .map(|games| (games.collection_name.clone(), games.code_name.clone()))
+        .collect()
+}
+
 pub struct MyEguiApp {
     selected_menu: Menues,
     pub(crate) settings: Settings,
     pub(crate) rt: Runtime,
     pub(crate) games_to_sync: GamesToSync,
     pub(crate) status_reciever: Receiver<SyncProgress>,
+    /// The number of Steam users whose collections wait for Steam to let go of its database
+    pub(crate) pending_collections: Receiver<usize>,
+    /// The Steam settings as they are now, for the watcher of the pending collections
+    steam_settings: watch::Sender<SteamSettings>,
     pub(crate) image_selected_state: ImageSelectState,
     pub(crate) backup_state: BackupState,
     pub(crate) disconnect_state: DisconnectState,
+    pub(crate) collections_state: CollectionsState,
     pub(crate) rename_map: HashMap<u32, String>,
+    /// The names chosen for single Steam users, by their user id
+    pub(crate) user_rename_map: HashMap<String, HashMap<u32, String>>,
+    /// The Steam user the choices on the import page are for, everyone if it is not set
+    pub(crate) import_user: Option<String>,
     pub(crate) current_edit: Option<u32>,
+    /// The compat tool chosen for a game, by the app id the game has before it is renamed
+    #[cfg(target_family = "unix")]
+    pub(crate) compat_tool_map: HashMap<u32, String>,
+    #[cfg(target_family = "unix")]
+    pub(crate) compat_tools: Vec<crate::steam::CompatTool>,
+    /// The shortcut flags chosen for a game, by the app id the game has before it is renamed
+    pub(crate) shortcut_flags_map: HashMap<u32, ShortcutFlags>,
+    /// The controller template chosen for a game, by the app id the game has before it is renamed
+    pub(crate) controller_template_map: HashMap<u32, ControllerTemplate>,
+    /// The Steam installations that were found and their users, for the Steam settings
+    pub(crate) steam_installations: Vec<(SteamInstallation, Vec<SteamUsersInfo>)>,
+    /// The installations a sync writes to, for the Steam settings
+    pub(crate) sync_targets: Vec<SteamInstallation>,
     pub(crate) platforms: Platforms,
+    pub(crate) new_platform_instance: NewPlatformInstanceState,
+    /// The kinds of platforms that instances can be added for
+    pub(crate) platform_registry: PlatformRegistry,
+    pub(crate) diagnostics: DiagnosticsState,
 }
 
 impl MyEguiApp {
@@ -72,24 +125,98 @@ impl MyEguiApp {
         let settings = Settings::new()?;
         let platforms = get_platforms();
         let games_to_sync = create_games_to_sync(&mut runtime, &platforms);
-        Ok(Self {
+        let steam_settings = watch::channel(settings.steam.clone()).0;
+        let mut app = Self {
             selected_menu: Menues::Import,
             settings,
             rt: runtime,
             games_to_sync,
             status_reciever: watch::channel(SyncProgress::NotStarted).1,
+            pending_collections: watch::channel(0).1,
+            steam_settings,
             image_selected_state: ImageSelectState::default(),
             backup_state: BackupState::default(),
             disconnect_state: DisconnectState::default(),
+            collections_state: CollectionsState::default(),
             rename_map: get_rename_map(),
+            user_rename_map: try_read_json(get_user_renames_file()).unwrap_or_default(),
+            import_user: None,
             current_edit: Option::None,
+            #[cfg(target_family = "unix")]
+            compat_tool_map: get_compat_tool_map(),
+            #[cfg(target_family = "unix")]
+            compat_tools: vec![],
+            shortcut_flags_map: try_read_json(get_shortcut_flags_file()).unwrap_or_default(),
+            controller_template_map: try_read_json(get_controller_templates_file())
+                .unwrap_or_default(),
+            steam_installations: vec![],
+            sync_targets: vec![],
             platforms,
-        })
+            new_platform_instance: Default::default(),
+            platform_registry: get_platform_registry(),
+            diagnostics: Default::default(),
+        };
+        app.refresh_steam_installations();
+        Ok(app)
+    }
+
+    /// Writes the collections that wait for Steam to exit, as soon as Steam lets go of its database
+    fn watch_pending_collections(&mut self) {
+        let (sender, reciever) = watch::channel(0);
+        let settings = self.steam_settings.subscribe();
+        self.rt.spawn(async move {
+            loop {
+                let steam_settings = settings.borrow().clone();
+                let pending =
+                    tokio::task::spawn_blocking(move || apply_pending_collections(&steam_settings))
+                        .await
+                        .unwrap_or_default();
+                if sender.send(pending).is_err() {
+                    break;
+                }
+                if pending == 0 {
+                    //Nothing waits, so there is nothing to check until an import keeps collections for later
+                    wait_for_pending_collections().await;
+                } else {
+                    tokio::time::sleep(PENDING_COLLECTIONS_INTERVAL).await;
+                }
+            }
+        });
+        self.pending_collections = reciever;
+    }
+
+    /// Looks for Steam installations and their compat tools again, fx after the Steam settings changed
+    pub(crate) fn refresh_steam_installations(&mut self) {
+        self.steam_settings
+            .send_replace(self.settings.steam.clone());
+        self.steam_installations = steam_installations(&self.settings.steam)
+            .into_iter()
+            .map(|installation| {
+                let users = installation.users_with_profiles().unwrap_or_default();
+                (installation, users)
+            })
+            .collect();
+        self.sync_targets = crate::steam::sync_targets(&self.settings.steam).unwrap_or_default();
+        #[cfg(target_family = "unix")]
+        {
+            self.compat_tools = crate::steam::get_compat_tools(&self.sync_targets);
+        }
     }
 
     fn render_import_button(&mut self, ui: &mut egui::Ui) {
         let (status_string, syncing, is_error) = match &*self.status_reciever.borrow() {
             SyncProgress::NotStarted => ("".to_string(), false, false),
+            SyncProgress::StoppingSteam(progress) => {
+                let status = match progress {
+                    SteamStopProgress::AskingToExit => "Asking Steam to exit".to_string(),
+                    SteamStopProgress::Waiting { seconds_left } => {
+                        format!("Waiting for Steam to exit ({seconds_left}s)")
+                    }
+                    SteamStopProgress::Forcing => "Stopping Steam".to_string(),
+                    SteamStopProgress::Stopped => "Steam is stopped".to_string(),
+                };
+                (status, true, false)
+            }
             SyncProgress::Starting => ("Starting Import".to_string(), true, false),
             SyncProgress::FoundGames { games_found } => {
                 (format!("Found {games_found} games to import"), true, false)
@@ -99,6 +226,12 @@ impl MyEguiApp {
                 (format!("Downloading {to_download} images"), true, false)
             }
             SyncProgress::Done => ("Done importing games".to_string(), false, false),
+            #[cfg(target_family = "unix")]
+            SyncProgress::DoneWithLinksReport { report } => (
+                format!("Done importing games\nLinks folder: {report}"),
+                false,
+                false,
+            ),
             SyncProgress::Error { message } => {
                 (format!("Error: {}", message), false, true)
             }
@@ -141,12 +274,16 @@ impl MyEguiApp {
 }
 
 fn get_rename_map() -> HashMap<u32, String> {
-    try_get_rename_map().unwrap_or_default()
+    try_read_json(get_renames_file()).unwrap_or_default()
 }
 
-fn try_get_rename_map() -> Result<HashMap<u32, String>, Box<dyn Error>> {
-    let rename_map = get_renames_file();
-    let file_content = std::fs::read_to_string(rename_map)?;
+#[cfg(target_family = "unix")]
+fn get_compat_tool_map() -> HashMap<u32, String> {
+    try_read_json(crate::config::get_compat_tools_file()).unwrap_or_default()
+}
+
+fn try_read_json<T: DeserializeOwned>(path: PathBuf) -> Result<T, Box<dyn Error>> {
+    let file_content = std::fs::read_to_string(path)?;
     let deserialized = serde_json::from_str(&file_content)?;
     Ok(deserialized)
 }
@@ -159,25 +296,50 @@ enum Menues {
     Images,
     Backup,
     Disconnect,
+    Collections,
 }
 
 fn create_games_to_sync(rt: &mut Runtime, platforms: &[Box<dyn GamesPlatform>]) -> GamesToSync {
     let mut to_sync = vec![];
     for platform in platforms {
         if platform.enabled() {
-            let (tx, rx) = watch::channel(FetchStatus::NeedsFetched);
-            to_sync.push((platform.name().to_string(), rx));
-            let platform = platform.clone();
-            rt.spawn_blocking(move || {
-                let _ = tx.send(FetchStatus::Fetching);
-                let games_to_sync = sync::get_platform_shortcuts(platform);
-                let _ = tx.send(FetchStatus::Fetched(games_to_sync));
+            to_sync.push(PlatformGames {
+                name: platform.name().to_string(),
+                code_name: platform.code_name().to_string(),
+                collection_name: platform.collection_name().to_string(),
+                status: fetch_platform_games(rt, platform.clone(), true),
             });
         }
     }
     to_sync
 }
 
+pub(crate) fn fetch_platform_games(
+    rt: &Runtime,
+    platform: Box<dyn GamesPlatform>,
+    use_cache: bool,
+) -> Receiver<FetchStatus<eyre::Result<Vec<ShortcutToImport>>>> {
+    let (tx, rx) = watch::channel(FetchStatus::NeedsFetched);
+    let tx = Arc::new(tx);
+    let cancel = CancelToken::default();
+    {
+        //The commands of the platform are stopped when nobody waits for the games anymore,
+        //fx when the platform is scanned again
+        let tx = tx.clone();
+        let cancel = cancel.clone();
+        rt.spawn(async move {
+            tx.closed().await;
+            cancel.cancel();
+        });
+    }
+    rt.spawn_blocking(move || {
+        let _ = tx.send(FetchStatus::Fetching);
+        let games_to_sync = cancel.run_with(|| sync::get_platform_shortcuts(platform, use_cache));
+        let _ = tx.send(FetchStatus::Fetched(games_to_sync));
+    });
+    rx
+}
+
 impl App for MyEguiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
         ctx.set_pixels_per_point(1.0);
@@ -217,6 +379,15 @@ impl App for MyEguiApp {
                     || ui
                         .selectable_value(&mut self.selected_menu, Menues::Disconnect, "Disconnect")
                         .changed();
+
+                changed = changed
+                    || ui
+                        .selectable_value(
+                            &mut self.selected_menu,
+                            Menues::Collections,
+                            "Collections",
+                        )
+                        .changed();
                 if self.selected_menu == Menues::Import {
                     ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
                         self.render_import_button(ui);
@@ -224,6 +395,7 @@ impl App for MyEguiApp {
                 }
                 if changed {
                     self.backup_state.available_backups = None;
+                    self.collections_state.collections = None;
                 }
                 if changed
                     && menu_before == Menues::Settings
@@ -251,6 +423,9 @@ impl App for MyEguiApp {
                 Menues::Disconnect => {
                     self.render_disconnect(ui);
                 }
+                Menues::Collections => {
+                    self.render_collections(ui);
+                }
             };
         });
 
@@ -314,6 +489,10 @@ fn setup(ctx: &egui::Context) {
 }
 pub fn run_sync() -> eyre::Result<()> {
     let mut app = MyEguiApp::new()?;
+    let pending = apply_pending_collections(&app.settings.steam);
+    if pending > 0 {
+        println!("Collections for {pending} Steam user(s) still wait for Steam to exit");
+    }
     while !all_ready(&app.games_to_sync) {
         println!("Finding games, trying again in 500ms");
         std::thread::sleep(Duration::from_secs_f32(0.5));
@@ -322,7 +501,8 @@ pub fn run_sync() -> eyre::Result<()> {
 }
 
 pub fn run_ui(args: Vec<String>) -> eyre::Result<()> {
-    let app = MyEguiApp::new()?;
+    let mut app = MyEguiApp::new()?;
+    app.watch_pending_collections();
     let no_v_sync = args.contains(&"--no-vsync".to_string());
     let fullscreen = is_fullscreen(&args);
     let logo = get_logo_icon();